@@ -1,5 +1,5 @@
 use divan::Bencher;
-use frivail::{frivail::FriVeilDefault, poly::Utils};
+use frivail::{frivail::FriVeilDefault, poly::Utils, B128};
 #[cfg(feature = "kzg")]
 use kate::{
     couscous::multiproof_params,
@@ -18,6 +18,7 @@ const DATA_8_MB: usize = 8 * 1024 * 1024;
 const DATA_14_MB: usize = 14 * 1024 * 1024;
 const DATA_16_MB: usize = 16 * 1024 * 1024;
 const DATA_32_MIB: usize = 32 * 1024 * 1024;
+const DATA_64_MB: usize = 64 * 1024 * 1024;
 
 // A simple opinionated function to build KZG commitments over the data
 #[cfg(feature = "kzg")]
@@ -140,6 +141,31 @@ fn fri_commitments_32mib_redundancy_factor_4(bencher: Bencher) {
     });
 }
 
+// Byte to packed MLE conversion benchmarks, one pass against 1 MB chunks
+#[divan::bench(max_time = 10)]
+fn packed_mle_64mb_sequential(bencher: Bencher) {
+    let mut rng = rand::rng();
+    let random_data: Vec<u8> = (0..DATA_64_MB).map(|_| rng.random()).collect();
+
+    bencher.bench_local(|| {
+        let _ = Utils::<B128>::new()
+            .bytes_to_packed_mle_with_chunking(&random_data, usize::MAX)
+            .expect("Data should be convertible to packed MLE values");
+    });
+}
+
+#[divan::bench(max_time = 10)]
+fn packed_mle_64mb_chunked(bencher: Bencher) {
+    let mut rng = rand::rng();
+    let random_data: Vec<u8> = (0..DATA_64_MB).map(|_| rng.random()).collect();
+
+    bencher.bench_local(|| {
+        let _ = Utils::<B128>::new()
+            .bytes_to_packed_mle_with_chunking(&random_data, 1 << 20)
+            .expect("Data should be convertible to packed MLE values");
+    });
+}
+
 // FRI Commitment Benchmarks
 #[divan::bench(max_time = 10)]
 fn fri_commitment_4mb(bencher: Bencher) {
@@ -0,0 +1,256 @@
+//! Append-only Merkle accumulator over commitment roots
+
+use crate::commitment::CommitmentRoot;
+use crate::error::FriVailError;
+use binius_verifier::hash::StdDigest;
+use digest::Digest;
+
+/// Domain separator for accumulator leaves
+const LEAF_TAG: u8 = 0x00;
+/// Domain separator for accumulator inner nodes
+const NODE_TAG: u8 = 0x01;
+
+/// Merkle accumulator of blob commitment roots
+///
+/// Leaves are added one at a time and only the nodes on the new leaf's path
+/// are recomputed. A node without a right sibling is carried up unchanged, so
+/// the tree needs no padding to a power of two.
+#[derive(Debug, Clone, Default)]
+pub struct CommitmentAccumulator {
+    /// Node digests per level, leaves first; the last level holds the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+/// Membership proof for one root in a `CommitmentAccumulator`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccumulatorProof {
+    /// Position the root was added at
+    pub position: usize,
+    /// Number of roots in the accumulator when the proof was made
+    pub num_leaves: usize,
+    /// Sibling digests from the leaf upwards, skipping levels with no sibling
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl CommitmentAccumulator {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of roots added so far
+    pub fn len(&self) -> usize {
+        self.levels.first().map_or(0, Vec::len)
+    }
+
+    /// Whether no roots have been added
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add a commitment root
+    ///
+    /// # Arguments
+    /// * `root` - Commitment root of a blob
+    ///
+    /// # Returns
+    /// Position of the root in the accumulator
+    pub fn add(&mut self, root: [u8; 32]) -> usize {
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        let position = self.levels[0].len();
+        self.levels[0].push(hash_leaf(&root));
+
+        let mut level = 0;
+        let mut index = position;
+        while self.levels[level].len() > 1 {
+            let nodes = &self.levels[level];
+            let parent = if index % 2 == 1 {
+                hash_node(&nodes[index - 1], &nodes[index])
+            } else {
+                nodes[index]
+            };
+
+            if self.levels.len() == level + 1 {
+                self.levels.push(Vec::new());
+            }
+            let parents = &mut self.levels[level + 1];
+            if index / 2 < parents.len() {
+                parents[index / 2] = parent;
+            } else {
+                parents.push(parent);
+            }
+
+            level += 1;
+            index /= 2;
+        }
+        position
+    }
+
+    /// Current accumulator root, all zeros when empty
+    pub fn accumulator_root(&self) -> [u8; 32] {
+        self.levels.last().map_or([0u8; 32], |top| top[0])
+    }
+
+    /// Prove membership of the root at `position`
+    ///
+    /// # Arguments
+    /// * `position` - Position returned by `add`
+    ///
+    /// # Returns
+    /// Proof verifiable against the current `accumulator_root`
+    ///
+    /// # Errors
+    /// When `position` has not been added
+    pub fn prove_included(&self, position: usize) -> Result<AccumulatorProof, FriVailError> {
+        let num_leaves = self.len();
+        if position >= num_leaves {
+            return Err(FriVailError::InvalidParams(format!(
+                "Position {} out of range for accumulator of {} roots",
+                position, num_leaves
+            )));
+        }
+
+        let mut siblings = Vec::new();
+        let mut index = position;
+        for nodes in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = nodes.get(index ^ 1) {
+                siblings.push(*sibling);
+            }
+            index /= 2;
+        }
+
+        Ok(AccumulatorProof {
+            position,
+            num_leaves,
+            siblings,
+        })
+    }
+
+    /// Verify that `root` is a member of the accumulator with `accumulator_root`
+    ///
+    /// # Arguments
+    /// * `accumulator_root` - Root of the accumulator the proof was made against
+    /// * `root` - Commitment root claimed to be a member
+    /// * `proof` - Proof from `prove_included`
+    ///
+    /// # Returns
+    /// Ok(()) if the proof recomputes `accumulator_root`
+    ///
+    /// # Errors
+    /// When the proof is malformed or leads to a different root
+    pub fn verify_included(
+        accumulator_root: [u8; 32],
+        root: [u8; 32],
+        proof: &AccumulatorProof,
+    ) -> Result<(), FriVailError> {
+        if proof.position >= proof.num_leaves {
+            return Err(FriVailError::InvalidParams(format!(
+                "Position {} out of range for accumulator of {} roots",
+                proof.position, proof.num_leaves
+            )));
+        }
+
+        let mut siblings = proof.siblings.iter();
+        let mut node = hash_leaf(&root);
+        let mut index = proof.position;
+        let mut level_len = proof.num_leaves;
+        while level_len > 1 {
+            if index % 2 == 1 {
+                let sibling = siblings
+                    .next()
+                    .ok_or_else(|| FriVailError::Verify("Accumulator proof is too short".into()))?;
+                node = hash_node(sibling, &node);
+            } else if index + 1 < level_len {
+                let sibling = siblings
+                    .next()
+                    .ok_or_else(|| FriVailError::Verify("Accumulator proof is too short".into()))?;
+                node = hash_node(&node, sibling);
+            }
+            index /= 2;
+            level_len = level_len.div_ceil(2);
+        }
+
+        if siblings.next().is_some() {
+            return Err(FriVailError::Verify("Accumulator proof is too long".into()));
+        }
+        if node != accumulator_root {
+            return Err(FriVailError::Verify(format!(
+                "Accumulator proof leads to root {}",
+                CommitmentRoot::from(node)
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Accumulator leaf digest of a commitment root
+fn hash_leaf(root: &[u8; 32]) -> [u8; 32] {
+    let digest = StdDigest::new()
+        .chain_update([LEAF_TAG])
+        .chain_update(root)
+        .finalize();
+    CommitmentRoot::from(digest).into()
+}
+
+/// Accumulator parent digest of two sibling nodes
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let digest = StdDigest::new()
+        .chain_update([NODE_TAG])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize();
+    CommitmentRoot::from(digest).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob_root(i: u8) -> [u8; 32] {
+        [i; 32]
+    }
+
+    #[test]
+    fn test_incremental_membership() {
+        let mut accumulator = CommitmentAccumulator::new();
+        assert!(accumulator.is_empty());
+
+        let mut roots_seen = Vec::new();
+        for i in 0..10 {
+            assert_eq!(accumulator.add(blob_root(i)), i as usize);
+            // Each addition changes the root
+            assert!(!roots_seen.contains(&accumulator.accumulator_root()));
+            roots_seen.push(accumulator.accumulator_root());
+        }
+        assert_eq!(accumulator.len(), 10);
+
+        let accumulator_root = accumulator.accumulator_root();
+        let proof = accumulator
+            .prove_included(2)
+            .expect("Failed to prove membership");
+        CommitmentAccumulator::verify_included(accumulator_root, blob_root(2), &proof)
+            .expect("Membership proof should verify");
+
+        // Every position verifies, including the carried-up last leaf
+        for i in 0..10 {
+            let proof = accumulator
+                .prove_included(i as usize)
+                .expect("Failed to prove membership");
+            assert!(
+                CommitmentAccumulator::verify_included(accumulator_root, blob_root(i), &proof)
+                    .is_ok()
+            );
+        }
+
+        // A different root or an outdated accumulator root is rejected
+        assert!(
+            CommitmentAccumulator::verify_included(accumulator_root, blob_root(3), &proof).is_err()
+        );
+        assert!(
+            CommitmentAccumulator::verify_included(roots_seen[5], blob_root(2), &proof).is_err()
+        );
+        assert!(accumulator.prove_included(10).is_err());
+    }
+}
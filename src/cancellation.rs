@@ -0,0 +1,81 @@
+//! Cooperative cancellation of long-running operations
+
+use crate::error::FriVailError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[cfg(test)]
+use std::sync::atomic::AtomicUsize;
+
+/// Shared flag checked by `*_cancellable` operations at safe points
+///
+/// Clones share the flag, so a service can hand one clone to the worker and
+/// cancel through another when the client disconnects.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    /// Checks that pass before the token cancels itself
+    #[cfg(test)]
+    remaining_checks: Option<Arc<AtomicUsize>>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of every operation holding a clone of this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        #[cfg(test)]
+        if let Some(remaining) = &self.remaining_checks {
+            if remaining
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+                .is_err()
+            {
+                self.cancel();
+            }
+        }
+
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Return `FriVailError::Cancelled` if cancellation was requested
+    pub fn check(&self) -> Result<(), FriVailError> {
+        if self.is_cancelled() {
+            Err(FriVailError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Token that passes `checks` checks and cancels on the next one
+    #[cfg(test)]
+    pub(crate) fn cancelled_after(checks: usize) -> Self {
+        Self {
+            cancelled: Arc::default(),
+            remaining_checks: Some(Arc::new(AtomicUsize::new(checks))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_cancellation() {
+        let token = CancellationToken::new();
+        let worker = token.clone();
+        assert!(worker.check().is_ok());
+
+        token.cancel();
+        assert!(worker.is_cancelled());
+        assert_eq!(worker.check(), Err(FriVailError::Cancelled));
+    }
+}
@@ -0,0 +1,59 @@
+//! Signed availability certificates over sampling results
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+/// Domain separation tag prefixed to every signed certificate message
+const CERTIFICATE_TAG: &[u8] = b"frivail-availability-v1";
+
+/// Attestation that a verifier checked availability of a commitment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailabilityCertificate {
+    /// Commitment root the samples were verified against
+    pub root: [u8; 32],
+    /// Codeword indices whose inclusion proofs verified
+    pub sample_indices: Vec<usize>,
+    /// Issue time in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Ed25519 signature over the certificate contents
+    pub verifier_signature: [u8; 64],
+}
+
+impl AvailabilityCertificate {
+    /// Canonical byte encoding of the signed certificate contents
+    fn signing_message(root: &[u8; 32], sample_indices: &[usize], timestamp: u64) -> Vec<u8> {
+        let mut message = Vec::with_capacity(CERTIFICATE_TAG.len() + 48 + sample_indices.len() * 8);
+        message.extend_from_slice(CERTIFICATE_TAG);
+        message.extend_from_slice(root);
+        message.extend_from_slice(&(sample_indices.len() as u64).to_le_bytes());
+        for &index in sample_indices {
+            message.extend_from_slice(&(index as u64).to_le_bytes());
+        }
+        message.extend_from_slice(&timestamp.to_le_bytes());
+        message
+    }
+
+    /// Sign certificate contents
+    pub(crate) fn sign(
+        root: [u8; 32],
+        sample_indices: Vec<usize>,
+        timestamp: u64,
+        signing_key: &SigningKey,
+    ) -> Self {
+        let message = Self::signing_message(&root, &sample_indices, timestamp);
+        let verifier_signature = signing_key.sign(&message).to_bytes();
+
+        Self {
+            root,
+            sample_indices,
+            timestamp,
+            verifier_signature,
+        }
+    }
+
+    /// Check the signature against the certificate contents
+    pub(crate) fn verify(&self, verifying_key: &VerifyingKey) -> bool {
+        let message = Self::signing_message(&self.root, &self.sample_indices, self.timestamp);
+        let signature = Signature::from_bytes(&self.verifier_signature);
+        verifying_key.verify(&message, &signature).is_ok()
+    }
+}
@@ -0,0 +1,481 @@
+//! Reed-Solomon encoding, decoding and erasure reconstruction over any binary field
+//!
+//! Nothing here touches the PCS, so these functions are generic over
+//! `F: BinaryField` rather than pinned to `B128` like `FriVail`; `FriVail`
+//! calls them with its scalar field.
+
+use crate::error::FriVailError;
+use binius_field::field::FieldOps;
+use binius_field::BinaryField;
+use binius_math::{
+    ntt::{
+        domain_context::{self, GenericPreExpanded},
+        AdditiveNTT, DomainContext, NeighborsLastMultiThread,
+    },
+    BinarySubspace, FieldSliceMut,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::mem::MaybeUninit;
+
+/// Additive NTT over the subspace spanned by the first `log_len` basis vectors
+///
+/// Subspaces of increasing dimension are nested and share the novel polynomial
+/// basis, so coefficients interpolated over a smaller domain evaluate
+/// consistently over a larger one.
+///
+/// # Arguments
+/// * `log_len` - Dimension of the subspace, at most the bit width of `F`
+/// * `log_num_shares` - Logarithm of the number of threads the NTT splits into
+pub fn subspace_ntt<F: BinaryField>(
+    log_len: usize,
+    log_num_shares: usize,
+) -> NeighborsLastMultiThread<GenericPreExpanded<F>> {
+    let subspace = BinarySubspace::<F>::with_dim(log_len);
+    let domain_context = domain_context::GenericPreExpanded::generate_from_subspace(&subspace);
+    NeighborsLastMultiThread::new(domain_context, log_num_shares.min(log_len))
+}
+
+/// Evaluation points of the first `len` codeword positions
+///
+/// These are the elements of the `BinarySubspace` that `subspace_ntt` builds
+/// the encoding NTT over, in index order. Subspaces of increasing dimension
+/// are nested, so a shorter codeword uses a prefix of the same points.
+pub fn codeword_domain<F: BinaryField>(len: usize) -> Vec<F> {
+    let log_len = len.next_power_of_two().ilog2() as usize;
+    BinarySubspace::<F>::with_dim(log_len)
+        .iter()
+        .take(len)
+        .collect()
+}
+
+/// Reed-Solomon encode a batched message with the forward NTT
+///
+/// The message is repeated `2^log_inv` times and transformed, skipping the
+/// `log_inv` earliest and `log_batch_size` latest layers, so the codeword is
+/// in `EncodingLayout::NaturalOrder` and `inverse_rs_decode` recovers the
+/// message from its first `2^(log_dim + log_batch_size)` scalars.
+///
+/// # Arguments
+/// * `log_dim` - Logarithm of the message length per batch
+/// * `log_inv` - Logarithm of inverse rate
+/// * `log_batch_size` - Logarithm of batch size
+/// * `ntt` - NTT over a subspace of dimension at least `log_dim + log_inv`
+/// * `data` - Message, `2^(log_dim + log_batch_size)` scalars
+///
+/// # Returns
+/// The codeword, `2^(log_dim + log_inv + log_batch_size)` scalars
+///
+/// # Errors
+/// `FriVailError::InvalidParams` when `data` has the wrong length
+pub fn rs_encode<F: BinaryField>(
+    log_dim: usize,
+    log_inv: usize,
+    log_batch_size: usize,
+    ntt: &NeighborsLastMultiThread<GenericPreExpanded<F>>,
+    data: &[F],
+) -> Result<Vec<F>, FriVailError> {
+    let expected_data_len = 1 << (log_dim + log_batch_size);
+    if data.len() != expected_data_len {
+        return Err(FriVailError::InvalidParams(format!(
+            "Message has {} scalars, expected {}",
+            data.len(),
+            expected_data_len
+        )));
+    }
+
+    let _scope = tracing::trace_span!(
+        "Reed-Solomon encode",
+        log_dim = log_dim,
+        log_batch_size = log_batch_size,
+    )
+    .entered();
+
+    let log_d = log_dim + log_inv + log_batch_size;
+    let mut codeword = Vec::with_capacity(1 << log_d);
+    for _ in 0..1 << log_inv {
+        codeword.extend_from_slice(data);
+    }
+    ntt.forward_transform(
+        FieldSliceMut::from_slice(log_d, codeword.as_mut_slice()),
+        log_inv,
+        log_batch_size,
+    );
+    Ok(codeword)
+}
+
+/// Invert Reed-Solomon encoding of a batched codeword with the inverse NTT
+///
+/// Runs the inverse additive NTT butterflies, skipping the `log_inv` earliest
+/// and `log_batch_size` latest layers like the encoder does. Needs no `FriVail`
+/// instance; `FriVailSampling::decode_batch` delegates here.
+///
+/// # Arguments
+/// * `log_len` - Logarithm of the codeword length
+/// * `log_inv` - Logarithm of inverse rate
+/// * `log_batch_size` - Logarithm of batch size
+/// * `ntt` - Number Theoretic Transform instance used for encoding
+/// * `data` - Codeword values, `2^(log_len + log_batch_size)` scalars
+/// * `output` - Output buffer of the same length, fully initialized on success
+///
+/// # Returns
+/// Ok(()) if decoding succeeds
+///
+/// # Errors
+/// When `data` or `output` has the wrong length
+pub fn inverse_rs_decode<F: BinaryField>(
+    log_len: usize,
+    log_inv: usize,
+    log_batch_size: usize,
+    ntt: &NeighborsLastMultiThread<GenericPreExpanded<F>>,
+    data: &[F],
+    output: &mut [MaybeUninit<F>],
+) -> Result<(), FriVailError> {
+    let log_d = log_len + log_batch_size;
+    let expected_data_len = 1 << log_d;
+    if data.len() != expected_data_len {
+        return Err(FriVailError::Decode {
+            expected: expected_data_len,
+            got: data.len(),
+        });
+    }
+    if output.len() != expected_data_len {
+        return Err(FriVailError::Decode {
+            expected: expected_data_len,
+            got: output.len(),
+        });
+    }
+
+    let _scope = tracing::trace_span!(
+        "Reed-Solomon decode",
+        log_len = log_len,
+        log_batch_size = log_batch_size,
+    )
+    .entered();
+
+    for (out, &value) in output.iter_mut().zip(data) {
+        out.write(value);
+    }
+    let code = unsafe { uninit::out_ref::Out::<[F]>::from(output).assume_init() };
+
+    // TODO: create an optimised version PR to binius 64 for inverse_ntt
+    for layer in (log_inv..(log_d - log_batch_size)).rev() {
+        // Blocks within a layer touch disjoint halves, so they run independently
+        #[cfg(feature = "parallel")]
+        let blocks = code.par_chunks_mut(1 << (log_d - layer));
+        #[cfg(not(feature = "parallel"))]
+        let blocks = code.chunks_mut(1 << (log_d - layer));
+
+        blocks.enumerate().for_each(|(block, values)| {
+            let twiddle = ntt.domain_context().twiddle(layer, block);
+            let (lower, upper) = values.split_at_mut(values.len() / 2);
+            for (u, v) in lower.iter_mut().zip(upper) {
+                // perform butterfly
+                *v += *u;
+                *u += *v * twiddle;
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Collect the `(x, y)` points of a codeword that are not erased
+///
+/// # Arguments
+/// * `codeword` - Codeword values
+/// * `domain` - Evaluation point of each position, from `codeword_domain`
+/// * `erased` - Indices of erased positions
+pub(crate) fn known_points<F: BinaryField>(
+    codeword: &[F],
+    domain: &[F],
+    erased: &[usize],
+) -> Result<Vec<(F, F)>, FriVailError> {
+    let erased: HashSet<usize> = erased.iter().copied().collect();
+    let known: Vec<(F, F)> = (0..codeword.len())
+        .filter(|i| !erased.contains(i))
+        .map(|i| (domain[i], codeword[i]))
+        .collect();
+
+    if known.is_empty() {
+        return Err(FriVailError::Reconstruction {
+            known_points: known.len(),
+        });
+    }
+    Ok(known)
+}
+
+/// Inverse Lagrange denominators `1 / prod_{m != j} (x_j - x_m)` of the known points
+///
+/// They depend only on the known x-coordinates, so a reconstruction computes
+/// them once in `O(k^2)` and shares them across every erased position.
+pub(crate) fn lagrange_inverse_denominators<F: BinaryField>(known: &[(F, F)]) -> Vec<F> {
+    #[cfg(feature = "parallel")]
+    let indices = (0..known.len()).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let indices = 0..known.len();
+
+    indices
+        .map(|j| {
+            let (x_j, _) = known[j];
+            known
+                .iter()
+                .enumerate()
+                .filter(|&(m, _)| m != j)
+                .fold(F::ONE, |acc, (_, &(x_m, _))| acc * (x_j - x_m))
+                .invert()
+                .expect("Known points have distinct x-coordinates")
+        })
+        .collect()
+}
+
+/// Compute Lagrange interpolation at a specific point in `O(k)`
+///
+/// Prefix and suffix products of `x_e - x_m` give every numerator
+/// `prod_{m != j} (x_e - x_m)` without division.
+pub(crate) fn interpolate_with_denominators<F: BinaryField>(
+    x_e: F,
+    known: &[(F, F)],
+    inverse_denominators: &[F],
+) -> F {
+    let mut suffix = vec![F::ONE; known.len() + 1];
+    for m in (0..known.len()).rev() {
+        suffix[m] = suffix[m + 1] * (x_e - known[m].0);
+    }
+
+    let mut prefix = F::ONE;
+    let mut value = F::zero();
+    for (j, (&(x_j, y_j), &inverse)) in known.iter().zip(inverse_denominators).enumerate() {
+        value += y_j * inverse * prefix * suffix[j + 1];
+        prefix *= x_e - x_j;
+    }
+    value
+}
+
+/// Evaluate novel-basis coefficients over the NTT domain in place
+pub(crate) fn ntt_forward<F: BinaryField>(
+    ntt: &NeighborsLastMultiThread<GenericPreExpanded<F>>,
+    log_len: usize,
+    values: &mut [F],
+) {
+    ntt.forward_transform(FieldSliceMut::from_slice(log_len, values), 0, 0);
+}
+
+/// Interpolate novel-basis coefficients from evaluations over the NTT domain in place
+pub(crate) fn ntt_inverse<F: BinaryField>(
+    ntt: &NeighborsLastMultiThread<GenericPreExpanded<F>>,
+    log_len: usize,
+    values: &mut [F],
+) {
+    for layer in (0..log_len).rev() {
+        let block_size_half = 1 << (log_len - layer - 1);
+        for block in 0..1 << layer {
+            let twiddle = ntt.domain_context().twiddle(layer, block);
+            let block_start = block << (log_len - layer);
+            for idx0 in block_start..(block_start + block_size_half) {
+                let idx1 = block_size_half | idx0;
+                // inverse butterfly, as in inverse_rs_decode
+                let mut u = values[idx0];
+                let mut v = values[idx1];
+                v += u;
+                u += v * twiddle;
+                values[idx0] = u;
+                values[idx1] = v;
+            }
+        }
+    }
+}
+
+/// Multiply two novel-basis polynomials with `len` coefficients each
+///
+/// Both factors must have degree at most `len / 2`, so the product fits in
+/// `2 * len` coefficients; `ntts[i]` covers the subspace of dimension `i + 1`.
+fn multiply_novel<F: BinaryField>(
+    a: &[F],
+    b: &[F],
+    ntts: &[NeighborsLastMultiThread<GenericPreExpanded<F>>],
+) -> Vec<F> {
+    let product_len = 2 * a.len();
+    let log_len = product_len.trailing_zeros() as usize;
+    let ntt = &ntts[log_len - 1];
+
+    let mut a_evals = a.to_vec();
+    a_evals.resize(product_len, F::zero());
+    ntt_forward(ntt, log_len, &mut a_evals);
+    let mut b_evals = b.to_vec();
+    b_evals.resize(product_len, F::zero());
+    ntt_forward(ntt, log_len, &mut b_evals);
+
+    for (a_eval, b_eval) in a_evals.iter_mut().zip(&b_evals) {
+        *a_eval = *a_eval * *b_eval;
+    }
+    ntt_inverse(ntt, log_len, &mut a_evals);
+    a_evals
+}
+
+/// Reconstruct the erased positions of a codeword with additive-NTT erasure decoding
+///
+/// With `Z` the polynomial vanishing on the erased points, `P = f * Z` is known
+/// on the whole domain `S` and has degree below `n`, so it is interpolated with
+/// one inverse NTT. On the coset `S + b` of the doubled domain `Z` has no roots,
+/// which gives `f(x + b) = P(x + b) / Z(x + b)`; interpolating that shifted
+/// polynomial over `S` and evaluating it on `S + b` yields `f` on `S`. `Z` comes
+/// from a product tree of NTT multiplications, the `O(n log^2 n)` step.
+///
+/// # Arguments
+/// * `codeword` - Codeword over `codeword_domain`, erased positions are overwritten
+/// * `erased` - Indices of erased elements in the codeword
+/// * `log_num_shares` - Logarithm of the number of threads each NTT splits into
+///
+/// # Returns
+/// Ok(()) if reconstruction succeeds
+///
+/// # Errors
+/// When the codeword length is not a power of two, an index is out of range,
+/// or no known points are available
+pub fn reconstruct_erasures_fft<F: BinaryField>(
+    codeword: &mut [F],
+    erased: &[usize],
+    log_num_shares: usize,
+) -> Result<(), FriVailError> {
+    let n = codeword.len();
+    let mut erased = erased.to_vec();
+    erased.sort_unstable();
+    erased.dedup();
+    if erased.is_empty() {
+        return Ok(());
+    }
+    if !n.is_power_of_two() {
+        return Err(FriVailError::InvalidParams(format!(
+            "Codeword length {} is not a power of two",
+            n
+        )));
+    }
+    if let Some(&index) = erased.iter().find(|&&i| i >= n) {
+        return Err(FriVailError::InvalidParams(format!(
+            "Erased index {} out of range for codeword of length {}",
+            index, n
+        )));
+    }
+    if erased.len() == n {
+        return Err(FriVailError::Reconstruction { known_points: 0 });
+    }
+
+    let log_len = n.trailing_zeros() as usize;
+    let ntts: Vec<_> = (1..=log_len + 1)
+        .map(|dim| subspace_ntt(dim, log_num_shares))
+        .collect();
+    let domain_ntt = &ntts[log_len - 1];
+    let doubled_ntt = &ntts[log_len];
+
+    // Coordinates of the domain points in the novel basis, X_1 evaluated over S
+    let mut coords = vec![F::zero(); n];
+    coords[1] = F::ONE;
+    ntt_forward(domain_ntt, log_len, &mut coords);
+
+    // Product tree of the linear factors X_1 - X_1(x_e), padded with constants
+    let mut nodes: Vec<Vec<F>> = erased.iter().map(|&e| vec![coords[e], F::ONE]).collect();
+    nodes.resize(erased.len().next_power_of_two(), vec![F::ONE, F::zero()]);
+    while nodes.len() > 1 {
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| multiply_novel(&pair[0], &pair[1], &ntts))
+            .collect();
+    }
+    let mut vanishing = nodes.pop().expect("product tree has a root");
+    vanishing.resize(2 * n, F::zero());
+    ntt_forward(doubled_ntt, log_len + 1, &mut vanishing);
+
+    // P = f * Z vanishes on the erased points whatever values they hold
+    let mut product: Vec<F> = codeword
+        .iter()
+        .zip(&vanishing[..n])
+        .map(|(&value, &z)| value * z)
+        .collect();
+    ntt_inverse(domain_ntt, log_len, &mut product);
+    product.resize(2 * n, F::zero());
+    ntt_forward(doubled_ntt, log_len + 1, &mut product);
+
+    // Values of f on the coset, read as the shifted polynomial over S
+    let mut shifted: Vec<F> = (n..2 * n)
+        .map(|i| {
+            let z_inv = vanishing[i]
+                .invert()
+                .expect("Z has no roots outside the codeword domain");
+            product[i] * z_inv
+        })
+        .collect();
+    ntt_inverse(domain_ntt, log_len, &mut shifted);
+    shifted.resize(2 * n, F::zero());
+    ntt_forward(doubled_ntt, log_len + 1, &mut shifted);
+
+    for &missing in &erased {
+        codeword[missing] = shifted[n + missing];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binius_field::{AESTowerField8b, Random};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_round_trip_over_small_field() {
+        type F = AESTowerField8b;
+        let (log_dim, log_inv) = (4, 1);
+        let log_len = log_dim + log_inv;
+        let len = 1 << log_len;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let message: Vec<F> = (0..1 << log_dim)
+            .map(|_| <F as Random>::random(&mut rng))
+            .collect();
+        let ntt = subspace_ntt::<F>(log_len, 0);
+        let codeword = rs_encode(log_dim, log_inv, 0, &ntt, &message).expect("Failed to encode");
+        assert_eq!(codeword.len(), len);
+
+        let mut decoded = Vec::with_capacity(len);
+        inverse_rs_decode(
+            log_len,
+            log_inv,
+            0,
+            &ntt,
+            &codeword,
+            &mut decoded.spare_capacity_mut()[..len],
+        )
+        .expect("Failed to decode codeword");
+        unsafe {
+            // Safety: inverse_rs_decode initialized the whole output on success
+            decoded.set_len(len);
+        }
+        assert_eq!(&decoded[..message.len()], message.as_slice());
+
+        // Erase up to n - k positions and recover them both ways
+        let erased: Vec<usize> = (0..len).step_by(2).collect();
+        let mut corrupted = codeword.clone();
+        for &i in &erased {
+            corrupted[i] = F::zero();
+        }
+        let mut fft = corrupted.clone();
+        reconstruct_erasures_fft(&mut fft, &erased, 0).expect("Failed to reconstruct");
+        assert_eq!(fft, codeword);
+
+        let domain = codeword_domain::<F>(len);
+        let known = known_points(&corrupted, &domain, &erased).expect("Known points remain");
+        let inverse_denominators = lagrange_inverse_denominators(&known);
+        for &i in &erased {
+            assert_eq!(
+                interpolate_with_denominators(domain[i], &known, &inverse_denominators),
+                codeword[i]
+            );
+        }
+
+        assert!(matches!(
+            rs_encode(log_dim, log_inv, 0, &ntt, &message[1..]),
+            Err(FriVailError::InvalidParams(_))
+        ));
+    }
+}
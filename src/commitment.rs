@@ -0,0 +1,306 @@
+//! Commitment root representation shared by all verification entry points
+
+use crate::error::FriVailError;
+use crate::hex;
+#[cfg(feature = "serde")]
+use crate::types::{CommitmentOutput, MerkleProver, B128};
+use binius_prover::fri::CommitOutput;
+#[cfg(feature = "serde")]
+use binius_prover::{
+    hash::parallel_compression::ParallelCompressionAdaptor, merkle_tree::MerkleTreeProver,
+};
+#[cfg(feature = "serde")]
+use binius_verifier::hash::StdCompression;
+use binius_verifier::hash::StdDigest;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Merkle root of a codeword commitment
+///
+/// Roots arrive as raw arrays, byte vectors or hex strings; all of them convert
+/// into this type, which verification methods accept uniformly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommitmentRoot([u8; 32]);
+
+/// Stable identifier of a commitment, usable as a map key
+///
+/// The identifier is the Merkle root, so it is a `CommitmentRoot` under another
+/// name and shares its hex `Display` and `FromStr`.
+pub type CommitmentId = CommitmentRoot;
+
+impl CommitmentRoot {
+    /// Raw root bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for CommitmentRoot {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<CommitmentRoot> for [u8; 32] {
+    fn from(root: CommitmentRoot) -> Self {
+        root.0
+    }
+}
+
+impl TryFrom<&[u8]> for CommitmentRoot {
+    type Error = FriVailError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+            FriVailError::Deserialize(format!(
+                "Commitment root must be 32 bytes, got {}",
+                bytes.len()
+            ))
+        })?;
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<Vec<u8>> for CommitmentRoot {
+    type Error = FriVailError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+impl From<digest::Output<StdDigest>> for CommitmentRoot {
+    fn from(digest: digest::Output<StdDigest>) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&digest);
+        Self(bytes)
+    }
+}
+
+impl From<CommitmentRoot> for digest::Output<StdDigest> {
+    fn from(root: CommitmentRoot) -> Self {
+        digest::Output::<StdDigest>::clone_from_slice(&root.0)
+    }
+}
+
+impl<P, C> From<&CommitOutput<P, digest::Output<StdDigest>, C>> for CommitmentRoot {
+    fn from(output: &CommitOutput<P, digest::Output<StdDigest>, C>) -> Self {
+        Self::from(output.commitment)
+    }
+}
+
+impl FromStr for CommitmentRoot {
+    type Err = FriVailError;
+
+    /// Parse a hex root, with or without a `0x` prefix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(hex::decode(s)?)
+    }
+}
+
+impl fmt::Display for CommitmentRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.0))
+    }
+}
+
+/// Commitment and codeword without the prover's Merkle tree state
+///
+/// The tree is rebuilt from the codeword when converting back, and the rebuilt
+/// root must match `commitment`. Field elements serialize as their 16
+/// little-endian bytes.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializableCommitment {
+    /// Merkle root of the codeword
+    pub commitment: [u8; 32],
+    /// Reed-Solomon codeword
+    #[serde(with = "le_scalars")]
+    pub codeword: Vec<B128>,
+    /// Logarithm of the number of codeword elements per Merkle leaf
+    pub log_batch_size: usize,
+}
+
+#[cfg(feature = "serde")]
+impl SerializableCommitment {
+    /// Capture a commitment output for serialization
+    ///
+    /// # Arguments
+    /// * `output` - Commitment output from `commit`
+    /// * `log_batch_size` - Batch size the output was committed with
+    ///
+    /// # Returns
+    /// Serializable copy of the root and codeword
+    pub fn new(output: &CommitmentOutput<B128>, log_batch_size: usize) -> Self {
+        Self {
+            commitment: CommitmentRoot::from(output.commitment).into(),
+            codeword: output.codeword.clone(),
+            log_batch_size,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<&CommitmentOutput<B128>> for SerializableCommitment {
+    /// Capture an output committed with one codeword element per leaf
+    fn from(output: &CommitmentOutput<B128>) -> Self {
+        Self::new(output, 0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SerializableCommitment> for CommitmentOutput<B128> {
+    type Error = FriVailError;
+
+    /// Rebuild the Merkle tree and check its root against the stored commitment
+    fn try_from(serialized: SerializableCommitment) -> Result<Self, Self::Error> {
+        let merkle_prover =
+            MerkleProver::<B128>::new(ParallelCompressionAdaptor::new(StdCompression::default()));
+        let (commitment, committed) = merkle_prover
+            .commit(&serialized.codeword, 1 << serialized.log_batch_size)
+            .map_err(|e| FriVailError::Merkle(e.to_string()))?;
+
+        let root = CommitmentRoot::from(commitment.root);
+        let expected = CommitmentRoot::from(serialized.commitment);
+        if root != expected {
+            return Err(FriVailError::Commit(format!(
+                "Codeword has root {}, expected {}",
+                root, expected
+            )));
+        }
+
+        Ok(CommitOutput {
+            commitment: commitment.root,
+            committed,
+            codeword: serialized.codeword,
+        })
+    }
+}
+
+/// Serde adapter writing field elements as 16 little-endian bytes
+#[cfg(feature = "serde")]
+mod le_scalars {
+    use crate::types::B128;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(values: &[B128], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|&value| u128::from(value).to_le_bytes()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<B128>, D::Error> {
+        let chunks = Vec::<[u8; 16]>::deserialize(deserializer)?;
+        Ok(chunks
+            .into_iter()
+            .map(|chunk| B128::from(u128::from_le_bytes(chunk)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_bytes() -> [u8; 32] {
+        std::array::from_fn(|i| i as u8 * 7)
+    }
+
+    #[test]
+    fn test_commitment_root_conversions() {
+        let bytes = sample_bytes();
+        let root = CommitmentRoot::from(bytes);
+
+        assert_eq!(CommitmentRoot::try_from(bytes.to_vec()), Ok(root));
+
+        let digest: digest::Output<StdDigest> = root.into();
+        assert_eq!(digest.as_slice(), &bytes);
+        assert_eq!(CommitmentRoot::from(digest), root);
+
+        let hex = root.to_string();
+        assert_eq!(hex.len(), 64);
+        assert_eq!(hex.parse::<CommitmentRoot>(), Ok(root));
+        assert_eq!(format!("0x{}", hex).parse::<CommitmentRoot>(), Ok(root));
+        assert_eq!(<[u8; 32]>::from(root), bytes);
+    }
+
+    #[test]
+    fn test_commitment_root_length_mismatch() {
+        let err = CommitmentRoot::try_from(vec![0u8; 31]).expect_err("31 bytes is too short");
+        assert!(err.to_string().contains("31"));
+        assert!(CommitmentRoot::try_from(vec![0u8; 33]).is_err());
+        assert!("abcd".parse::<CommitmentRoot>().is_err());
+        assert!("zz".repeat(32).parse::<CommitmentRoot>().is_err());
+    }
+
+    #[test]
+    fn test_commitment_id_as_map_key() {
+        use crate::poly::Utils;
+        use crate::testing::{synthetic_blob, DataPattern};
+        use crate::types::{TestFriVail, B128};
+        use std::collections::HashMap;
+
+        let mut blobs = HashMap::new();
+        for seed in 0..3 {
+            let packed_mle_values = Utils::<B128>::new()
+                .bytes_to_packed_mle(&synthetic_blob(1024, DataPattern::Random(seed)))
+                .expect("Failed to create packed MLE");
+            let fri_vail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+            let (fri_params, ntt) = fri_vail
+                .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+                .expect("Failed to initialize FRI context");
+            let commit_output = fri_vail
+                .commit(packed_mle_values.packed_mle, fri_params, &ntt)
+                .expect("Failed to commit");
+            blobs.insert(CommitmentId::from(&commit_output), seed);
+        }
+        assert_eq!(blobs.len(), 3);
+
+        for (id, seed) in &blobs {
+            let parsed: CommitmentId = id.to_string().parse().expect("Failed to parse hex id");
+            assert_eq!(&parsed, id);
+            assert_eq!(blobs.get(&parsed), Some(seed));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serializable_commitment_round_trip() {
+        use crate::poly::Utils;
+        use crate::testing::{synthetic_blob, DataPattern};
+        use crate::types::TestFriVail;
+
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&synthetic_blob(1024, DataPattern::Random(3)))
+            .expect("Failed to create packed MLE");
+        let fri_vail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = fri_vail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = fri_vail
+            .commit(packed_mle_values.packed_mle, fri_params, &ntt)
+            .expect("Failed to commit");
+
+        let serialized = SerializableCommitment::from(&commit_output);
+
+        let json = serde_json::to_string(&serialized).expect("Failed to serialize to JSON");
+        let from_json: SerializableCommitment =
+            serde_json::from_str(&json).expect("Failed to deserialize JSON");
+        assert_eq!(from_json, serialized);
+
+        let bytes = bincode::serialize(&serialized).expect("Failed to serialize to bincode");
+        let from_bincode: SerializableCommitment =
+            bincode::deserialize(&bytes).expect("Failed to deserialize bincode");
+        assert_eq!(from_bincode, serialized);
+
+        let restored = CommitmentOutput::<B128>::try_from(from_bincode)
+            .expect("Failed to restore commitment output");
+        assert_eq!(restored.commitment, commit_output.commitment);
+        assert_eq!(restored.codeword, commit_output.codeword);
+
+        // A tampered codeword no longer matches its commitment
+        let mut tampered = serialized;
+        tampered.codeword[0] += B128::from(1u128);
+        assert!(CommitmentOutput::<B128>::try_from(tampered).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Error types for FRI-Vail
+
+use thiserror::Error;
+
+/// Errors returned by FRI-Vail operations
+///
+/// Variants wrapping a message display it unchanged, so log output matches
+/// the `String` errors these replace.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum FriVailError {
+    /// Requested parameters cannot be satisfied by any configuration
+    #[error("Invalid parameters: {0}")]
+    InvalidParams(String),
+    /// Builder configuration violates a FRI-Vail invariant
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    /// FRI parameters do not reach the required security level
+    #[error("Insufficient security: {achieved:.1} bits achieved, {required:.1} bits required")]
+    InsufficientSecurity { achieved: f64, required: f64 },
+    /// More positions are erased than the code can recover
+    #[error("Cannot reconstruct {erasures} erasures, at most {max} are recoverable")]
+    Unrecoverable { erasures: usize, max: usize },
+    /// Too few known points remain to interpolate the erased positions
+    #[error("No known points available for reconstruction")]
+    Reconstruction { known_points: usize },
+    /// Two known points share an x-coordinate, so interpolation is undefined
+    #[error("Known points {first} and {second} share an x-coordinate")]
+    DuplicatePoint { first: usize, second: usize },
+    /// FRI context would exceed the configured memory limit
+    #[error("FRI context needs an estimated {estimated} bytes, limit is {max} bytes")]
+    ContextTooLarge { estimated: usize, max: usize },
+    /// FRI parameters could not be constructed
+    #[error("{0}")]
+    FriParams(String),
+    /// Commitment generation failed
+    #[error("{0}")]
+    Commit(String),
+    /// Evaluation proof generation failed
+    #[error("{0}")]
+    Prove(String),
+    /// Evaluation proof or query proof verification failed
+    #[error("{0}")]
+    Verify(String),
+    /// Input to Reed-Solomon decoding has the wrong length
+    #[error("Unexpected data length: {expected} {got} ")]
+    Decode { expected: usize, got: usize },
+    /// Byte chunk is longer than one field element
+    #[error("Chunk of {len} bytes exceeds the 16 bytes of a field element")]
+    ChunkTooLong { len: usize },
+    /// Merkle opening generation or verification failed
+    #[error("{0}")]
+    Merkle(String),
+    /// Reading from or writing to a transcript failed
+    #[error("{0}")]
+    Transcript(String),
+    /// Reading input from the filesystem failed
+    #[error("{0}")]
+    Io(String),
+    /// Serialized input could not be decoded
+    #[error("{0}")]
+    Deserialize(String),
+    /// A verification primitive rejected the proof
+    #[error(transparent)]
+    Verification(#[from] VerifyError),
+    /// Operation was stopped through its cancellation token
+    #[error("Operation cancelled")]
+    Cancelled,
+}
+
+/// Reasons a proof fails verification
+///
+/// Variants carry only sizes and indices, so reporting a failure on the verify
+/// path allocates nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum VerifyError {
+    /// Evaluation point has fewer coordinates than the packed variables
+    #[error("Evaluation point has {got} coordinates, expected at least {expected}")]
+    PointTooShort { got: usize, expected: usize },
+    /// Proof header could not be read
+    #[error("Malformed proof header")]
+    MalformedHeader,
+    /// Proof header announces more fold rounds than accepted
+    #[error("Malformed proof header: {rounds} fold rounds exceeds {max}")]
+    TooManyFoldRounds { rounds: usize, max: usize },
+    /// Folding schedule in the proof header is not valid for the code
+    #[error("Invalid folding schedule in proof header")]
+    InvalidFoldSchedule,
+    /// Spartan evaluation proof does not verify
+    #[error("Evaluation proof does not verify")]
+    EvaluationProof,
+    /// Wrong number of Merkle layers for the codeword and fold rounds
+    #[error(
+        "Proof has {got} Merkle layers, expected {expected} for the codeword and each fold round"
+    )]
+    LayerCount { got: usize, expected: usize },
+    /// A Merkle layer does not match its commitment
+    #[error("Merkle layer does not match its commitment")]
+    LayerMismatch,
+    /// Query proof binding could not be read
+    #[error("Malformed query proof")]
+    MalformedQuery,
+    /// Query proof was opened against another commitment
+    #[error("Query proof is not bound to this commitment")]
+    UnboundQuery,
+    /// FRI query proof does not verify
+    #[error("Query proof does not verify")]
+    QueryProof,
+    /// Leaf index is outside the Merkle tree
+    #[error("Leaf index {index} out of range for a tree of {num_leaves} leaves")]
+    LeafIndex { index: usize, num_leaves: usize },
+    /// Merkle inclusion proof does not verify
+    #[error("Inclusion proof does not verify")]
+    InclusionProof,
+}
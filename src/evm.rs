@@ -0,0 +1,187 @@
+//! EVM-compatible proof encoding
+//!
+//! ABI layout produced by [`EvmProof::to_abi_bytes`], every word 32 bytes big-endian:
+//! - `uint256` number of terminal codeword elements `n`
+//! - `ceil(n / 2)` `uint256` words, each packing two field elements as
+//!   `hi = element[2i]`, `lo = element[2i + 1]`; the last `lo` is zero when `n` is odd
+//! - `uint256` number of layers, then per layer a `uint256` digest count
+//!   followed by that many `bytes32` roots
+//! - `uint256` transcript length in bytes, then the transcript right-padded
+//!   with zeros to a multiple of 32 bytes
+
+use crate::error::FriVailError;
+
+/// Size of an EVM word in bytes
+const WORD_BYTES: usize = 32;
+
+/// Proof with fixed-size big-endian fields for an on-chain verifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmProof {
+    /// Serialized evaluation proof transcript
+    pub transcript: Vec<u8>,
+    /// Number of field elements in the terminal codeword
+    pub terminate_codeword_len: usize,
+    /// Terminal codeword elements packed in pairs into `uint256` words
+    pub terminate_codeword: Vec<[u8; 32]>,
+    /// Merkle layer digests as `bytes32` roots
+    pub layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl EvmProof {
+    /// Pack 128-bit field elements two per `uint256` word
+    pub fn pack_elements(elements: &[u128]) -> Vec<[u8; 32]> {
+        elements
+            .chunks(2)
+            .map(|pair| {
+                let mut word = [0u8; WORD_BYTES];
+                word[..16].copy_from_slice(&pair[0].to_be_bytes());
+                if let Some(lo) = pair.get(1) {
+                    word[16..].copy_from_slice(&lo.to_be_bytes());
+                }
+                word
+            })
+            .collect()
+    }
+
+    /// Unpack the terminal codeword into 128-bit field elements
+    ///
+    /// # Errors
+    /// When the packed words do not hold exactly `terminate_codeword_len` elements
+    pub fn unpack_elements(&self) -> Result<Vec<u128>, FriVailError> {
+        if self.terminate_codeword.len() != self.terminate_codeword_len.div_ceil(2) {
+            return Err(FriVailError::Deserialize(format!(
+                "Expected {} packed words for {} elements, got {}",
+                self.terminate_codeword_len.div_ceil(2),
+                self.terminate_codeword_len,
+                self.terminate_codeword.len()
+            )));
+        }
+
+        let mut elements = Vec::with_capacity(self.terminate_codeword_len);
+        for word in &self.terminate_codeword {
+            elements.push(u128::from_be_bytes(word[..16].try_into().unwrap()));
+            elements.push(u128::from_be_bytes(word[16..].try_into().unwrap()));
+        }
+        elements.truncate(self.terminate_codeword_len);
+        Ok(elements)
+    }
+
+    /// Encode the proof using the ABI layout described in the module docs
+    pub fn to_abi_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        out.extend_from_slice(&length_word(self.terminate_codeword_len));
+        for word in &self.terminate_codeword {
+            out.extend_from_slice(word);
+        }
+
+        out.extend_from_slice(&length_word(self.layers.len()));
+        for layer in &self.layers {
+            out.extend_from_slice(&length_word(layer.len()));
+            for digest in layer {
+                out.extend_from_slice(digest);
+            }
+        }
+
+        out.extend_from_slice(&length_word(self.transcript.len()));
+        out.extend_from_slice(&self.transcript);
+        out.resize(out.len().next_multiple_of(WORD_BYTES), 0);
+
+        out
+    }
+
+    /// Decode a proof from the ABI layout described in the module docs
+    ///
+    /// # Errors
+    /// When the input is truncated, misaligned or has trailing data
+    pub fn from_abi_bytes(bytes: &[u8]) -> Result<Self, FriVailError> {
+        if bytes.len() % WORD_BYTES != 0 {
+            return Err(FriVailError::Deserialize(format!(
+                "ABI encoding length {} is not a multiple of {}",
+                bytes.len(),
+                WORD_BYTES
+            )));
+        }
+
+        let mut reader = WordReader { bytes, offset: 0 };
+
+        let terminate_codeword_len = reader.read_length()?;
+        let terminate_codeword = (0..terminate_codeword_len.div_ceil(2))
+            .map(|_| reader.read_word())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let num_layers = reader.read_length()?;
+        let mut layers = Vec::with_capacity(num_layers.min(bytes.len() / WORD_BYTES));
+        for _ in 0..num_layers {
+            let num_digests = reader.read_length()?;
+            let layer = (0..num_digests)
+                .map(|_| reader.read_word())
+                .collect::<Result<Vec<_>, _>>()?;
+            layers.push(layer);
+        }
+
+        let transcript_len = reader.read_length()?;
+        let transcript = reader.read_padded_bytes(transcript_len)?.to_vec();
+
+        if reader.offset != bytes.len() {
+            return Err(FriVailError::Deserialize(format!(
+                "Unexpected {} trailing bytes in ABI encoding",
+                bytes.len() - reader.offset
+            )));
+        }
+
+        Ok(Self {
+            transcript,
+            terminate_codeword_len,
+            terminate_codeword,
+            layers,
+        })
+    }
+}
+
+/// Encode a length as a big-endian `uint256` word
+fn length_word(len: usize) -> [u8; 32] {
+    let mut word = [0u8; WORD_BYTES];
+    word[WORD_BYTES - 8..].copy_from_slice(&(len as u64).to_be_bytes());
+    word
+}
+
+/// Sequential reader over 32-byte ABI words
+struct WordReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> WordReader<'a> {
+    fn read_word(&mut self) -> Result<[u8; 32], FriVailError> {
+        let end = self.offset + WORD_BYTES;
+        let word = self
+            .bytes
+            .get(self.offset..end)
+            .ok_or_else(|| FriVailError::Deserialize("Truncated ABI encoding".into()))?;
+        self.offset = end;
+        Ok(word.try_into().unwrap())
+    }
+
+    fn read_length(&mut self) -> Result<usize, FriVailError> {
+        let word = self.read_word()?;
+        if word[..WORD_BYTES - 8].iter().any(|&b| b != 0) {
+            return Err(FriVailError::Deserialize(
+                "ABI length word out of range".into(),
+            ));
+        }
+        let len = u64::from_be_bytes(word[WORD_BYTES - 8..].try_into().unwrap());
+        usize::try_from(len).map_err(|e| FriVailError::Deserialize(e.to_string()))
+    }
+
+    fn read_padded_bytes(&mut self, len: usize) -> Result<&'a [u8], FriVailError> {
+        let padded_end = len
+            .checked_next_multiple_of(WORD_BYTES)
+            .and_then(|padded| self.offset.checked_add(padded))
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| FriVailError::Deserialize("Truncated ABI encoding".into()))?;
+        let data = &self.bytes[self.offset..self.offset + len];
+        self.offset = padded_end;
+        Ok(data)
+    }
+}
@@ -1,7 +1,22 @@
 //! FRI-Vail: FRI-based Vector Commitment Scheme with Data Availability Sampling
 
+use crate::cancellation::CancellationToken;
+#[cfg(feature = "ed25519")]
+use crate::certificate::AvailabilityCertificate;
+use crate::codec;
+pub use crate::codec::inverse_rs_decode;
+use crate::commitment::CommitmentRoot;
+use crate::error::{FriVailError, VerifyError};
+use crate::evm::EvmProof;
+#[cfg(feature = "std")]
+use crate::poly::Utils;
+use crate::poly::BYTES_PER_ELEMENT;
+#[cfg(feature = "ed25519")]
+use crate::sampling::AvailabilityReport;
+use crate::sampling::SamplingStrategy;
 use crate::traits::{FriVailSampling, FriVailUtils};
 use crate::types::*;
+use crate::verifier::{self, OPEN_CONTEXT_TAG};
 use binius_field::field::FieldOps;
 pub use binius_field::PackedField;
 use binius_field::{Field, PackedExtension, Random};
@@ -14,60 +29,785 @@ use binius_math::{
         domain_context::{self, GenericPreExpanded},
         AdditiveNTT, NeighborsLastMultiThread,
     },
-    BinarySubspace, FieldBuffer, FieldSlice, FieldSliceMut,
-};
-use binius_prover::{
-    hash::parallel_compression::ParallelCompressionAdaptor,
-    merkle_tree::{prover::BinaryMerkleTreeProver, MerkleTreeProver},
+    BinarySubspace, FieldBuffer, FieldSliceMut,
 };
+use binius_prover::merkle_tree::MerkleTreeProver;
 use binius_spartan_prover::pcs::PCSProver;
 use binius_spartan_verifier::pcs::verify as spartan_verify;
-use binius_transcript::{Buf, ProverTranscript, VerifierTranscript};
+use binius_transcript::{fiat_shamir::CanSample, Buf, ProverTranscript, VerifierTranscript};
 pub use binius_verifier::config::B128;
 use binius_verifier::{
     config::{StdChallenger, B1},
     fri::{ConstantArityStrategy, FRIParams},
-    hash::{StdCompression, StdDigest},
+    hash::{PseudoCompressionFunction, StdDigest},
     merkle_tree::MerkleTreeScheme,
 };
 
-use itertools::{izip, Itertools};
-use rand::{rngs::StdRng, SeedableRng};
-use std::{marker::PhantomData, mem::MaybeUninit};
+use digest::Digest;
+use itertools::izip;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+#[cfg(feature = "std")]
+use std::time::Instant;
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    time::Duration,
+};
 use tracing::debug;
 
+#[cfg(feature = "ed25519")]
+use ed25519_dalek::{SigningKey, VerifyingKey};
+
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+/// Domain separation tag for proof-of-retrievability challenge derivation
+const POR_CHALLENGE_TAG: &[u8; 14] = b"frivail-por-v1";
+
+/// Domain separation tag for the multi-point batching challenge
+const MULTI_POINT_TAG: &[u8; 16] = b"frivail-multi-v1";
+
+/// Domain separation tag for the commitment batching challenge and its queries
+const BATCH_TAG: &[u8; 16] = b"frivail-batch-v1";
+
+/// Domain separation tag for evaluation points derived from a randomness beacon
+const BEACON_POINT_TAG: &[u8; 17] = b"frivail-beacon-v1";
+
+/// Erased positions reconstructed between cancellation checks
+const RECONSTRUCTION_BATCH: usize = 16;
+
+/// Items processed between two calls of a progress callback
+pub const PROGRESS_INTERVAL: usize = 64;
+
+/// Bit size of the B128 field, an upper bound on achievable soundness
+const FIELD_BITS: f64 = 128.0;
+
+#[cfg(test)]
+thread_local! {
+    /// Full Spartan verifications run on this thread, to observe early exits
+    static SPARTAN_VERIFY_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    /// Equality polynomials computed on this thread, to observe caching
+    static EQ_COMPUTATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Compute the equality polynomial `eq(point, x)` over the boolean hypercube
+fn compute_eq<P: PackedField>(point: &[P::Scalar]) -> FieldBuffer<P> {
+    #[cfg(test)]
+    EQ_COMPUTATIONS.with(|count| count.set(count.get() + 1));
+
+    eq_ind_partial_eval::<P>(point)
+}
+
+/// Opening of one column of a batched commitment at a codeword position
+pub struct ColumnOpening<F> {
+    /// Position in the codeword of each column
+    pub index: usize,
+    /// Column the opening was requested for
+    pub column: usize,
+    /// Merkle leaf at `index`, holding the value of every column
+    pub leaf: Vec<F>,
+    /// Inclusion proof of the leaf
+    pub proof: VerifierTranscript<StdChallenger>,
+}
+
+/// Storer's answer to one proof-of-retrievability challenge position
+pub struct PorResponse<F> {
+    /// Challenged codeword position
+    pub index: usize,
+    /// Codeword value at `index`
+    pub value: F,
+    /// Inclusion proof of the value
+    pub proof: VerifierTranscript<StdChallenger>,
+}
+
+/// Evaluation proof bundled with the data for the extra query check
+///
+/// Produced by `prove_full` and checked by `verify_proof`; every part is plain
+/// data, so the proof can be stored or sent as is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FriVailProof<F> {
+    /// Transcript of the evaluation proof
+    pub transcript_bytes: Vec<u8>,
+    /// Terminal codeword of the FRI folding
+    pub terminate_codeword: TerminalCodeword<F>,
+    /// Merkle layers of the codeword and each fold round commitment
+    pub layers: Vec<Vec<digest::Output<StdDigest>>>,
+    /// Codeword index of the extra query
+    pub extra_index: usize,
+    /// Transcript of the extra query opening, bound to the commitment
+    pub extra_transcript_bytes: Vec<u8>,
+}
+
+impl<F> FriVailProof<F> {
+    /// Proof carrying only the evaluation proof transcript
+    ///
+    /// Enough for `VerifyMode::ClaimOnly`; the extra query parts are empty.
+    ///
+    /// # Arguments
+    /// * `transcript_bytes` - Transcript bytes from `prove`
+    pub fn claim_only(transcript_bytes: Vec<u8>) -> Self {
+        Self {
+            transcript_bytes,
+            terminate_codeword: Vec::new().into(),
+            layers: Vec::new(),
+            extra_index: 0,
+            extra_transcript_bytes: Vec::new(),
+        }
+    }
+}
+
+/// Evaluation proof for several points of one committed polynomial
+///
+/// Produced by `prove_multi` and checked by `verify_multi`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPointProof<F> {
+    /// Values of the polynomial along the curve through the points, one per
+    /// curve node; the first values are the claims at the points
+    pub evaluations: Vec<F>,
+    /// Transcript of the single evaluation proof at the combined point
+    pub transcript_bytes: Vec<u8>,
+}
+
+/// Evaluation proof for several commitments at one point
+///
+/// Produced by `prove_batch` and checked by `batch_verify_commitments`.
+pub struct BatchProof<F> {
+    /// Transcript of the evaluation proof of the combined polynomial, whose
+    /// header carries the root of the combined codeword
+    pub transcript_bytes: Vec<u8>,
+    /// Openings tying the combined codeword to the batched commitments
+    pub openings: Vec<BatchOpening<F>>,
+}
+
+/// Leaves of every batched commitment and of the combined codeword at one position
+pub struct BatchOpening<F> {
+    /// Merkle leaf index
+    pub index: usize,
+    /// Leaf of each batched commitment, in batch order
+    pub leaves: Vec<Vec<F>>,
+    /// Inclusion proof of each leaf
+    pub proofs: Vec<VerifierTranscript<StdChallenger>>,
+    /// Leaf of the combined codeword
+    pub combined_leaf: Vec<F>,
+    /// Inclusion proof of the combined leaf
+    pub combined_proof: VerifierTranscript<StdChallenger>,
+}
+
+/// Checks `verify_with_mode` runs on a `FriVailProof`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyMode {
+    /// Verify the evaluation proof only; the extra query parts are ignored
+    ClaimOnly,
+    /// Also verify the extra query, which must open codeword position `index`
+    WithExtraQuery { index: usize },
+}
+
+/// Message order of a Reed-Solomon codeword
+///
+/// `encode_batch` bit-reverses the message before its NTT, so codewords from
+/// `commit` are `BitReversed`. Codewords from tools that transform the message
+/// as is are `NaturalOrder`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EncodingLayout {
+    /// The NTT is applied to the message in natural order
+    NaturalOrder,
+    /// The NTT is applied to the bit-reversed message, as `commit` does
+    #[default]
+    BitReversed,
+}
+
+/// Step of `verify`, as recorded by `verify_traced`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyStage {
+    /// Commitment and folding schedule read from the proof header
+    Header,
+    /// Spartan verification of the evaluation claim
+    EvaluationProof,
+    /// Merkle layers checked against the proof's commitments
+    LayerMatch,
+    /// Extra query checked against the layers and terminal codeword
+    Query,
+}
+
+/// Outcome of one step of `verify`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyStep {
+    /// Step that ran
+    pub stage: VerifyStage,
+    /// Whether it passed, with the error it failed with otherwise
+    pub result: Result<(), FriVailError>,
+}
+
+/// Append a step's outcome to `trace`, passing `result` through
+fn record<T>(
+    trace: &mut Option<&mut Vec<VerifyStep>>,
+    stage: VerifyStage,
+    result: Result<T, FriVailError>,
+) -> Result<T, FriVailError> {
+    if let Some(steps) = trace {
+        steps.push(VerifyStep {
+            stage,
+            result: result.as_ref().map(|_| ()).map_err(Clone::clone),
+        });
+    }
+    result
+}
+
+/// Terminal codeword of the FRI folding, as returned by `prove`
+///
+/// Holds the scalars in the order `verify` expects, so a proof's terminal
+/// codeword can be passed on without conversion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalCodeword<F>(Vec<F>);
+
+impl<F> TerminalCodeword<F> {
+    /// Scalars of the terminal codeword
+    pub fn as_scalars(&self) -> &[F] {
+        &self.0
+    }
+
+    /// Number of scalars in the terminal codeword
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the terminal codeword has no scalars
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Check the terminal codeword has the expected length
+    ///
+    /// # Arguments
+    /// * `expected` - Number of scalars the terminal codeword must have
+    ///
+    /// # Returns
+    /// Ok(()) if the length matches
+    ///
+    /// # Errors
+    /// `FriVailError::Decode` with both lengths on mismatch
+    pub fn check_len(&self, expected: usize) -> Result<(), FriVailError> {
+        if self.0.len() != expected {
+            return Err(FriVailError::Decode {
+                expected,
+                got: self.0.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<F: Clone> TerminalCodeword<F> {
+    /// Copy the scalars into a vector
+    pub fn to_vec(&self) -> Vec<F> {
+        self.0.clone()
+    }
+}
+
+impl<P: PackedField> From<FieldBuffer<P>> for TerminalCodeword<P::Scalar> {
+    fn from(buffer: FieldBuffer<P>) -> Self {
+        Self(buffer.iter_scalars().collect())
+    }
+}
+
+impl<F> From<Vec<F>> for TerminalCodeword<F> {
+    fn from(values: Vec<F>) -> Self {
+        Self(values)
+    }
+}
+
+/// Merkle opening of one codeword leaf
+pub struct LeafOpening<F> {
+    /// Position of the leaf in the codeword
+    pub index: usize,
+    /// Values committed in the leaf
+    pub leaf: Vec<F>,
+    /// Inclusion proof from `inclusion_proof`
+    pub proof: VerifierTranscript<StdChallenger>,
+}
+
+/// Claim that a commitment has room for a number of data bytes
+///
+/// A capacity check only: the original data length is not committed, so the
+/// claim shows the commitment can hold `claimed_bytes`, not that the data
+/// committed under it is that long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinSizeClaim {
+    /// Commitment root the claim is about
+    pub root: [u8; 32],
+    /// Claimed number of data bytes
+    pub claimed_bytes: usize,
+    /// Number of variables of the committed MLE
+    pub n_vars: usize,
+}
+
+/// Commitment output together with the length of the committed payload
+///
+/// `bytes_to_packed_mle` zero-pads data to a power of two, so the decoded
+/// values alone cannot tell genuine trailing zeros from padding. Carrying the
+/// payload length with the commitment lets `decode_payload` return exactly the
+/// committed elements.
+pub struct PayloadCommitment<P: PackedField, H: MerkleHash = StdMerkleHash> {
+    /// Commitment and codeword from `commit`
+    pub output: CommitmentOutput<P, H>,
+    /// Length of the committed payload in bytes, `PackedMLE::original_byte_len`
+    pub original_byte_len: usize,
+}
+
+impl<P: PackedField, H: MerkleHash> PayloadCommitment<P, H> {
+    /// Number of field elements holding payload data, before zero padding
+    pub fn num_data_elements(&self) -> usize {
+        self.original_byte_len.div_ceil(BYTES_PER_ELEMENT)
+    }
+}
+
+/// Codeword split into fixed-size segments, each committed under its own sub-root
+///
+/// Appending data only re-encodes the last partially filled segment and any new
+/// segments, so earlier segments and their sub-roots stay unchanged.
+pub struct SegmentedCommitment<P: PackedField, H: MerkleHash = StdMerkleHash> {
+    /// Logarithm of the number of message values per segment
+    segment_log_len: usize,
+    /// Appended message values, the last segment possibly partial
+    values: Vec<P::Scalar>,
+    /// Commitment of each segment, the last one zero-padded when partial
+    segments: Vec<CommitmentOutput<P, H>>,
+}
+
+impl<P: PackedField, H: MerkleHash> SegmentedCommitment<P, H> {
+    /// Create an empty segmented commitment
+    ///
+    /// # Arguments
+    /// * `segment_log_len` - Logarithm of the number of message values per segment
+    pub fn new(segment_log_len: usize) -> Self {
+        Self {
+            segment_log_len,
+            values: Vec::new(),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Number of message values per segment
+    pub fn segment_len(&self) -> usize {
+        1 << self.segment_log_len
+    }
+
+    /// Number of committed segments
+    pub fn num_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Commitment output of a segment, used to open positions within it
+    pub fn segment(&self, segment: usize) -> Option<&CommitmentOutput<P, H>> {
+        self.segments.get(segment)
+    }
+
+    /// Merkle sub-root of a segment
+    pub fn segment_root(&self, segment: usize) -> Option<[u8; 32]> {
+        self.segments.get(segment).map(|output| {
+            let mut root = [0u8; 32];
+            root.copy_from_slice(&output.commitment);
+            root
+        })
+    }
+}
+
+/// Reed-Solomon code parameters of a FRI context
+///
+/// Each Merkle leaf commits `2^log_batch_size` consecutive codeword scalars, so
+/// `codeword_len = num_leaves << log_batch_size`. Sampling and inclusion proofs
+/// address leaves; `FriVail::leaf_for` maps a codeword position to its leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeInfo {
+    /// Logarithm of the message length of one batch column
+    pub log_dim: usize,
+    /// Logarithm of the codeword length of one batch column
+    pub log_len: usize,
+    /// Logarithm of the inverse code rate
+    pub log_inv_rate: usize,
+    /// Logarithm of the number of interleaved batch columns
+    pub log_batch_size: usize,
+    /// Number of Merkle tree leaves, `2^log_len`
+    pub num_leaves: usize,
+    /// Number of codeword scalars, `2^(log_len + log_batch_size)`
+    pub codeword_len: usize,
+}
+
+/// Reed-Solomon code parameters a standalone decoder needs to reconstruct a codeword
+///
+/// Lengths count scalar codeword elements, including the interleaved batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconstructionParams {
+    /// Message length `k`; any `k` known elements determine the codeword
+    pub dim: usize,
+    /// Codeword length `n`
+    pub len: usize,
+    /// Logarithm of the inverse code rate `n / k`
+    pub log_inv_rate: usize,
+    /// Dimension of the binary subspace the codeword is evaluated over
+    pub domain_dim: usize,
+}
+
+impl ReconstructionParams {
+    /// Encoded size in bytes
+    pub const ENCODED_LEN: usize = 32;
+
+    /// Encode as four little-endian `u64` values in field order
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0u8; Self::ENCODED_LEN];
+        for (chunk, value) in
+            bytes
+                .chunks_exact_mut(8)
+                .zip([self.dim, self.len, self.log_inv_rate, self.domain_dim])
+        {
+            chunk.copy_from_slice(&(value as u64).to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decode from the encoding produced by `to_bytes`
+    ///
+    /// # Errors
+    /// When the input has the wrong length or the parameters are inconsistent
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FriVailError> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(FriVailError::Deserialize(format!(
+                "Expected {} bytes of reconstruction parameters, got {}",
+                Self::ENCODED_LEN,
+                bytes.len()
+            )));
+        }
+
+        let mut values = bytes.chunks_exact(8).map(|chunk| {
+            usize::try_from(u64::from_le_bytes(chunk.try_into().unwrap()))
+                .map_err(|e| FriVailError::Deserialize(e.to_string()))
+        });
+        let params = Self {
+            dim: values.next().unwrap()?,
+            len: values.next().unwrap()?,
+            log_inv_rate: values.next().unwrap()?,
+            domain_dim: values.next().unwrap()?,
+        };
+
+        if params.domain_dim >= usize::BITS as usize
+            || params.len != 1 << params.domain_dim
+            || params.log_inv_rate > params.domain_dim
+            || params.dim << params.log_inv_rate != params.len
+        {
+            return Err(FriVailError::Deserialize(format!(
+                "Inconsistent reconstruction parameters {:?}",
+                params
+            )));
+        }
+        Ok(params)
+    }
+}
+
+/// Checkpoint of a resumable reconstruction
+///
+/// Erased positions are reconstructed in order; `completed` counts those already
+/// written back to the codeword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconstructionState {
+    /// Erased codeword positions, in reconstruction order
+    pub erased: Vec<usize>,
+    /// Number of leading `erased` positions already reconstructed
+    pub completed: usize,
+}
+
+impl ReconstructionState {
+    /// Start a reconstruction of the given erased positions
+    pub fn new(erased: Vec<usize>) -> Self {
+        Self {
+            erased,
+            completed: 0,
+        }
+    }
+
+    /// Whether every erased position has been reconstructed
+    pub fn is_done(&self) -> bool {
+        self.completed >= self.erased.len()
+    }
+
+    /// Encode as little-endian `u64` values: `completed`, the erased count, then
+    /// each erased position
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.completed, self.erased.len()]
+            .into_iter()
+            .chain(self.erased.iter().copied())
+            .flat_map(|value| (value as u64).to_le_bytes())
+            .collect()
+    }
+
+    /// Decode from the encoding produced by `to_bytes`
+    ///
+    /// # Errors
+    /// When the input is truncated, has trailing data or `completed` is out of range
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FriVailError> {
+        if bytes.len() % 8 != 0 || bytes.len() < 16 {
+            return Err(FriVailError::Deserialize(format!(
+                "Invalid reconstruction state encoding of {} bytes",
+                bytes.len()
+            )));
+        }
+
+        let values = bytes
+            .chunks_exact(8)
+            .map(|chunk| {
+                usize::try_from(u64::from_le_bytes(chunk.try_into().unwrap()))
+                    .map_err(|e| FriVailError::Deserialize(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (completed, num_erased) = (values[0], values[1]);
+        if values.len() - 2 != num_erased || completed > num_erased {
+            return Err(FriVailError::Deserialize(format!(
+                "Inconsistent reconstruction state: {} of {} completed, {} positions encoded",
+                completed,
+                num_erased,
+                values.len() - 2
+            )));
+        }
+
+        Ok(Self {
+            erased: values[2..].to_vec(),
+            completed,
+        })
+    }
+}
+
+/// Outcome of one bounded reconstruction pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReconstructionProgress {
+    /// Time ran out; resume from the checkpoint
+    Incomplete(ReconstructionState),
+    /// Every erased position has been reconstructed
+    Done,
+}
+
+/// Equality polynomial precomputed for one evaluation point
+///
+/// Reuse across `calculate_evaluation_claim_cached` calls to evaluate many
+/// polynomials at the same point while computing `eq` once.
+pub struct EqCache<P: PackedField> {
+    point: Vec<P::Scalar>,
+    eq: FieldBuffer<P>,
+}
+
+impl<P: PackedField> EqCache<P> {
+    /// Precompute the equality polynomial for a point
+    pub fn for_point(point: &[P::Scalar]) -> Self {
+        Self {
+            point: point.to_vec(),
+            eq: compute_eq::<P>(point),
+        }
+    }
+
+    /// Point the cache was built for
+    pub fn point(&self) -> &[P::Scalar] {
+        &self.point
+    }
+
+    /// Precomputed equality polynomial
+    pub fn eq(&self) -> &FieldBuffer<P> {
+        &self.eq
+    }
+}
+
 /// FRI-Vail polynomial commitment scheme
-pub struct FriVail<'a, P, VCS, NTT>
+///
+/// `H` selects the Merkle tree hash that commitments, openings and proofs are
+/// built and checked with, `StdMerkleHash` unless another is given. `VCS`
+/// must be `H::Scheme`, so the two cannot name different trees.
+///
+/// The scalar field is pinned to `B128` because binius64's PCS prover and
+/// Spartan verifier are; the encoding and reconstruction it delegates to
+/// `codec` are generic over `BinaryField`.
+pub struct FriVail<'a, P, VCS, NTT, H = StdMerkleHash>
 where
     NTT: AdditiveNTT<Field = B128> + Sync,
     P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
     VCS: MerkleTreeScheme<P::Scalar>,
+    H: MerkleHash<Scheme = VCS>,
 {
     _ntt: PhantomData<&'a NTT>,
-    pub merkle_prover:
-        BinaryMerkleTreeProver<P::Scalar, StdDigest, ParallelCompressionAdaptor<StdCompression>>,
+    pub merkle_prover: H::Prover,
     log_inv_rate: usize,
     num_test_queries: usize,
     arity: usize,
     n_vars: usize,
     log_num_shares: usize,
+    max_memory: Option<usize>,
     _vcs: PhantomData<VCS>,
 }
 
-impl<'a, P, VCS, NTT> FriVail<'a, P, VCS, NTT>
+/// Validating builder for `FriVail`
+///
+/// Unset parameters fall back to `log_inv_rate = 1`, `arity = 2` and
+/// `log_num_shares = 0`; `n_vars` and `num_test_queries` must be set.
+pub struct FriVailBuilder<'a, P, VCS, NTT>
+where
+    NTT: AdditiveNTT<Field = B128> + Sync,
+    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
+    VCS: MerkleTreeScheme<P::Scalar>,
+{
+    log_inv_rate: usize,
+    num_test_queries: usize,
+    arity: usize,
+    n_vars: Option<usize>,
+    log_num_shares: usize,
+    data_len: Option<usize>,
+    max_memory: Option<usize>,
+    _marker: PhantomData<(&'a NTT, P, VCS)>,
+}
+
+impl<'a, P, VCS, NTT> Default for FriVailBuilder<'a, P, VCS, NTT>
+where
+    NTT: AdditiveNTT<Field = B128> + Sync,
+    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
+    VCS: MerkleTreeScheme<P::Scalar>,
+{
+    fn default() -> Self {
+        Self {
+            log_inv_rate: 1,
+            num_test_queries: 0,
+            arity: 2,
+            n_vars: None,
+            log_num_shares: 0,
+            data_len: None,
+            max_memory: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, P, VCS, NTT> FriVailBuilder<'a, P, VCS, NTT>
+where
+    NTT: AdditiveNTT<Field = B128> + Sync,
+    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
+    VCS: MerkleTreeScheme<P::Scalar>,
+    StdMerkleHash: MerkleHash<Scheme = VCS>,
+{
+    /// Logarithm of inverse rate for Reed-Solomon encoding
+    pub fn log_inv_rate(mut self, log_inv_rate: usize) -> Self {
+        self.log_inv_rate = log_inv_rate;
+        self
+    }
+
+    /// Number of test queries for the FRI protocol
+    pub fn num_test_queries(mut self, num_test_queries: usize) -> Self {
+        self.num_test_queries = num_test_queries;
+        self
+    }
+
+    /// Arity for FRI folding strategy
+    pub fn arity(mut self, arity: usize) -> Self {
+        self.arity = arity;
+        self
+    }
+
+    /// Number of variables for multilinear extension
+    pub fn n_vars(mut self, n_vars: usize) -> Self {
+        self.n_vars = Some(n_vars);
+        self
+    }
+
+    /// Logarithm of number of shares for Merkle tree
+    pub fn log_num_shares(mut self, log_num_shares: usize) -> Self {
+        self.log_num_shares = log_num_shares;
+        self
+    }
+
+    /// Length in bytes of the data that will be committed
+    ///
+    /// `build` checks `n_vars` against the MLE this data packs into, or
+    /// derives it when `n_vars` is unset.
+    pub fn data_len(mut self, data_len: usize) -> Self {
+        self.data_len = Some(data_len);
+        self
+    }
+
+    /// Maximum estimated FRI context size in bytes
+    pub fn max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = Some(max_memory);
+        self
+    }
+
+    /// Validate the configuration and create the FriVail instance
+    ///
+    /// # Returns
+    /// FriVail instance with the configured parameters
+    ///
+    /// # Errors
+    /// `FriVailError::InvalidConfig` when `arity`, `num_test_queries` or
+    /// `log_inv_rate` is zero, or when `n_vars` is missing or does not match
+    /// the configured data length
+    pub fn build(self) -> Result<FriVail<'a, P, VCS, NTT>, FriVailError> {
+        if self.arity < 1 {
+            return Err(FriVailError::InvalidConfig(
+                "arity must be at least 1".into(),
+            ));
+        }
+        if self.num_test_queries == 0 {
+            return Err(FriVailError::InvalidConfig(
+                "num_test_queries must be positive".into(),
+            ));
+        }
+        if self.log_inv_rate < 1 {
+            return Err(FriVailError::InvalidConfig(
+                "log_inv_rate must be at least 1".into(),
+            ));
+        }
+
+        // Matches the padding applied by `bytes_to_packed_mle`
+        let data_n_vars = self
+            .data_len
+            .map(|len| len.div_ceil(BYTES_PER_ELEMENT).next_power_of_two().ilog2() as usize);
+        let n_vars = match (self.n_vars, data_n_vars) {
+            (Some(n_vars), Some(expected)) if n_vars != expected => {
+                return Err(FriVailError::InvalidConfig(format!(
+                    "n_vars is {} but {} bytes of data pack into {} variables",
+                    n_vars,
+                    self.data_len.unwrap_or_default(),
+                    expected
+                )));
+            }
+            (Some(n_vars), _) | (None, Some(n_vars)) => n_vars,
+            (None, None) => {
+                return Err(FriVailError::InvalidConfig(
+                    "n_vars or data_len must be set".into(),
+                ));
+            }
+        };
+
+        let fri_vail: FriVail<'a, P, VCS, NTT> = FriVail::new(
+            self.log_inv_rate,
+            self.num_test_queries,
+            self.arity,
+            n_vars,
+            self.log_num_shares,
+        );
+        Ok(match self.max_memory {
+            Some(max_memory) => fri_vail.with_max_memory(max_memory),
+            None => fri_vail,
+        })
+    }
+}
+
+impl<'a, P, VCS, NTT, H> FriVail<'a, P, VCS, NTT, H>
 where
     P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
     VCS: MerkleTreeScheme<P::Scalar>,
     NTT: AdditiveNTT<Field = B128> + Sync,
+    H: MerkleHash<Scheme = VCS>,
 {
     /// Create a new FRI-Vail instance
     ///
     /// # Arguments
     /// * `log_inv_rate` - Logarithm of inverse rate for Reed-Solomon encoding
-    /// * `num_test_queries` - Number of test queries for FRI protocol (security parameter)
+    /// * `num_test_queries` - Number of test queries for FRI protocol (security parameter).
+    ///   Small values such as the 3 queries used in unit tests are test-only; check
+    ///   production configurations with `assert_secure`
     /// * `arity` - Arity for FRI folding strategy
     /// * `n_vars` - Number of variables for multilinear extension
     /// * `log_num_shares` - Logarithm of number of shares for Merkle tree
@@ -82,18 +822,80 @@ where
         log_num_shares: usize,
     ) -> Self {
         Self {
-            merkle_prover: BinaryMerkleTreeProver::<P::Scalar, StdDigest, _>::new(
-                ParallelCompressionAdaptor::new(StdCompression::default()),
-            ),
+            merkle_prover: H::merkle_prover(),
             log_inv_rate,
             num_test_queries,
             arity,
             n_vars,
             log_num_shares,
+            max_memory: None,
             _ntt: PhantomData,
             _vcs: PhantomData,
         }
     }
+}
+
+impl<'a, P, VCS, NTT> FriVail<'a, P, VCS, NTT>
+where
+    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
+    VCS: MerkleTreeScheme<P::Scalar>,
+    NTT: AdditiveNTT<Field = B128> + Sync,
+    StdMerkleHash: MerkleHash<Scheme = VCS>,
+{
+    /// Start a validating builder for a FRI-Vail instance
+    ///
+    /// # Returns
+    /// Builder with default parameters, see `FriVailBuilder`
+    pub fn builder() -> FriVailBuilder<'a, P, VCS, NTT> {
+        FriVailBuilder::default()
+    }
+}
+
+impl<'a, P, VCS, NTT, H> FriVail<'a, P, VCS, NTT, H>
+where
+    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
+    VCS: MerkleTreeScheme<P::Scalar>,
+    NTT: AdditiveNTT<Field = B128> + Sync,
+    H: MerkleHash<Scheme = VCS>,
+{
+    /// Merkle tree scheme used to verify commitments and openings
+    ///
+    /// # Returns
+    /// Scheme of the Merkle prover, independent of the prover's concrete type
+    pub fn merkle_scheme(&self) -> &H::Scheme {
+        self.merkle_prover.scheme()
+    }
+
+    /// Limit the memory `initialize_fri_context` may allocate
+    ///
+    /// # Arguments
+    /// * `max_memory` - Maximum estimated FRI context size in bytes
+    ///
+    /// # Returns
+    /// The instance with the limit applied
+    pub fn with_max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = Some(max_memory);
+        self
+    }
+
+    /// Estimate the memory allocated by `initialize_fri_context`
+    ///
+    /// The pre-expanded NTT domain stores one twiddle per butterfly block across
+    /// all layers, close to one field element per codeword position.
+    ///
+    /// # Arguments
+    /// * `packed_buffer_log_len` - Logarithm of packed buffer length
+    ///
+    /// # Returns
+    /// Estimated allocation in bytes, saturating at `usize::MAX`
+    pub fn estimate_fri_context_memory(&self, packed_buffer_log_len: usize) -> usize {
+        let code_log_len = packed_buffer_log_len.saturating_add(self.log_inv_rate);
+        1usize
+            .checked_shl(code_log_len as u32)
+            .filter(|_| code_log_len < usize::BITS as usize)
+            .and_then(|len| len.checked_mul(std::mem::size_of::<P::Scalar>()))
+            .unwrap_or(usize::MAX)
+    }
 
     /// Initialize FRI protocol context and NTT for Reed-Solomon encoding
     ///
@@ -104,7 +906,8 @@ where
     /// Tuple containing FRI parameters and NTT instance
     ///
     /// # Errors
-    /// When FRI parameter initialization fails
+    /// `FriVailError::ContextTooLarge` when the estimated allocation exceeds
+    /// `max_memory`, or when FRI parameter initialization fails
     pub fn initialize_fri_context(
         &self,
         packed_buffer_log_len: usize,
@@ -113,42 +916,252 @@ where
             FRIParams<P::Scalar>,
             NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
         ),
-        String,
+        FriVailError,
     > {
-        // Create subspace and NTT first (needed for with_strategy)
-        let code_log_len = packed_buffer_log_len + self.log_inv_rate;
-        let subspace = BinarySubspace::with_dim(code_log_len);
+        // DAS doesn't need the data to be clubbed into cosets
+        self.initialize_fri_context_batched(packed_buffer_log_len, 0)
+    }
 
-        let domain_context = domain_context::GenericPreExpanded::generate_from_subspace(&subspace);
+    /// Initialize FRI protocol context for a batch of interleaved columns
+    ///
+    /// The message holds `2^log_batch_size` columns interleaved element by element,
+    /// and each Merkle leaf commits one position of every column.
+    ///
+    /// # Arguments
+    /// * `packed_buffer_log_len` - Logarithm of packed buffer length, including columns
+    /// * `log_batch_size` - Logarithm of the number of columns
+    ///
+    /// # Returns
+    /// Tuple containing FRI parameters and NTT instance
+    ///
+    /// # Errors
+    /// `FriVailError::ContextTooLarge` when the estimated allocation exceeds
+    /// `max_memory`, or when FRI parameter initialization fails
+    pub fn initialize_fri_context_batched(
+        &self,
+        packed_buffer_log_len: usize,
+        log_batch_size: usize,
+    ) -> Result<
+        (
+            FRIParams<P::Scalar>,
+            NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        ),
+        FriVailError,
+    > {
+        if let Some(max) = self.max_memory {
+            let estimated = self.estimate_fri_context_memory(packed_buffer_log_len);
+            if estimated > max {
+                return Err(FriVailError::ContextTooLarge { estimated, max });
+            }
+        }
+
+        // Create subspace and NTT first (needed for with_strategy)
+        let code_log_len = packed_buffer_log_len + self.log_inv_rate;
+        let subspace = BinarySubspace::with_dim(code_log_len);
+
+        let domain_context = domain_context::GenericPreExpanded::generate_from_subspace(&subspace);
         let ntt = NeighborsLastMultiThread::new(domain_context, self.log_num_shares);
 
         // Use with_strategy to create FRI parameters
         let fri_params = FRIParams::with_strategy(
             &ntt,
-            self.merkle_prover.scheme(),
+            self.merkle_scheme(),
             packed_buffer_log_len,
-            Some(0), // hardcoded to 0, DAS doesn't need the data to be clubbed
-            // into cosets
+            Some(log_batch_size),
             self.log_inv_rate,
             self.num_test_queries,
             &ConstantArityStrategy::new(self.arity),
         )
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| FriVailError::InvalidParams(e.to_string()))?;
 
         Ok((fri_params, ntt))
     }
 
-    /// Generate a random evaluation point for polynomial evaluation
+    /// Compute the parameters needed for a codeword of at least `target_len` positions
+    ///
+    /// # Arguments
+    /// * `target_len` - Minimum number of codeword positions required
+    /// * `log_inv_rate` - Logarithm of inverse rate for Reed-Solomon encoding
+    ///
+    /// # Returns
+    /// Tuple of `n_vars` and the padded data length in bytes
+    ///
+    /// # Errors
+    /// When `target_len` is zero or the codeword length would overflow
+    pub fn params_for_codeword_len(
+        target_len: usize,
+        log_inv_rate: usize,
+    ) -> Result<(usize, usize), FriVailError> {
+        if target_len == 0 {
+            return Err(FriVailError::InvalidParams(
+                "target codeword length must be non-zero".into(),
+            ));
+        }
+
+        let code_log_len = target_len
+            .checked_next_power_of_two()
+            .ok_or_else(|| {
+                FriVailError::InvalidParams(format!(
+                    "target codeword length {} is too large",
+                    target_len
+                ))
+            })?
+            .ilog2() as usize;
+
+        // Targets below the rate expansion collapse to a single-element message
+        let n_vars = code_log_len.saturating_sub(log_inv_rate);
+        let padded_data_len = (1usize << n_vars)
+            .checked_mul(BYTES_PER_ELEMENT)
+            .ok_or_else(|| {
+                FriVailError::InvalidParams(format!("n_vars {} is too large", n_vars))
+            })?;
+
+        Ok((n_vars, padded_data_len))
+    }
+
+    /// Conjectured FRI soundness in bits for a query count and rate
+    fn conjectured_security_bits(num_test_queries: usize, log_inv_rate: usize) -> f64 {
+        ((num_test_queries * log_inv_rate) as f64).min(FIELD_BITS)
+    }
+
+    /// Conjectured security level of this instance's configuration
+    ///
+    /// # Returns
+    /// Soundness in bits, `num_test_queries * log_inv_rate` capped at the field size
+    #[must_use]
+    pub fn security_bits(&self) -> f64 {
+        Self::conjectured_security_bits(self.num_test_queries, self.log_inv_rate)
+    }
+
+    /// Smallest query count reaching a security level at this instance's rate
+    ///
+    /// Targets above the field size cannot be reached by any query count; the
+    /// returned count then only reaches the field-size cap.
+    ///
+    /// # Arguments
+    /// * `target` - Required soundness in bits
+    ///
+    /// # Returns
+    /// Minimum `num_test_queries` for `target` bits, at least 1
+    #[must_use]
+    pub fn min_queries_for_bits(&self, target: f64) -> usize {
+        let bits_per_query = self.log_inv_rate.max(1) as f64;
+        ((target.min(FIELD_BITS) / bits_per_query).ceil() as usize).max(1)
+    }
+
+    /// Check that FRI parameters reach a minimum security level
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters to check
+    /// * `min_bits` - Required soundness in bits
+    ///
+    /// # Returns
+    /// Ok(()) if the parameters are secure enough
+    ///
+    /// # Errors
+    /// When the conjectured soundness is below `min_bits`
+    pub fn assert_secure(
+        &self,
+        fri_params: &FRIParams<P::Scalar>,
+        min_bits: f64,
+    ) -> Result<(), FriVailError> {
+        let achieved = Self::conjectured_security_bits(
+            fri_params.n_test_queries(),
+            fri_params.rs_code().log_inv_rate(),
+        );
+        if achieved < min_bits {
+            return Err(FriVailError::InsufficientSecurity {
+                achieved,
+                required: min_bits,
+            });
+        }
+        Ok(())
+    }
+
+    /// Generate a deterministic evaluation point for polynomial evaluation
+    ///
+    /// Always draws from `StdRng` seeded with zeros, so every call returns the same
+    /// point. Use `calculate_evaluation_point_with_rng` when the point must be
+    /// unpredictable to the prover.
     ///
     /// # Returns
-    /// Vector of random field elements representing the evaluation point
+    /// Vector of field elements representing the evaluation point
     ///
     /// # Errors
     /// When random number generation fails
     pub fn calculate_evaluation_point_random(&self) -> FieldResult<P> {
-        let mut rng = StdRng::from_seed([0; 32]);
+        self.calculate_evaluation_point_with_rng(&mut StdRng::from_seed([0; 32]))
+    }
+
+    /// Generate an evaluation point from a caller-supplied random number generator
+    ///
+    /// # Arguments
+    /// * `rng` - Random number generator, e.g. a CSPRNG or one seeded from a transcript
+    ///
+    /// # Returns
+    /// Evaluation point with `n_vars` coordinates
+    ///
+    /// # Errors
+    /// When random number generation fails
+    pub fn calculate_evaluation_point_with_rng<R: Rng>(&self, rng: &mut R) -> FieldResult<P> {
         let evaluation_point: Vec<P::Scalar> = (0..self.n_vars)
-            .map(|_| <B128 as Random>::random(&mut rng))
+            .map(|_| <B128 as Random>::random(&mut *rng))
+            .collect();
+        Ok(evaluation_point)
+    }
+
+    /// Derive the evaluation point from a Fiat-Shamir transcript
+    ///
+    /// Call after the commitment has been written to (prover) or read from
+    /// (verifier) the transcript, so the point is bound to it and both sides
+    /// sample the same `n_vars` challenges.
+    ///
+    /// # Arguments
+    /// * `transcript` - Prover or verifier transcript to sample challenges from
+    ///
+    /// # Returns
+    /// Evaluation point with `n_vars` coordinates
+    ///
+    /// # Errors
+    /// When sampling from the transcript fails
+    pub fn calculate_evaluation_point_from_transcript<T: CanSample<P::Scalar>>(
+        &self,
+        transcript: &mut T,
+    ) -> FieldResult<P> {
+        let evaluation_point: Vec<P::Scalar> =
+            (0..self.n_vars).map(|_| transcript.sample()).collect();
+        Ok(evaluation_point)
+    }
+
+    /// Derive the evaluation point from a public randomness beacon output
+    ///
+    /// Coordinate `i` is the first 16 bytes, little-endian, of
+    /// `SHA-256(tag || beacon_output || i)` with `i` as a little-endian `u64`.
+    ///
+    /// # Arguments
+    /// * `beacon_output` - Published beacon randomness, e.g. a drand signature
+    ///
+    /// # Returns
+    /// Evaluation point with `n_vars` coordinates
+    ///
+    /// # Errors
+    /// When the beacon output is empty
+    pub fn calculate_evaluation_point_from_beacon(&self, beacon_output: &[u8]) -> FieldResult<P> {
+        if beacon_output.is_empty() {
+            return Err(FriVailError::InvalidParams("Beacon output is empty".into()));
+        }
+
+        let evaluation_point = (0..self.n_vars as u64)
+            .map(|i| {
+                let digest = StdDigest::new()
+                    .chain_update(BEACON_POINT_TAG)
+                    .chain_update(beacon_output)
+                    .chain_update(i.to_le_bytes())
+                    .finalize();
+                let mut bytes = [0u8; 16];
+                bytes.copy_from_slice(&digest[..16]);
+                P::Scalar::from(u128::from_le_bytes(bytes))
+            })
             .collect();
         Ok(evaluation_point)
     }
@@ -168,245 +1181,287 @@ where
         &self,
         values: &[P::Scalar],
         evaluation_point: &[P::Scalar],
-    ) -> Result<P::Scalar, String> {
-        // Compute inner product with equality polynomial
-        let evaluation_claim = inner_product::<P::Scalar>(
-            values.to_vec(),
-            eq_ind_partial_eval(evaluation_point)
-                .as_ref()
-                .iter()
-                .copied()
-                .collect_vec(),
-        );
+    ) -> Result<P::Scalar, FriVailError> {
+        self.calculate_evaluation_claim_with_eq(values, &self.precompute_eq(evaluation_point))
+    }
 
-        Ok(evaluation_claim)
+    /// Precompute the equality polynomial for an evaluation point
+    ///
+    /// # Arguments
+    /// * `evaluation_point` - Point at which polynomials will be evaluated
+    ///
+    /// # Returns
+    /// Equality polynomial buffer, reusable across evaluations at the same point
+    pub fn precompute_eq(&self, evaluation_point: &[P::Scalar]) -> FieldBuffer<P> {
+        compute_eq::<P>(evaluation_point)
     }
 
-    /// Generate a polynomial commitment and codeword
+    /// Evaluate the multilinear extension at a point
     ///
     /// # Arguments
-    /// * `packed_mle` - Packed multilinear extension to commit to
-    /// * `fri_params` - FRI protocol parameters
-    /// * `ntt` - Number Theoretic Transform instance
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `point` - Point with one coordinate per variable
     ///
     /// # Returns
-    /// Commitment output containing commitment and codeword
+    /// Value of the multilinear extension at `point`
     ///
     /// # Errors
-    /// When commitment generation fails
-    pub fn commit(
+    /// When the point dimension does not match the number of variables
+    pub fn evaluate(
         &self,
-        packed_mle: FieldBuffer<P>,
-        fri_params: FRIParams<P::Scalar>,
-        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
-    ) -> Result<CommitmentOutput<P>, String> {
-        let pcs = PCSProver::new(ntt, &self.merkle_prover, &fri_params);
-        pcs.commit(packed_mle.to_ref()).map_err(|e| e.to_string())
+        packed_mle: &FieldBuffer<P>,
+        point: &[P::Scalar],
+    ) -> Result<P::Scalar, FriVailError> {
+        if point.len() != packed_mle.log_len() {
+            return Err(FriVailError::InvalidParams(format!(
+                "Point has {} coordinates, MLE has {} variables",
+                point.len(),
+                packed_mle.log_len()
+            )));
+        }
+
+        Ok(inner_product_buffers(
+            packed_mle,
+            &self.precompute_eq(point),
+        ))
     }
 
-    /// Generate an evaluation proof for the committed polynomial
+    /// Evaluate the multilinear extension at several points
+    ///
+    /// The MLE buffer is shared by every evaluation; only the equality
+    /// polynomial is computed per point.
     ///
     /// # Arguments
     /// * `packed_mle` - Packed multilinear extension
-    /// * `fri_params` - FRI protocol parameters
-    /// * `ntt` - Number Theoretic Transform instance
-    /// * `commit_output` - Previous commitment output
-    /// * `evaluation_point` - Point at which to evaluate the polynomial
+    /// * `points` - Points with one coordinate per variable each
     ///
     /// # Returns
-    /// Tuple containing terminal codeword, query prover, and transcript bytes
+    /// Value of the multilinear extension at each point, in order
     ///
     /// # Errors
-    /// When proof generation fails
-    pub fn prove<'b>(
-        &'b self,
-        packed_mle: FieldBuffer<P>,
-        fri_params: &'b FRIParams<P::Scalar>,
-        ntt: &'b NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
-        commit_output: &'b CommitmentOutput<P>,
-        evaluation_point: &[P::Scalar],
-    ) -> ProveResult<'b, P> {
-        let pcs = PCSProver::new(ntt, &self.merkle_prover, fri_params);
-
-        let mut prover_transcript = ProverTranscript::new(StdChallenger::default());
-
-        // Write commitment to transcript
-        prover_transcript.message().write(&commit_output.commitment);
-
-        let eval_point_eq = eq_ind_partial_eval(evaluation_point);
-        let _evaluation_claim = inner_product_buffers(&packed_mle, &eval_point_eq);
-
-        // Use prove_with_openings instead of prove
-        let (terminate_codeword, query_prover) = pcs
-            .prove_with_openings(
-                commit_output.codeword.clone(),
-                &commit_output.committed,
-                packed_mle,
-                evaluation_point,
-                _evaluation_claim,
-                &mut prover_transcript,
-            )
-            .map_err(|e| e.to_string())?;
-
-        // Get transcript bytes
-        let transcript_bytes = prover_transcript.finalize();
-
-        Ok((terminate_codeword, query_prover, transcript_bytes))
-    }
-
-    /// Encode data using Reed-Solomon code with NTT
-    #[allow(dead_code)]
-    pub fn encode_codeword(
+    /// When any point dimension does not match the number of variables
+    pub fn evaluate_batch(
         &self,
-        data: &[P::Scalar],
-        fri_params: FRIParams<P::Scalar>,
-        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
-    ) -> Result<Vec<P::Scalar>, String> {
-        let rs_code = fri_params.rs_code();
-        let len = 1
-            << (rs_code.log_dim() + fri_params.log_batch_size() - P::LOG_WIDTH
-                + rs_code.log_inv_rate());
-
-        let mut encoded = Vec::with_capacity(len);
-
-        let data_log_len = rs_code.log_dim() + fri_params.log_batch_size();
-        let encoded_buffer = rs_code.encode_batch(
-            ntt,
-            FieldSlice::from_slice(data_log_len, data),
-            fri_params.log_batch_size(),
-        );
-        encoded.extend_from_slice(encoded_buffer.as_ref());
+        packed_mle: &FieldBuffer<P>,
+        points: &[Vec<P::Scalar>],
+    ) -> Result<Vec<P::Scalar>, FriVailError> {
+        #[cfg(feature = "parallel")]
+        let points_iter = points.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let points_iter = points.iter();
 
-        Ok(encoded)
+        points_iter
+            .map(|point| self.evaluate(packed_mle, point))
+            .collect()
     }
 
-    /// Compute Lagrange interpolation at a specific point
-    fn interpolate_at_point(
-        x_e: P::Scalar,
-        known: &[(P::Scalar, P::Scalar)],
-        k: usize,
-    ) -> P::Scalar {
-        let mut value = P::Scalar::zero();
-        for j in 0..k {
-            let (x_j, y_j) = known[j];
-            let mut l_j = P::Scalar::ONE;
-            for m in 0..k {
-                if m == j {
-                    continue;
-                }
-                let (x_m, _) = known[m];
-                l_j = l_j * (x_e - x_m) * (x_j - x_m).invert().unwrap();
-            }
-            value = value + y_j * l_j;
-        }
-        value
+    /// Calculate the evaluation claim at the point of an `EqCache`
+    ///
+    /// # Arguments
+    /// * `values` - Polynomial values to evaluate
+    /// * `eq_cache` - Equality polynomial cache for the evaluation point
+    ///
+    /// # Returns
+    /// Evaluation claim (inner product result)
+    ///
+    /// # Errors
+    /// When the cached equality polynomial does not have one entry per value
+    pub fn calculate_evaluation_claim_cached(
+        &self,
+        values: &[P::Scalar],
+        eq_cache: &EqCache<P>,
+    ) -> Result<P::Scalar, FriVailError> {
+        self.calculate_evaluation_claim_with_eq(values, eq_cache.eq())
     }
-}
 
-impl<'a, P, VCS, NTT> FriVailSampling<P, NTT> for FriVail<'a, P, VCS, NTT>
-where
-    NTT: AdditiveNTT<Field = B128> + Sync,
-    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
-    VCS: MerkleTreeScheme<P::Scalar>,
-{
-    /// Decode a Reed-Solomon codeword with error correction for missing points
+    /// Calculate the evaluation claim using a precomputed equality polynomial
     ///
     /// # Arguments
-    /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
-    /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    /// * `values` - Polynomial values to evaluate
+    /// * `eq` - Equality polynomial from `precompute_eq`
     ///
     /// # Returns
-    /// Ok(()) if reconstruction succeeds
+    /// Evaluation claim (inner product result)
     ///
     /// # Errors
-    /// When no known points are available for reconstruction
-    fn reconstruct_codeword_naive(
+    /// When `eq` does not have one entry per value
+    pub fn calculate_evaluation_claim_with_eq(
         &self,
-        corrupted_codeword: &mut [P::Scalar],
-        corrupted_indices: &[usize],
-    ) -> Result<(), String> {
-        let n = corrupted_codeword.len();
-        let domain = (0..corrupted_codeword.len())
-            .map(|i| P::Scalar::from(i as u128))
-            .collect::<Vec<_>>();
-        if corrupted_indices.is_empty() {
-            return Ok(());
-        }
-
-        // Collect known points (x_j, y_j)
-        let known: Vec<(P::Scalar, P::Scalar)> = (0..n)
-            .filter(|i| !corrupted_indices.contains(i))
-            .map(|i| (domain[i], corrupted_codeword[i]))
-            .collect();
-
-        let k = known.len();
-        if k == 0 {
-            return Err("No known points available for reconstruction".into());
-        }
-
-        // For each erased position, interpolate and evaluate
-        #[cfg(feature = "parallel")]
-        {
-            // Parallel version using rayon
-            let reconstructed_values: Vec<(usize, P::Scalar)> = corrupted_indices
-                .par_iter()
-                .map(|&missing| {
-                    debug!("Calculating value for missing index: {}", missing);
-                    let x_e = domain[missing];
-                    let value = Self::interpolate_at_point(x_e, &known, k);
-
-                    debug!(
-                        "Reconstructed value for missing index {}: {:?}",
-                        missing, value
-                    );
-                    (missing, value)
-                })
-                .collect();
-
-            // Apply the reconstructed values to the codeword
-            for (missing, value) in reconstructed_values {
-                corrupted_codeword[missing] = value;
-            }
+        values: &[P::Scalar],
+        eq: &FieldBuffer<P>,
+    ) -> Result<P::Scalar, FriVailError> {
+        if values.len() != eq.len() {
+            return Err(FriVailError::InvalidParams(format!(
+                "Equality polynomial length mismatch: expected {}, got {}",
+                values.len(),
+                eq.len()
+            )));
         }
 
-        #[cfg(not(feature = "parallel"))]
-        {
-            // Sequential version
-            for &missing in corrupted_indices {
-                debug!("Calculating value for missing index: {}", missing);
-                let x_e = domain[missing];
-                let value = Self::interpolate_at_point(x_e, &known, k);
-
-                debug!(
-                    "Reconstructed value for missing index {}: {:?}",
-                    missing, value
-                );
-                corrupted_codeword[missing] = value;
-            }
-        }
+        // Compute inner product with equality polynomial
+        let evaluation_claim =
+            inner_product::<P::Scalar>(values.iter().copied(), eq.iter_scalars());
 
-        Ok(())
+        Ok(evaluation_claim)
     }
 
-    /// Verify an evaluation proof for the committed polynomial
+    /// Verify an evaluation proof using a precomputed equality polynomial
+    ///
+    /// The evaluation claim is derived from `values` and `eq`, so a stale or
+    /// wrong `eq` yields a wrong claim that the Spartan verifier rejects.
     ///
     /// # Arguments
     /// * `verifier_transcript` - Verifier transcript containing the proof
-    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `values` - Polynomial values the claim is computed from
+    /// * `evaluation_point` - Point at which polynomial was evaluated
+    /// * `eq` - Equality polynomial from `precompute_eq`
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Ok(()) if verification succeeds
+    ///
+    /// # Errors
+    /// `FriVailError::InvalidParams` when `eq` has the wrong size, or the
+    /// verification error when the proof does not verify
+    pub fn verify_with_eq(
+        &self,
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+        values: &[P::Scalar],
+        evaluation_point: &[P::Scalar],
+        eq: &FieldBuffer<P>,
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+    ) -> Result<(), FriVailError> {
+        if eq.log_len() != evaluation_point.len() {
+            return Err(FriVailError::InvalidParams(format!(
+                "Equality polynomial has {} variables, evaluation point has {}",
+                eq.log_len(),
+                evaluation_point.len()
+            )));
+        }
+
+        let evaluation_claim = self.calculate_evaluation_claim_with_eq(values, eq)?;
+        self.verify(
+            verifier_transcript,
+            evaluation_claim,
+            evaluation_point,
+            fri_params,
+            ntt,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// Verify a proof from `prove_full`, including its extra query
+    ///
+    /// # Arguments
+    /// * `proof` - Proof to verify
+    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `evaluation_point` - Point at which polynomial was evaluated
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Ok(()) if the evaluation proof and the extra query verify
+    ///
+    /// # Errors
+    /// When either check fails
+    pub fn verify_proof(
+        &self,
+        proof: &FriVailProof<P::Scalar>,
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+    ) -> Result<(), FriVailError> {
+        self.verify_with_mode(
+            proof,
+            evaluation_claim,
+            evaluation_point,
+            fri_params,
+            ntt,
+            VerifyMode::WithExtraQuery {
+                index: proof.extra_index,
+            },
+        )
+    }
+
+    /// Verify an evaluation proof and record the outcome of each step
+    ///
+    /// Runs the same checks as `verify`. Steps are recorded in order until the
+    /// first failure, so the last step of the trace shows where a rejected proof
+    /// diverged.
+    ///
+    /// # Arguments
+    /// * `verifier_transcript` - Verifier transcript containing the proof
+    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `evaluation_point` - Point at which polynomial was evaluated
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `extra_index` - Optional index for extra query verification
+    /// * `terminate_codeword` - Optional terminal codeword from `prove`
+    /// * `layers` - Optional Merkle tree layers for verification
+    /// * `extra_transcript` - Optional extra transcript for query verification
+    ///
+    /// # Returns
+    /// Result of `verify` and the steps that ran
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_traced(
+        &self,
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+        extra_index: Option<usize>,
+        terminate_codeword: Option<&TerminalCodeword<P::Scalar>>,
+        layers: Option<&[Vec<digest::Output<H::Digest>>]>,
+        extra_transcript: Option<&mut VerifierTranscript<StdChallenger>>,
+    ) -> (Result<(), FriVailError>, Vec<VerifyStep>) {
+        let mut steps = Vec::new();
+        let result = self.verify_recorded(
+            verifier_transcript,
+            evaluation_claim,
+            evaluation_point,
+            fri_params,
+            ntt,
+            extra_index,
+            terminate_codeword,
+            layers,
+            extra_transcript,
+            &[],
+            Some(&mut steps),
+        );
+        (result, steps)
+    }
+
+    /// Verify an evaluation proof made by `prove_with_context`
+    ///
+    /// Runs the same checks as `verify` after absorbing `context` into the
+    /// challenger, so a proof verifies only under the context it was made with.
+    ///
+    /// # Arguments
+    /// * `verifier_transcript` - Verifier transcript containing the proof
+    /// * `evaluation_claim` - Claimed evaluation result
     /// * `evaluation_point` - Point at which polynomial was evaluated
     /// * `fri_params` - FRI protocol parameters
     /// * `ntt` - Number Theoretic Transform instance
     /// * `extra_index` - Optional index for extra query verification
-    /// * `terminate_codeword` - Optional terminal codeword for verification
+    /// * `terminate_codeword` - Optional terminal codeword from `prove`
     /// * `layers` - Optional Merkle tree layers for verification
     /// * `extra_transcript` - Optional extra transcript for query verification
+    /// * `context` - Domain separation context the proof was made with
     ///
     /// # Returns
     /// Ok(()) if verification succeeds
     ///
     /// # Errors
-    /// When verification fails due to invalid proof or parameters
-    fn verify(
+    /// When verification fails, including under a different context
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_with_context(
         &self,
         verifier_transcript: &mut VerifierTranscript<StdChallenger>,
         evaluation_claim: P::Scalar,
@@ -414,31 +1469,78 @@ where
         fri_params: &FRIParams<P::Scalar>,
         ntt: &NTT,
         extra_index: Option<usize>,
-        terminate_codeword: Option<&[P::Scalar]>,
-        layers: Option<&[Vec<digest::Output<StdDigest>>]>,
+        terminate_codeword: Option<&TerminalCodeword<P::Scalar>>,
+        layers: Option<&[Vec<digest::Output<H::Digest>>]>,
         extra_transcript: Option<&mut VerifierTranscript<StdChallenger>>,
-    ) -> Result<(), String> {
-        // Extract commitment from transcript
-        let retrieved_codeword_commitment = verifier_transcript
-            .message()
-            .read()
-            .map_err(|e| e.to_string())?;
+        context: &[u8],
+    ) -> Result<(), FriVailError> {
+        self.verify_recorded(
+            verifier_transcript,
+            evaluation_claim,
+            evaluation_point,
+            fri_params,
+            ntt,
+            extra_index,
+            terminate_codeword,
+            layers,
+            extra_transcript,
+            context,
+            None,
+        )
+    }
+
+    /// Body of `verify`, appending each step's outcome to `trace` if given
+    #[allow(clippy::too_many_arguments)]
+    fn verify_recorded(
+        &self,
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+        extra_index: Option<usize>,
+        terminate_codeword: Option<&TerminalCodeword<P::Scalar>>,
+        layers: Option<&[Vec<digest::Output<H::Digest>>]>,
+        extra_transcript: Option<&mut VerifierTranscript<StdChallenger>>,
+        context: &[u8],
+        mut trace: Option<&mut Vec<VerifyStep>>,
+    ) -> Result<(), FriVailError> {
+        if !context.is_empty() {
+            let mut observer = verifier_transcript.observe();
+            observer.write_bytes(&(context.len() as u64).to_le_bytes());
+            observer.write_bytes(context);
+        }
 
-        let merkle_prover_scheme = self.merkle_prover.scheme().clone();
+        // Reject malformed proofs before the expensive Spartan verification
+        let (retrieved_codeword_commitment, proof_params) = record(
+            &mut trace,
+            VerifyStage::Header,
+            self.verify_header(verifier_transcript, evaluation_point, fri_params),
+        )?;
+        let fri_params = &proof_params;
+
+        let merkle_prover_scheme = self.merkle_scheme().clone();
 
         let n_packed_vars = fri_params.rs_code().log_dim() + fri_params.log_batch_size();
         let eval_point = &evaluation_point[..n_packed_vars];
 
+        #[cfg(test)]
+        SPARTAN_VERIFY_CALLS.with(|calls| calls.set(calls.get() + 1));
+
         // Verify and get verifier_with_arena using the verifier_with_arena pattern
-        let verifier_with_arena = spartan_verify(
-            verifier_transcript,
-            evaluation_claim,
-            eval_point,
-            retrieved_codeword_commitment,
-            fri_params,
-            &merkle_prover_scheme,
-        )
-        .map_err(|e| e.to_string())?;
+        let verifier_with_arena = record(
+            &mut trace,
+            VerifyStage::EvaluationProof,
+            spartan_verify(
+                verifier_transcript,
+                evaluation_claim,
+                eval_point,
+                retrieved_codeword_commitment,
+                fri_params,
+                &merkle_prover_scheme,
+            )
+            .map_err(|_| VerifyError::EvaluationProof.into()),
+        )?;
 
         // Get the verifier from arena (demonstrates the verifier_with_arena pattern)
         let verifier = verifier_with_arena.verifier();
@@ -447,615 +1549,8557 @@ where
         if let (Some(idx), Some(codeword), Some(layers), Some(extra_transcript)) =
             (extra_index, terminate_codeword, layers, extra_transcript)
         {
-            // Verify layers match commitments using vcs_optimal_layers_depths_iter
-            for (commitment, layer_depth, layer) in izip!(
-                std::iter::once(verifier.codeword_commitment).chain(verifier.round_commitments),
-                vcs_optimal_layers_depths_iter(verifier.params, verifier.vcs),
-                layers
-            ) {
-                verifier
-                    .vcs
-                    .verify_layer(commitment, layer_depth, layer)
-                    .map_err(|e| e.to_string())?;
-            }
+            let layer_match = || -> Result<(), FriVailError> {
+                // izip! stops at the shortest input, so a missing layer would go unchecked
+                let expected_layers = 1 + verifier.round_commitments.len();
+                if layers.len() != expected_layers {
+                    return Err(VerifyError::LayerCount {
+                        got: layers.len(),
+                        expected: expected_layers,
+                    }
+                    .into());
+                }
+
+                // Verify layers match commitments using vcs_optimal_layers_depths_iter
+                for (commitment, layer_depth, layer) in izip!(
+                    std::iter::once(verifier.codeword_commitment).chain(verifier.round_commitments),
+                    vcs_optimal_layers_depths_iter(verifier.params, verifier.vcs),
+                    layers
+                ) {
+                    verifier
+                        .vcs
+                        .verify_layer(commitment, layer_depth, layer)
+                        .map_err(|_| VerifyError::LayerMismatch)?;
+                }
+                Ok(())
+            };
+            record(&mut trace, VerifyStage::LayerMatch, layer_match())?;
+
+            let mut query = || -> Result<(), FriVailError> {
+                // Check the query proof was opened against this proof's commitment
+                verifier::check_query_binding(extra_transcript, &retrieved_codeword_commitment)?;
 
-            // Create advice reader from extra transcript for query verification
-            let mut advice = extra_transcript.decommitment();
+                // Create advice reader from extra transcript for query verification
+                let mut advice = extra_transcript.decommitment();
 
-            // Verify the extra query proof
-            verifier
-                .verify_query(idx, ntt, codeword, layers, &mut advice)
-                .map_err(|e| e.to_string())?;
+                // Verify the extra query proof
+                verifier
+                    .verify_query(idx, ntt, codeword.as_scalars(), layers, &mut advice)
+                    .map_err(|_| VerifyError::QueryProof)?;
+                Ok(())
+            };
+            record(&mut trace, VerifyStage::Query, query())?;
         }
 
         Ok(())
     }
 
-    /// Generate a Merkle inclusion proof for a specific codeword position
+    /// Verify a proof with the checks selected by `mode`
+    ///
+    /// Both modes fully verify the Spartan evaluation proof.
     ///
     /// # Arguments
-    /// * `committed` - Committed Merkle tree
-    /// * `index` - Index in the codeword to generate proof for
+    /// * `proof` - Proof to verify, from `prove_full` or `FriVailProof::claim_only`
+    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `evaluation_point` - Point at which polynomial was evaluated
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `mode` - Whether to verify the proof's extra query as well
     ///
     /// # Returns
-    /// Verifier transcript containing the inclusion proof
+    /// Ok(()) if every selected check passes
     ///
     /// # Errors
-    /// When proof generation fails
-    fn inclusion_proof(
+    /// When a check fails, or the proof's extra query opens a different index
+    /// than `mode` requires
+    pub fn verify_with_mode(
         &self,
-        committed: &<MerkleProver<P> as MerkleTreeProver<<P as PackedField>::Scalar>>::Committed,
-        index: usize,
-    ) -> TranscriptResult {
-        let mut proof_writer = ProverTranscript::new(StdChallenger::default());
-        self.merkle_prover
-            .prove_opening(committed, 0, index, &mut proof_writer.message())
-            .map_err(|e| e.to_string())?;
-
-        let proof_reader = proof_writer.into_verifier();
-
-        Ok(proof_reader)
+        proof: &FriVailProof<P::Scalar>,
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+        mode: VerifyMode,
+    ) -> Result<(), FriVailError> {
+        match mode {
+            VerifyMode::ClaimOnly => self.verify(
+                &mut self.reconstruct_transcript_from_bytes(proof.transcript_bytes.clone()),
+                evaluation_claim,
+                evaluation_point,
+                fri_params,
+                ntt,
+                None,
+                None,
+                None,
+                None,
+            ),
+            VerifyMode::WithExtraQuery { index } => self.verify_extra_query(
+                index,
+                fri_params,
+                ntt,
+                proof,
+                evaluation_claim,
+                evaluation_point,
+            ),
+        }
     }
 
-    /// Open a commitment at a specific index using FRI query prover
+    /// Verify a proof's extra query at `index` from the bundled proof fields
+    ///
+    /// The Merkle layers are matched against the codeword and fold round
+    /// commitments and the query is checked against the terminal codeword, so
+    /// callers need not extract layers or rebuild transcripts themselves. The
+    /// query's fold challenges come from the evaluation proof, which is verified
+    /// as well.
     ///
     /// # Arguments
-    /// * `index` - Index in the codeword to open
-    /// * `query_prover` - FRI query prover instance
+    /// * `index` - Codeword index the extra query must open
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `proof` - Proof from `prove_full`
+    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `evaluation_point` - Point at which polynomial was evaluated
     ///
     /// # Returns
-    /// Verifier transcript containing the opening proof
+    /// Ok(()) if the evaluation proof and the extra query verify
     ///
     /// # Errors
-    /// When opening fails
-    fn open<'b>(
+    /// When the proof opens a different index, a layer does not match its
+    /// commitment, or either proof fails to verify
+    pub fn verify_extra_query(
         &self,
         index: usize,
-        query_prover: &FRIQueryProverAlias<'b, P>,
-    ) -> TranscriptResult {
-        // Create new transcript for the query proof
-        let mut proof_transcript = ProverTranscript::new(StdChallenger::default());
-        let mut advice = proof_transcript.decommitment();
-
-        // Generate proof for specific index
-        query_prover
-            .prove_query(index, &mut advice)
-            .map_err(|e| e.to_string())?;
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+        proof: &FriVailProof<P::Scalar>,
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+    ) -> Result<(), FriVailError> {
+        if index != proof.extra_index {
+            return Err(FriVailError::Verify(format!(
+                "Proof opens index {}, expected {}",
+                proof.extra_index, index
+            )));
+        }
 
-        // Return verifier transcript
-        Ok(proof_transcript.into_verifier())
+        let mut verifier_transcript =
+            self.reconstruct_transcript_from_bytes(proof.transcript_bytes.clone());
+        let mut extra_transcript =
+            self.reconstruct_transcript_from_bytes(proof.extra_transcript_bytes.clone());
+        self.verify(
+            &mut verifier_transcript,
+            evaluation_claim,
+            evaluation_point,
+            fri_params,
+            ntt,
+            Some(index),
+            Some(&proof.terminate_codeword),
+            Some(&proof.layers),
+            Some(&mut extra_transcript),
+        )
     }
 
-    /// Verify a Merkle inclusion proof for a codeword value
+    /// Verify evaluation claims at several points from a `prove_multi` proof
+    ///
+    /// Checks the proof's curve values start with the claims, recomputes the
+    /// batching challenge and verifies the single evaluation proof at the
+    /// combined point.
     ///
     /// # Arguments
-    /// * `verifier_transcript` - Verifier transcript containing the inclusion proof
-    /// * `data` - Data value to verify inclusion for
-    /// * `index` - Index in the codeword
+    /// * `proof` - Proof from `prove_multi`
+    /// * `evaluation_claims` - Claimed evaluation per point
+    /// * `points` - Evaluation points, in the order they were proven
     /// * `fri_params` - FRI protocol parameters
-    /// * `commitment` - Merkle tree root commitment
+    /// * `ntt` - Number Theoretic Transform instance
     ///
     /// # Returns
-    /// Ok(()) if inclusion proof is valid
+    /// Ok(()) if every claim holds
     ///
     /// # Errors
-    /// When inclusion proof verification fails
-    fn verify_inclusion_proof(
+    /// When the points or claims are malformed, a claim differs from the
+    /// proof, or the evaluation proof fails
+    pub fn verify_multi(
         &self,
-        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
-        data: &[P::Scalar],
-        index: usize,
+        proof: &MultiPointProof<P::Scalar>,
+        evaluation_claims: &[P::Scalar],
+        points: &[Vec<P::Scalar>],
         fri_params: &FRIParams<P::Scalar>,
-        commitment: [u8; 32],
-    ) -> Result<(), String> {
-        let tree_depth = fri_params.rs_code().log_len();
-        self.merkle_prover
-            .scheme()
-            .verify_opening(
-                index,
-                data,
-                0,
-                tree_depth,
-                &[commitment.into()],
-                &mut verifier_transcript.message(),
-            )
-            .map_err(|e| e.to_string())
+        ntt: &NTT,
+    ) -> Result<(), FriVailError> {
+        Self::check_multi_points(points)?;
+        if evaluation_claims.len() != points.len() {
+            return Err(FriVailError::Verify(format!(
+                "Got {} claims for {} points",
+                evaluation_claims.len(),
+                points.len()
+            )));
+        }
+        let num_nodes = Self::multi_point_degree(points) + 1;
+        if proof.evaluations.len() != num_nodes {
+            return Err(FriVailError::Verify(format!(
+                "Proof has {} curve values, expected {}",
+                proof.evaluations.len(),
+                num_nodes
+            )));
+        }
+        if let Some(i) = (0..points.len()).find(|&i| proof.evaluations[i] != evaluation_claims[i]) {
+            return Err(FriVailError::Verify(format!(
+                "Claim {} does not match the proof",
+                i
+            )));
+        }
+
+        let mut verifier_transcript =
+            self.reconstruct_transcript_from_bytes(proof.transcript_bytes.clone());
+        let (root, _) =
+            self.verify_header(&mut verifier_transcript.clone(), &points[0], fri_params)?;
+
+        let challenge = Self::multi_point_challenge(&root, points, &proof.evaluations);
+        let combined_point = Self::curve_point(points, challenge);
+        let combined_claim = inner_product::<P::Scalar>(
+            proof.evaluations.iter().copied(),
+            Self::lagrange_weights(num_nodes, challenge),
+        );
+
+        self.verify(
+            &mut verifier_transcript,
+            combined_claim,
+            &combined_point,
+            fri_params,
+            ntt,
+            None,
+            None,
+            None,
+            None,
+        )
     }
 
-    /// Decode a Reed-Solomon encoded codeword back to original data
+    /// Verify an evaluation proof against an expected commitment root
     ///
     /// # Arguments
-    /// * `codeword` - Encoded codeword to decode
+    /// * `verifier_transcript` - Verifier transcript containing the proof
+    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `evaluation_point` - Point at which polynomial was evaluated
     /// * `fri_params` - FRI protocol parameters
     /// * `ntt` - Number Theoretic Transform instance
+    /// * `root` - Commitment root the proof must be for
     ///
     /// # Returns
-    /// Decoded packed field values
+    /// Ok(()) if the proof is for `root` and verifies
     ///
     /// # Errors
-    /// When decoding fails
-    fn decode_codeword(
+    /// When the proof commits to a different root or verification fails
+    pub fn verify_with_root(
         &self,
-        codeword: &[P::Scalar],
-        fri_params: FRIParams<P::Scalar>,
-        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
-    ) -> FieldResult<P> {
-        let rs_code = fri_params.rs_code();
-        let len = 1 << (rs_code.log_len() + fri_params.log_batch_size() - P::LOG_WIDTH);
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+        root: impl Into<CommitmentRoot>,
+    ) -> Result<(), FriVailError> {
+        let (proof_root, _) = self.verify_header(
+            &mut verifier_transcript.clone(),
+            evaluation_point,
+            fri_params,
+        )?;
+        let root = root.into();
+        if CommitmentRoot::from(proof_root) != root {
+            return Err(FriVailError::Verify(format!(
+                "Proof is not for commitment {}",
+                root
+            )));
+        }
 
-        let mut decoded = Vec::with_capacity(len);
-        self.decode_batch(
-            rs_code.log_len(),
-            rs_code.log_inv_rate(),
-            fri_params.log_batch_size(),
+        self.verify(
+            verifier_transcript,
+            evaluation_claim,
+            evaluation_point,
+            fri_params,
             ntt,
-            codeword.as_ref(),
-            decoded.spare_capacity_mut(),
+            None,
+            None,
+            None,
+            None,
         )
-        .map_err(|e| e.to_string())?;
-
-        unsafe {
-            // Safety: decode_batch guarantees all elements are initialized on success
-            decoded.set_len(len);
-        }
-
-        // Trim to original data size (remove redundancy)
-        let trim_len = 1 << (rs_code.log_dim() + fri_params.log_batch_size() - P::LOG_WIDTH);
-        decoded.resize(trim_len, P::Scalar::zero());
+    }
 
-        // Undo bit-reversal that encode_batch applied internally
-        let data_log_len = rs_code.log_dim() + fri_params.log_batch_size();
-        bit_reverse_packed(FieldSliceMut::from_slice(
-            data_log_len,
-            decoded.as_mut_slice(),
-        ));
-
-        Ok(decoded)
+    /// Run the cheap checks of `verify` without the Spartan verification
+    ///
+    /// Checks the evaluation point covers every packed variable and reads the
+    /// proof header, the codeword commitment followed by the prover's folding
+    /// schedule, so malformed proofs are rejected before any expensive work.
+    ///
+    /// # Arguments
+    /// * `verifier_transcript` - Verifier transcript positioned at the proof start
+    /// * `evaluation_point` - Point at which polynomial was evaluated
+    /// * `fri_params` - FRI protocol parameters; the fold arities are taken from the proof
+    ///
+    /// # Returns
+    /// Codeword commitment and FRI parameters with the prover's folding schedule
+    ///
+    /// # Errors
+    /// When the evaluation point is too short or the header cannot be read
+    pub fn verify_header(
+        &self,
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<(digest::Output<H::Digest>, FRIParams<P::Scalar>), FriVailError> {
+        Ok(verifier::read_proof_header(
+            verifier_transcript,
+            evaluation_point.len(),
+            fri_params,
+        )?)
     }
 
-    /// Extract commitment from verifier transcript
+    /// Replay a proof transcript and return the FRI folding challenges
+    ///
+    /// Runs the same Spartan verification as `verify`, so the challenges are
+    /// only returned for a proof that verifies, one scalar per folded variable
+    /// in round order.
     ///
     /// # Arguments
-    /// * `verifier_transcript` - Verifier transcript to extract commitment from
+    /// * `verifier_transcript` - Verifier transcript containing the proof
+    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `evaluation_point` - Point at which polynomial was evaluated
+    /// * `fri_params` - FRI protocol parameters; the fold arities are taken from the proof
     ///
     /// # Returns
-    /// Commitment bytes
+    /// Folding challenges sampled from the transcript
     ///
     /// # Errors
-    /// When commitment extraction fails
-    #[allow(dead_code)]
-    fn extract_commitment(
+    /// When the proof header is malformed or verification fails
+    pub fn fri_folding_challenges(
         &self,
         verifier_transcript: &mut VerifierTranscript<StdChallenger>,
-    ) -> ByteResult {
-        verifier_transcript
-            .message()
-            .read()
-            .map_err(|e| e.to_string())
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<Vec<P::Scalar>, FriVailError> {
+        let (commitment, proof_params) =
+            self.verify_header(verifier_transcript, evaluation_point, fri_params)?;
+
+        let merkle_prover_scheme = self.merkle_scheme().clone();
+        let n_packed_vars = proof_params.rs_code().log_dim() + proof_params.log_batch_size();
+
+        let verifier_with_arena = spartan_verify(
+            verifier_transcript,
+            evaluation_claim,
+            &evaluation_point[..n_packed_vars],
+            commitment,
+            &proof_params,
+            &merkle_prover_scheme,
+        )
+        .map_err(|_| VerifyError::EvaluationProof)?;
+
+        Ok(verifier_with_arena.verifier().fold_challenges.clone())
     }
 
-    /// Low-level batch decoding using inverse NTT
+    /// Generate a polynomial commitment and codeword
     ///
     /// # Arguments
-    /// * `log_len` - Logarithm of dimension
-    /// * `log_inv` - Logarithm of inverse rate
-    /// * `log_batch_size` - Logarithm of batch size
+    /// * `packed_mle` - Packed multilinear extension to commit to
+    /// * `fri_params` - FRI protocol parameters
     /// * `ntt` - Number Theoretic Transform instance
-    /// * `data` - Input data to decode
-    /// * `output` - Output buffer for decoded data
     ///
     /// # Returns
-    /// Ok(()) if decoding succeeds
+    /// Commitment output containing commitment and codeword
     ///
     /// # Errors
-    /// When decoding fails due to invalid parameters
-    fn decode_batch(
+    /// When commitment generation fails
+    pub fn commit(
         &self,
-        log_len: usize,
-        log_inv: usize,
-        log_batch_size: usize,
+        packed_mle: FieldBuffer<P>,
+        fri_params: FRIParams<P::Scalar>,
         ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
-        data: &[P::Scalar],
-        output: &mut [MaybeUninit<P::Scalar>],
-    ) -> Result<(), String> {
-        let data_log_len = log_len + log_batch_size;
+    ) -> Result<CommitmentOutput<P, H>, FriVailError> {
+        let pcs = PCSProver::new(ntt, &self.merkle_prover, &fri_params);
+        pcs.commit(packed_mle.to_ref())
+            .map_err(|e| FriVailError::Commit(e.to_string()))
+    }
 
-        let expected_data_len = if data_log_len >= P::LOG_WIDTH {
-            1 << (data_log_len - P::LOG_WIDTH)
-        } else {
-            1
+    /// Commit to the contents of a file
+    ///
+    /// The file is streamed through `Utils::stream_to_packed_mle`, so it is never
+    /// held in memory as bytes. A length that is not a multiple of 16 bytes is
+    /// zero-filled and padded exactly as `bytes_to_packed_mle` does.
+    ///
+    /// # Arguments
+    /// * `path` - File holding the blob
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Commitment output and the file length in bytes
+    ///
+    /// # Errors
+    /// `FriVailError::Io` when the file cannot be read, or when commitment fails
+    #[cfg(feature = "std")]
+    pub fn commit_file(
+        &self,
+        path: &std::path::Path,
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<(CommitmentOutput<P, H>, usize), FriVailError> {
+        let io_error = |e: std::io::Error| {
+            FriVailError::Io(format!("Failed to read {}: {}", path.display(), e))
         };
+        let file = std::fs::File::open(path).map_err(io_error)?;
+        let original_byte_len = usize::try_from(file.metadata().map_err(io_error)?.len())
+            .map_err(|_| FriVailError::Io(format!("{} is too large", path.display())))?;
 
-        if data.len() != expected_data_len {
-            return Err(format!(
-                "Unexpected data length: {} {} ",
-                expected_data_len,
-                data.len()
-            ));
-        }
-
-        let _scope = tracing::trace_span!(
-            "Reed-Solomon encode",
-            log_len = log_len,
-            log_batch_size = log_batch_size,
-        )
-        .entered();
-
-        let data_portion_len = data.len();
+        let packed_mle_values = Utils::<B128>::new()
+            .stream_to_packed_mle(std::io::BufReader::new(file), original_byte_len)?;
+        let output = self.commit(
+            FieldBuffer::<P>::from_values(&packed_mle_values.packed_values),
+            fri_params,
+            ntt,
+        )?;
+        Ok((output, original_byte_len))
+    }
 
-        for i in 0..data_portion_len {
-            output[i].write(data[i]);
+    /// Generate a commitment that records the payload length
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension to commit to
+    /// * `original_byte_len` - Payload length in bytes, `PackedMLE::original_byte_len`
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Commitment output paired with the payload length
+    ///
+    /// # Errors
+    /// When the payload does not fit the polynomial or commitment generation fails
+    pub fn commit_payload(
+        &self,
+        packed_mle: FieldBuffer<P>,
+        original_byte_len: usize,
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<PayloadCommitment<P, H>, FriVailError> {
+        let capacity = Self::data_capacity(packed_mle.log_len());
+        if original_byte_len > capacity {
+            return Err(FriVailError::InvalidParams(format!(
+                "Payload of {} bytes exceeds the {} bytes the polynomial holds",
+                original_byte_len, capacity
+            )));
         }
 
-        for i in data_portion_len..output.len() {
-            output[i].write(P::Scalar::zero());
-        }
+        Ok(PayloadCommitment {
+            output: self.commit(packed_mle, fri_params, ntt)?,
+            original_byte_len,
+        })
+    }
 
-        let output_initialized =
-            unsafe { uninit::out_ref::Out::<[P::Scalar]>::from(output).assume_init() };
-        let mut code = FieldSliceMut::from_slice(log_len + log_batch_size, output_initialized);
+    /// Commit to several polynomials with one prover setup
+    ///
+    /// The PCS prover is built once and reused, so each blob pays only for its
+    /// encoding and Merkle tree. Each polynomial gets its own commitment, and its
+    /// codeword positions are opened with the usual indices.
+    ///
+    /// # Arguments
+    /// * `mles` - Packed multilinear extensions, all with `fri_params`'s size
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// One commitment output per polynomial, in input order
+    ///
+    /// # Errors
+    /// When committing any polynomial fails
+    pub fn commit_batch(
+        &self,
+        mles: &[FieldBuffer<P>],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<Vec<CommitmentOutput<P, H>>, FriVailError> {
+        let pcs = PCSProver::new(ntt, &self.merkle_prover, fri_params);
+        mles.iter()
+            .enumerate()
+            .map(|(i, mle)| {
+                pcs.commit(mle.to_ref())
+                    .map_err(|e| FriVailError::Commit(format!("Polynomial {}: {}", i, e)))
+            })
+            .collect()
+    }
 
-        let skip_early = log_inv;
-        let skip_late = log_batch_size;
+    /// Generate a polynomial commitment and codeword unless cancelled
+    ///
+    /// The token is checked before encoding starts; encoding and Merkle
+    /// commitment run as one stage.
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `token` - Cancellation token
+    ///
+    /// # Returns
+    /// Commitment output containing commitment and codeword
+    ///
+    /// # Errors
+    /// `FriVailError::Cancelled` when the token is cancelled, or
+    /// `FriVailError::Commit` when commitment generation fails
+    pub fn commit_cancellable(
+        &self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        token: &CancellationToken,
+    ) -> Result<CommitmentOutput<P, H>, FriVailError> {
+        token.check()?;
+        self.commit(packed_mle, fri_params, ntt)
+    }
 
-        // TODO: create an optimised version PR to binius 64 for inverse_ntt
-        let log_d = code.log_len();
-        use binius_math::ntt::DomainContext;
-        for layer in (skip_early..(log_d - skip_late)).rev() {
-            let num_blocks = 1 << layer;
-            let block_size_half = 1 << (log_d - layer - 1);
-            for block in 0..num_blocks {
-                let twiddle = ntt.domain_context().twiddle(layer, block);
-                let block_start = block << (log_d - layer);
-                for idx0 in block_start..(block_start + block_size_half) {
-                    let idx1 = block_size_half | idx0;
-                    // perform butterfly
-                    let mut u = code.get(idx0);
-                    let mut v = code.get(idx1);
-
-                    v += u;
-                    u += v * twiddle;
-                    code.set(idx0, u);
-                    code.set(idx1, v);
+    /// Compute the commitment root without building the Merkle tree
+    ///
+    /// Leaves are hashed as the codeword is scanned and merged on a stack holding
+    /// at most one digest per tree level, so only the codeword and `O(log n)`
+    /// digests are kept instead of every tree node. The result cannot serve
+    /// openings; use `commit` for that.
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Merkle root equal to the commitment produced by `commit`
+    ///
+    /// # Errors
+    /// When encoding or leaf hashing fails
+    pub fn commit_root_only(
+        &self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<[u8; 32], FriVailError> {
+        let leaf_width = 1 << fri_params.log_batch_size();
+        let values: Vec<P::Scalar> = packed_mle.iter_scalars().collect();
+        let codeword =
+            self.encode_codeword(&values, fri_params, ntt, EncodingLayout::BitReversed)?;
+
+        // (level, digest) pairs with strictly decreasing levels
+        let mut stack: Vec<(usize, digest::Output<H::Digest>)> = Vec::new();
+        for leaf in codeword.chunks(leaf_width) {
+            let mut node = (0, self.leaf_digest(leaf)?);
+
+            while let Some(&(level, left)) = stack.last() {
+                if level != node.0 {
+                    break;
                 }
+                stack.pop();
+                node = (level + 1, Self::compress_nodes(left, node.1));
             }
+            stack.push(node);
         }
 
-        Ok(())
-    }
-}
-
-impl FriVailUtils for FriVailDefault {
-    fn get_transcript_bytes(&self, transcript: &VerifierTranscript<StdChallenger>) -> Vec<u8> {
-        let mut cloned = transcript.clone();
-        let mut message_reader = cloned.message();
-        let buffer = message_reader.buffer();
-        let remaining = buffer.remaining();
-
-        if remaining == 0 {
-            return Vec::new();
+        match stack.as_slice() {
+            [(_, root)] => {
+                let mut root_bytes = [0u8; 32];
+                root_bytes.copy_from_slice(root);
+                Ok(root_bytes)
+            }
+            _ => Err(FriVailError::InvalidParams(format!(
+                "Codeword of {} leaves is not a power of two",
+                codeword.len() / leaf_width
+            ))),
         }
-
-        // Read all remaining bytes
-        let mut bytes = vec![0u8; remaining];
-        buffer.copy_to_slice(&mut bytes);
-        bytes
     }
-    fn reconstruct_transcript_from_bytes(
-        &self,
-        bytes: Vec<u8>,
-    ) -> VerifierTranscript<StdChallenger> {
-        VerifierTranscript::new(StdChallenger::default(), bytes)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    use crate::poly::Utils;
-    use binius_math::ntt::{domain_context::GenericPreExpanded, NeighborsLastMultiThread};
-    use binius_verifier::{
-        config::B128,
-        hash::{StdCompression, StdDigest},
-        merkle_tree::BinaryMerkleTreeScheme,
-    };
 
-    fn create_test_data(size_bytes: usize) -> Vec<u8> {
-        (0..size_bytes).map(|i| (i % 256) as u8).collect()
+    /// Merkle leaf digest of a batch of codeword values
+    ///
+    /// The root of a one-leaf tree is its leaf digest, so the leaf is hashed by
+    /// the Merkle prover itself rather than by a copy of its serialization.
+    fn leaf_digest(&self, leaf: &[P::Scalar]) -> Result<digest::Output<H::Digest>, FriVailError> {
+        let (commitment, _committed) = self
+            .merkle_prover
+            .commit(leaf, leaf.len())
+            .map_err(|e| FriVailError::Merkle(e.to_string()))?;
+        Ok(commitment.root)
     }
 
-    #[test]
-    fn test_friveil_new() {
-        const LOG_INV_RATE: usize = 1;
-        const NUM_TEST_QUERIES: usize = 3;
-        const N_VARS: usize = 10;
-        const LOG_NUM_SHARES: usize = 2;
+    /// Merkle parent digest of two sibling nodes, using the scheme's compression
+    fn compress_nodes(
+        left: digest::Output<H::Digest>,
+        right: digest::Output<H::Digest>,
+    ) -> digest::Output<H::Digest> {
+        H::Compression::default().compress([left, right])
+    }
 
-        let friVail = TestFriVail::new(LOG_INV_RATE, NUM_TEST_QUERIES, 2, N_VARS, LOG_NUM_SHARES);
+    /// Generate a polynomial commitment from the nonzero evaluations of an MLE
+    ///
+    /// The dense evaluation vector is built by scattering the nonzero entries over
+    /// zeros and committed exactly like `commit`, so the root matches the dense
+    /// commitment of the same polynomial.
+    ///
+    /// # Arguments
+    /// * `nonzero` - `(index, value)` pairs of nonzero evaluations, indices distinct
+    /// * `n_vars` - Number of variables of the MLE
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Commitment output containing commitment and codeword
+    ///
+    /// # Errors
+    /// When an index is out of range or repeated, or commitment fails
+    pub fn commit_sparse(
+        &self,
+        nonzero: &[(usize, P::Scalar)],
+        n_vars: usize,
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<CommitmentOutput<P, H>, FriVailError> {
+        let len = 1usize << n_vars;
+        let mut values = vec![P::Scalar::zero(); len];
+        let mut seen = HashSet::with_capacity(nonzero.len());
+        for &(index, value) in nonzero {
+            if index >= len {
+                return Err(FriVailError::InvalidParams(format!(
+                    "Index {} out of range for an MLE with {} variables",
+                    index, n_vars
+                )));
+            }
+            if !seen.insert(index) {
+                return Err(FriVailError::InvalidParams(format!(
+                    "Index {} given more than once",
+                    index
+                )));
+            }
+            values[index] = value;
+        }
 
-        assert_eq!(friVail.log_inv_rate, LOG_INV_RATE);
-        assert_eq!(friVail.num_test_queries, NUM_TEST_QUERIES);
-        assert_eq!(friVail.n_vars, N_VARS);
-        assert_eq!(friVail.log_num_shares, LOG_NUM_SHARES);
+        self.commit(FieldBuffer::<P>::from_values(&values), fri_params, ntt)
     }
 
-    #[test]
-    fn test_calculate_evaluation_point_random() {
-        const N_VARS: usize = 8;
-        let friVail = TestFriVail::new(1, 3, 2, N_VARS, 2);
-
-        let result = friVail.calculate_evaluation_point_random();
-        assert!(result.is_ok());
+    /// Generate a polynomial commitment from multilinear coefficients
+    ///
+    /// Coefficient `i` multiplies the monomial `prod_{j : bit j of i set} X_j`, with
+    /// `X_0` the least significant bit of the index, matching the variable order of
+    /// the evaluation-form MLE taken by `commit`.
+    ///
+    /// # Arguments
+    /// * `coeffs` - Multilinear coefficients, a power-of-two count
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Commitment output containing commitment and codeword
+    ///
+    /// # Errors
+    /// When the coefficient count is not a power of two or commitment fails
+    pub fn commit_coeffs(
+        &self,
+        coeffs: &[P::Scalar],
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<CommitmentOutput<P, H>, FriVailError> {
+        if !coeffs.len().is_power_of_two() {
+            return Err(FriVailError::InvalidParams(format!(
+                "Coefficient count {} is not a power of two",
+                coeffs.len()
+            )));
+        }
 
-        let evaluation_point = result.unwrap();
-        assert_eq!(evaluation_point.len(), N_VARS);
+        let evals = Self::multilinear_coeffs_to_evals(coeffs);
+        self.commit(FieldBuffer::<P>::from_values(&evals), fri_params, ntt)
+    }
 
-        // Test deterministic behavior with fixed seed
-        let result2 = friVail.calculate_evaluation_point_random();
-        assert!(result2.is_ok());
-        let evaluation_point2 = result2.unwrap();
-        assert_eq!(evaluation_point, evaluation_point2);
+    /// Evaluate a multilinear polynomial in coefficient form over the boolean hypercube
+    ///
+    /// Each evaluation is the sum of coefficients whose monomial is a subset of the
+    /// point. Over a binary field this transform is its own inverse.
+    fn multilinear_coeffs_to_evals(coeffs: &[P::Scalar]) -> Vec<P::Scalar> {
+        let mut values = coeffs.to_vec();
+        let n_vars = values.len().ilog2();
+        for var in 0..n_vars {
+            let bit = 1 << var;
+            for index in 0..values.len() {
+                if index & bit != 0 {
+                    let lower = values[index ^ bit];
+                    values[index] += lower;
+                }
+            }
+        }
+        values
     }
 
+    /// Generate an evaluation proof for the committed polynomial
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `commit_output` - Previous commitment output
+    /// * `evaluation_point` - Point at which to evaluate the polynomial
+    ///
+    /// # Returns
+    /// Tuple containing terminal codeword, query prover, transcript bytes, and
+    /// the evaluation claim at `evaluation_point`
+    ///
+    /// # Errors
+    /// When proof generation fails
+    pub fn prove<'b>(
+        &'b self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: &'b FRIParams<P::Scalar>,
+        ntt: &'b NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        commit_output: &'b CommitmentOutput<P, H>,
+        evaluation_point: &[P::Scalar],
+    ) -> ProveResult<'b, P, H> {
+        self.prove_with_token(
+            packed_mle,
+            fri_params,
+            ntt,
+            commit_output,
+            evaluation_point,
+            &[],
+            None,
+        )
+    }
+
+    /// Generate an evaluation proof bound to a domain separation context
+    ///
+    /// `context` is absorbed into the challenger before the commitment, so every
+    /// challenge depends on it and the proof verifies only through
+    /// `verify_with_context` with the same context. An empty context gives the
+    /// same proof as `prove`.
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `commit_output` - Previous commitment output
+    /// * `evaluation_point` - Point at which to evaluate the polynomial
+    /// * `context` - Domain separation context, e.g. a protocol and round label
+    ///
+    /// # Returns
+    /// Tuple containing terminal codeword, query prover, transcript bytes, and
+    /// the evaluation claim at `evaluation_point`
+    ///
+    /// # Errors
+    /// When proof generation fails
+    pub fn prove_with_context<'b>(
+        &'b self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: &'b FRIParams<P::Scalar>,
+        ntt: &'b NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        commit_output: &'b CommitmentOutput<P, H>,
+        evaluation_point: &[P::Scalar],
+        context: &[u8],
+    ) -> ProveResult<'b, P, H> {
+        self.prove_with_token(
+            packed_mle,
+            fri_params,
+            ntt,
+            commit_output,
+            evaluation_point,
+            context,
+            None,
+        )
+    }
+
+    /// Generate an evaluation proof together with its extra query opening
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `commit_output` - Previous commitment output
+    /// * `evaluation_point` - Point at which to evaluate the polynomial
+    /// * `extra_index` - Codeword index to open for the extra query check
+    ///
+    /// # Returns
+    /// Proof accepted by `verify_proof`
+    ///
+    /// # Errors
+    /// When proof generation, layer extraction or the opening fails
+    pub fn prove_full(
+        &self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        commit_output: &CommitmentOutput<P, H>,
+        evaluation_point: &[P::Scalar],
+        extra_index: usize,
+    ) -> Result<FriVailProof<P::Scalar>, FriVailError> {
+        let (terminate_codeword, query_prover, transcript_bytes, _) =
+            self.prove(packed_mle, fri_params, ntt, commit_output, evaluation_point)?;
+        let layers = query_prover
+            .vcs_optimal_layers()
+            .map_err(|e| FriVailError::Prove(e.to_string()))?;
+        let extra_transcript = self.open(extra_index, &commit_output.commitment, &query_prover)?;
+
+        Ok(FriVailProof {
+            transcript_bytes,
+            terminate_codeword,
+            layers,
+            extra_index,
+            extra_transcript_bytes: self.get_transcript_bytes(&extra_transcript),
+        })
+    }
+
+    /// Generate one evaluation proof for several points
+    ///
+    /// The points `z_0..z_{k-1}` are joined by the curve `l(t)` of degree `k - 1`
+    /// with `l(i) = z_i`. Restricted to it the polynomial becomes the univariate
+    /// `g(t) = f(l(t))` of degree at most `D = n_vars * (k - 1)`, and the prover
+    /// sends its values at the nodes `0..=D`, the first `k` being the claims. A
+    /// challenge `r` is drawn from a transcript over the commitment, points and
+    /// values, and a single proof is made that `f(l(r))` equals the random linear
+    /// combination of the values with the Lagrange weights of `r`.
+    ///
+    /// Soundness: if any claim is false the sent values describe a polynomial
+    /// other than `g`. Two distinct polynomials of degree at most `D` agree on at
+    /// most `D` points, so the combined claim is true with probability at most
+    /// `D / 2^128` over `r`, on top of the soundness of the single proof.
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `commit_output` - Previous commitment output
+    /// * `points` - Evaluation points, each with one coordinate per variable
+    ///
+    /// # Returns
+    /// Proof accepted by `verify_multi` for the claims at `points`
+    ///
+    /// # Errors
+    /// When the points are malformed or proof generation fails
+    pub fn prove_multi(
+        &self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        commit_output: &CommitmentOutput<P, H>,
+        points: &[Vec<P::Scalar>],
+    ) -> Result<MultiPointProof<P::Scalar>, FriVailError> {
+        Self::check_multi_points(points)?;
+        let num_nodes = Self::multi_point_degree(points) + 1;
+        let evaluations = (0..num_nodes)
+            .map(|node| {
+                let point = Self::curve_point(points, P::Scalar::from(node as u128));
+                self.evaluate(&packed_mle, &point)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let challenge =
+            Self::multi_point_challenge(&commit_output.commitment, points, &evaluations);
+        let combined_point = Self::curve_point(points, challenge);
+        let (_, _, transcript_bytes, _) =
+            self.prove(packed_mle, fri_params, ntt, commit_output, &combined_point)?;
+
+        Ok(MultiPointProof {
+            evaluations,
+            transcript_bytes,
+        })
+    }
+
+    /// Generate an evaluation proof, stopping early when cancelled
+    ///
+    /// The token is checked before each proving stage; a stage that has started
+    /// runs to completion.
+    ///
+    /// # Arguments
+    /// * `packed_mle` - Packed multilinear extension
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `commit_output` - Previous commitment output
+    /// * `evaluation_point` - Point at which to evaluate the polynomial
+    /// * `token` - Cancellation token
+    ///
+    /// # Returns
+    /// Tuple containing terminal codeword, query prover, transcript bytes, and
+    /// the evaluation claim at `evaluation_point`
+    ///
+    /// # Errors
+    /// `FriVailError::Cancelled` when the token is cancelled, or
+    /// `FriVailError::Prove` when proof generation fails
+    pub fn prove_cancellable<'b>(
+        &'b self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: &'b FRIParams<P::Scalar>,
+        ntt: &'b NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        commit_output: &'b CommitmentOutput<P, H>,
+        evaluation_point: &[P::Scalar],
+        token: &CancellationToken,
+    ) -> ProveResult<'b, P, H> {
+        self.prove_with_token(
+            packed_mle,
+            fri_params,
+            ntt,
+            commit_output,
+            evaluation_point,
+            &[],
+            Some(token),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn prove_with_token<'b>(
+        &'b self,
+        packed_mle: FieldBuffer<P>,
+        fri_params: &'b FRIParams<P::Scalar>,
+        ntt: &'b NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        commit_output: &'b CommitmentOutput<P, H>,
+        evaluation_point: &[P::Scalar],
+        context: &[u8],
+        token: Option<&CancellationToken>,
+    ) -> ProveResult<'b, P, H> {
+        let check_cancelled = || token.map_or(Ok(()), CancellationToken::check);
+        check_cancelled()?;
+
+        let pcs = PCSProver::new(ntt, &self.merkle_prover, fri_params);
+
+        let mut prover_transcript = ProverTranscript::new(StdChallenger::default());
+
+        // Absorb the context first; it is length-prefixed and not sent in the proof
+        if !context.is_empty() {
+            let mut observer = prover_transcript.observe();
+            observer.write_bytes(&(context.len() as u64).to_le_bytes());
+            observer.write_bytes(context);
+        }
+
+        // Write commitment to transcript
+        prover_transcript.message().write(&commit_output.commitment);
+
+        // Embed the folding schedule so verifiers need not share our arity
+        let fold_arities = fri_params.fold_arities();
+        prover_transcript
+            .message()
+            .write_bytes(&(fold_arities.len() as u64).to_le_bytes());
+        for &arity in fold_arities {
+            prover_transcript
+                .message()
+                .write_bytes(&(arity as u64).to_le_bytes());
+        }
+
+        let eval_point_eq = eq_ind_partial_eval(evaluation_point);
+        let evaluation_claim = inner_product_buffers(&packed_mle, &eval_point_eq);
+        check_cancelled()?;
+
+        // Use prove_with_openings instead of prove
+        let (terminate_codeword, query_prover) = pcs
+            .prove_with_openings(
+                commit_output.codeword.clone(),
+                &commit_output.committed,
+                packed_mle,
+                evaluation_point,
+                evaluation_claim,
+                &mut prover_transcript,
+            )
+            .map_err(|e| FriVailError::Prove(e.to_string()))?;
+
+        // Get transcript bytes
+        let transcript_bytes = prover_transcript.finalize();
+
+        Ok((
+            terminate_codeword.into(),
+            query_prover,
+            transcript_bytes,
+            evaluation_claim,
+        ))
+    }
+
+    /// Check that `decode_batch` inverts binius's forward additive NTT
+    ///
+    /// Random inputs are transformed with the NTT's `forward_transform` using the
+    /// same layer skips as Reed-Solomon encoding, then decoded with `decode_batch`;
+    /// every trial must return the original input.
+    ///
+    /// # Arguments
+    /// * `ntt` - Number Theoretic Transform instance to validate
+    /// * `log_len` - Logarithm of the codeword length
+    /// * `log_batch_size` - Logarithm of batch size
+    ///
+    /// # Returns
+    /// Ok(()) if the inverse matches the forward transform
+    ///
+    /// # Errors
+    /// When `decode_batch` fails or produces a different vector
+    pub fn verify_ntt_inverse_correctness(
+        &self,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        log_len: usize,
+        log_batch_size: usize,
+    ) -> Result<(), FriVailError> {
+        const TRIALS: u64 = 4;
+
+        if self.log_inv_rate > log_len {
+            return Err(FriVailError::InvalidParams(format!(
+                "log_inv_rate {} exceeds codeword log length {}",
+                self.log_inv_rate, log_len
+            )));
+        }
+
+        let data_log_len = log_len + log_batch_size;
+        let len = 1 << data_log_len;
+
+        for trial in 0..TRIALS {
+            let mut rng = StdRng::seed_from_u64(trial);
+            let original: Vec<P::Scalar> = (0..len)
+                .map(|_| <B128 as Random>::random(&mut rng))
+                .collect();
+
+            let mut transformed = original.clone();
+            ntt.forward_transform(
+                FieldSliceMut::from_slice(data_log_len, transformed.as_mut_slice()),
+                self.log_inv_rate,
+                log_batch_size,
+            );
+
+            let mut decoded = Vec::with_capacity(len);
+            let initialized = self.decode_batch(
+                log_len,
+                self.log_inv_rate,
+                log_batch_size,
+                ntt,
+                &transformed,
+                &mut decoded.spare_capacity_mut()[..len],
+            )?;
+            unsafe {
+                // Safety: decode_batch initialized the first `initialized` elements
+                decoded.set_len(initialized);
+            }
+
+            if let Some(index) = (0..len).find(|&i| decoded[i] != original[i]) {
+                return Err(FriVailError::Verify(format!(
+                    "Inverse NTT mismatch at index {} (log_len {}, log_batch_size {}, trial {})",
+                    index, log_len, log_batch_size, trial
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Open one column of a batched commitment at a codeword position
+    ///
+    /// # Arguments
+    /// * `commit_output` - Commitment output from `commit`
+    /// * `column` - Column to open
+    /// * `index` - Position in the codeword of each column
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Opening carrying the full leaf and its inclusion proof
+    ///
+    /// # Errors
+    /// When `column` or `index` is out of range or proof generation fails
+    pub fn open_column(
+        &self,
+        commit_output: &CommitmentOutput<P, H>,
+        column: usize,
+        index: usize,
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<ColumnOpening<P::Scalar>, FriVailError> {
+        let leaf_width = 1 << fri_params.log_batch_size();
+        let num_leaves = 1 << Self::merkle_tree_depth(fri_params);
+        if column >= leaf_width {
+            return Err(FriVailError::InvalidParams(format!(
+                "Column {} out of range for a batch of {} columns",
+                column, leaf_width
+            )));
+        }
+        if index >= num_leaves {
+            return Err(FriVailError::InvalidParams(format!(
+                "Index {} out of range for a codeword of {} positions",
+                index, num_leaves
+            )));
+        }
+
+        let leaf: Vec<P::Scalar> = P::iter_slice(&commit_output.codeword)
+            .skip(index * leaf_width)
+            .take(leaf_width)
+            .collect();
+        let proof = self.inclusion_proof(&commit_output.committed, index)?;
+
+        Ok(ColumnOpening {
+            index,
+            column,
+            leaf,
+            proof,
+        })
+    }
+
+    /// Verify a column opening against a commitment
+    ///
+    /// # Arguments
+    /// * `opening` - Opening produced by `open_column`
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    /// * `commitment` - Merkle tree root commitment
+    ///
+    /// # Returns
+    /// The opened column's value at the opening's index
+    ///
+    /// # Errors
+    /// When the leaf has the wrong width or its inclusion proof is invalid
+    pub fn verify_column_opening(
+        &self,
+        opening: &mut ColumnOpening<P::Scalar>,
+        fri_params: &FRIParams<P::Scalar>,
+        commitment: [u8; 32],
+    ) -> Result<P::Scalar, FriVailError> {
+        let leaf_width = 1 << fri_params.log_batch_size();
+        if opening.leaf.len() != leaf_width || opening.column >= leaf_width {
+            return Err(FriVailError::InvalidParams(format!(
+                "Opening of column {} with {} leaf values does not match a batch of {} columns",
+                opening.column,
+                opening.leaf.len(),
+                leaf_width
+            )));
+        }
+
+        self.verify_inclusion_proof(
+            &mut opening.proof,
+            &opening.leaf,
+            opening.index,
+            fri_params,
+            commitment,
+        )?;
+
+        Ok(opening.leaf[opening.column])
+    }
+
+    /// Append message values to a segmented commitment
+    ///
+    /// Only the last partially filled segment and newly started segments are
+    /// re-encoded; every other segment keeps its codeword and sub-root.
+    ///
+    /// # Arguments
+    /// * `segmented` - Segmented commitment to extend
+    /// * `values` - Message values to append
+    /// * `fri_params` - FRI protocol parameters for one segment
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Indices of the segments that were re-committed
+    ///
+    /// # Errors
+    /// When `fri_params` does not match the segment length or commitment fails
+    pub fn append_segment(
+        &self,
+        segmented: &mut SegmentedCommitment<P, H>,
+        values: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<std::ops::Range<usize>, FriVailError> {
+        if fri_params.rs_code().log_dim() + fri_params.log_batch_size() != segmented.segment_log_len
+        {
+            return Err(FriVailError::InvalidParams(format!(
+                "FRI parameters encode 2^{} values, segments hold 2^{}",
+                fri_params.rs_code().log_dim() + fri_params.log_batch_size(),
+                segmented.segment_log_len
+            )));
+        }
+
+        let segment_len = segmented.segment_len();
+        let first_dirty = segmented.values.len() / segment_len;
+        segmented.values.extend_from_slice(values);
+        let num_segments = segmented.values.len().div_ceil(segment_len);
+
+        segmented.segments.truncate(first_dirty);
+        for segment in first_dirty..num_segments {
+            let start = segment * segment_len;
+            let end = segmented.values.len().min(start + segment_len);
+
+            let mut message = segmented.values[start..end].to_vec();
+            message.resize(segment_len, P::Scalar::zero());
+
+            let output = self.commit(
+                FieldBuffer::<P>::from_values(&message),
+                fri_params.clone(),
+                ntt,
+            )?;
+            segmented.segments.push(output);
+        }
+
+        Ok(first_dirty..num_segments)
+    }
+
+    /// Verify sampled leaves against a commitment, in parallel with the `parallel` feature
+    ///
+    /// Merkle verification only reads the scheme, so samples are checked
+    /// independently and one failure does not stop the others.
+    ///
+    /// # Arguments
+    /// * `commitment` - Merkle root the samples were opened against
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    /// * `samples` - Leaf index, leaf values and inclusion proof bytes of each sample
+    /// * `progress` - Called with `(done, total)` samples every `PROGRESS_INTERVAL`
+    ///   samples and once at the end
+    ///
+    /// # Returns
+    /// Verification result of each sample, in the order of `samples`
+    pub fn verify_samples_parallel(
+        &self,
+        commitment: [u8; 32],
+        fri_params: &FRIParams<P::Scalar>,
+        samples: &[(usize, Vec<P::Scalar>, Vec<u8>)],
+        progress: Option<&dyn Fn(usize, usize)>,
+    ) -> Vec<Result<(), FriVailError>> {
+        // Without a callback every sample goes into a single batch
+        let batch_len = progress.map_or(samples.len(), |_| PROGRESS_INTERVAL).max(1);
+        let mut results = Vec::with_capacity(samples.len());
+        for batch in samples.chunks(batch_len) {
+            #[cfg(feature = "parallel")]
+            let samples_iter = batch.par_iter();
+            #[cfg(not(feature = "parallel"))]
+            let samples_iter = batch.iter();
+
+            let batch_results: Vec<_> = samples_iter
+                .map(|(index, values, proof_bytes)| {
+                    let mut proof = self.reconstruct_transcript_from_bytes(proof_bytes.clone());
+                    self.verify_inclusion_proof(&mut proof, values, *index, fri_params, commitment)
+                })
+                .collect();
+            results.extend(batch_results);
+
+            if let Some(progress) = progress {
+                progress(results.len(), samples.len());
+            }
+        }
+        results
+    }
+
+    /// Commit to a previous commitment's data followed by new data
+    ///
+    /// The Reed-Solomon domain and the bit-reversal applied before the NTT both
+    /// depend on the total message length, so growing the message changes every
+    /// codeword symbol and every Merkle leaf; no part of `prev.codeword` or its
+    /// tree survives. What is reused is the previous data itself: it is decoded
+    /// from `prev.codeword`, so callers need not keep the original payload. Use
+    /// `SegmentedCommitment` when earlier roots must stay unchanged.
+    ///
+    /// # Arguments
+    /// * `prev` - Commitment output of the existing data
+    /// * `prev_params` - FRI protocol parameters `prev` was committed with
+    /// * `new_mle` - Data to append after the previous message
+    /// * `fri_params` - FRI protocol parameters for the combined message
+    /// * `ntt` - NTT instance for `fri_params`, also used to decode `prev`
+    ///
+    /// # Returns
+    /// Commitment output of the combined message, zero-padded to the length
+    /// `fri_params` encodes
+    ///
+    /// # Errors
+    /// When `prev` cannot be decoded, the combined message is longer than
+    /// `fri_params` encodes, or commitment fails
+    pub fn commit_append(
+        &self,
+        prev: &CommitmentOutput<P, H>,
+        prev_params: &FRIParams<P::Scalar>,
+        new_mle: FieldBuffer<P>,
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<CommitmentOutput<P, H>, FriVailError> {
+        let mut message = self.decode_codeword(
+            &P::iter_slice(&prev.codeword).collect::<Vec<_>>(),
+            prev_params.clone(),
+            ntt,
+            EncodingLayout::BitReversed,
+        )?;
+        message.extend(new_mle.iter_scalars());
+
+        let message_len = 1 << (fri_params.rs_code().log_dim() + fri_params.log_batch_size());
+        if message.len() > message_len {
+            return Err(FriVailError::InvalidParams(format!(
+                "Combined message of {} values exceeds the {} values the FRI parameters encode",
+                message.len(),
+                message_len
+            )));
+        }
+        message.resize(message_len, P::Scalar::zero());
+
+        self.commit(
+            FieldBuffer::<P>::from_values(&message),
+            fri_params.clone(),
+            ntt,
+        )
+    }
+
+    /// Derive proof-of-retrievability challenge positions for a commitment
+    ///
+    /// Positions are distinct and bound to both the seed and the commitment, so a
+    /// storer cannot precompute answers before the challenge is issued. Positions
+    /// address single codeword values, as committed by `initialize_fri_context`.
+    ///
+    /// # Arguments
+    /// * `commit_output` - Commitment being audited
+    /// * `challenge_seed` - Fresh randomness chosen by the auditor
+    /// * `count` - Number of positions, capped at the codeword length
+    ///
+    /// # Returns
+    /// Challenged codeword positions
+    pub fn por_challenge(
+        &self,
+        commit_output: &CommitmentOutput<P, H>,
+        challenge_seed: [u8; 32],
+        count: usize,
+    ) -> Vec<usize> {
+        Self::por_positions(
+            &commit_output.commitment,
+            commit_output.codeword.len() << P::LOG_WIDTH,
+            challenge_seed,
+            count,
+        )
+    }
+
+    /// Choose codeword indices to sample with a sampling strategy
+    ///
+    /// Indices address single codeword values, as committed by
+    /// `initialize_fri_context`, and can be passed to `inclusion_proof`.
+    ///
+    /// # Arguments
+    /// * `strategy` - Strategy choosing the indices
+    /// * `commit_output` - Commitment being sampled
+    /// * `count` - Number of indices, capped at the codeword length
+    /// * `seed` - Sampling seed
+    ///
+    /// # Returns
+    /// Distinct codeword indices
+    pub fn sample_indices(
+        &self,
+        strategy: &dyn SamplingStrategy,
+        commit_output: &CommitmentOutput<P, H>,
+        count: usize,
+        seed: [u8; 32],
+    ) -> Vec<usize> {
+        strategy.indices(commit_output.codeword.len() << P::LOG_WIDTH, count, seed)
+    }
+
+    /// Deterministically sample distinct positions from the seed and commitment root
+    fn por_positions(
+        root: &[u8],
+        codeword_len: usize,
+        challenge_seed: [u8; 32],
+        count: usize,
+    ) -> Vec<usize> {
+        let digest = StdDigest::new()
+            .chain_update(POR_CHALLENGE_TAG)
+            .chain_update(challenge_seed)
+            .chain_update(root)
+            .finalize();
+        let mut rng_seed = [0u8; 32];
+        rng_seed.copy_from_slice(&digest);
+        let mut rng = StdRng::from_seed(rng_seed);
+        rand::seq::index::sample(&mut rng, codeword_len, count.min(codeword_len)).into_vec()
+    }
+
+    /// Answer a proof-of-retrievability challenge from stored data
+    ///
+    /// # Arguments
+    /// * `codeword` - Codeword held by the storer
+    /// * `committed` - Merkle tree of the commitment
+    /// * `positions` - Positions from `por_challenge`
+    ///
+    /// # Returns
+    /// Value and inclusion proof for every position
+    ///
+    /// # Errors
+    /// When a position is outside the stored codeword or proof generation fails
+    pub fn por_respond(
+        &self,
+        codeword: &[P::Scalar],
+        committed: &<H::Prover as MerkleTreeProver<B128>>::Committed,
+        positions: &[usize],
+    ) -> Result<Vec<PorResponse<P::Scalar>>, FriVailError> {
+        positions
+            .iter()
+            .map(|&index| {
+                let value = *codeword.get(index).ok_or_else(|| {
+                    FriVailError::InvalidParams(format!(
+                        "Position {} missing from stored codeword",
+                        index
+                    ))
+                })?;
+                let proof = self.inclusion_proof(committed, index)?;
+                Ok(PorResponse {
+                    index,
+                    value,
+                    proof,
+                })
+            })
+            .collect()
+    }
+
+    /// Check a storer's answers to a proof-of-retrievability challenge
+    ///
+    /// # Arguments
+    /// * `commitment` - Merkle tree root commitment
+    /// * `challenge_seed` - Seed the challenge was derived from
+    /// * `count` - Number of positions challenged
+    /// * `responses` - Answers from `por_respond`
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Ok(()) if every challenged position was answered with a valid opening
+    ///
+    /// # Errors
+    /// When positions do not match the challenge or an inclusion proof is invalid
+    pub fn por_verify(
+        &self,
+        commitment: [u8; 32],
+        challenge_seed: [u8; 32],
+        count: usize,
+        responses: &mut [PorResponse<P::Scalar>],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<(), FriVailError> {
+        if fri_params.log_batch_size() != 0 {
+            return Err(FriVailError::InvalidParams(
+                "Proof of retrievability requires one value per leaf".into(),
+            ));
+        }
+
+        let codeword_len = 1 << Self::merkle_tree_depth(fri_params);
+        let positions = Self::por_positions(&commitment, codeword_len, challenge_seed, count);
+        if responses.len() != positions.len()
+            || responses
+                .iter()
+                .zip(&positions)
+                .any(|(response, &index)| response.index != index)
+        {
+            return Err(FriVailError::Verify(
+                "Responses do not answer the challenged positions".into(),
+            ));
+        }
+
+        for response in responses.iter_mut() {
+            self.verify_inclusion_proof(
+                &mut response.proof,
+                &[response.value],
+                response.index,
+                fri_params,
+                commitment,
+            )
+            .map_err(|e| {
+                FriVailError::Verify(format!(
+                    "Invalid response at position {}: {}",
+                    response.index, e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Generate an inclusion proof from the codeword alone
+    ///
+    /// The Merkle tree is rebuilt from the codeword on every call, trading CPU
+    /// for not having to store the `committed` tree.
+    ///
+    /// # Arguments
+    /// * `codeword` - Committed codeword
+    /// * `index` - Index in the codeword
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Verifier transcript containing the inclusion proof
+    ///
+    /// # Errors
+    /// When the index is out of range or proof generation fails
+    pub fn inclusion_proof_from_codeword(
+        &self,
+        codeword: &[P::Scalar],
+        index: usize,
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> TranscriptResult {
+        let num_leaves = codeword.len() >> fri_params.log_batch_size();
+        if index >= num_leaves {
+            return Err(FriVailError::InvalidParams(format!(
+                "Index {} out of range for a codeword of {} positions",
+                index, num_leaves
+            )));
+        }
+
+        let (_commitment, committed) = self
+            .merkle_prover
+            .commit(codeword, 1 << fri_params.log_batch_size())
+            .map_err(|e| FriVailError::Merkle(e.to_string()))?;
+        self.inclusion_proof(&committed, index)
+    }
+
+    /// Compress inclusion proofs of several leaves by dropping shared siblings
+    ///
+    /// A sibling is omitted when it lies on the path of another opened leaf, since
+    /// the verifier recomputes it from that leaf. A single path has no such
+    /// siblings; the savings grow with the number of nearby openings.
+    ///
+    /// # Arguments
+    /// * `openings` - Leaf openings against the same commitment
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Siblings that cannot be recomputed, in the order `decompress_openings` reads them
+    ///
+    /// # Errors
+    /// When an inclusion proof is shorter than the tree depth
+    pub fn compress_openings(
+        &self,
+        openings: &[LeafOpening<P::Scalar>],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<Vec<u8>, FriVailError> {
+        let depth = Self::merkle_tree_depth(fri_params);
+        let paths = openings
+            .iter()
+            .map(|opening| {
+                let mut proof = opening.proof.clone();
+                (0..depth)
+                    .map(|_| {
+                        proof
+                            .message()
+                            .read::<digest::Output<H::Digest>>()
+                            .map_err(|e| FriVailError::Transcript(e.to_string()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut compressed = Vec::new();
+        self.walk_opening_paths(openings, depth, |i, level, known| {
+            let sibling = paths[i][level];
+            if known.is_none() {
+                compressed.extend_from_slice(&sibling);
+            }
+            Ok(known.unwrap_or(sibling))
+        })?;
+        Ok(compressed)
+    }
+
+    /// Rebuild the inclusion proofs compressed by `compress_openings`
+    ///
+    /// # Arguments
+    /// * `indices` - Opened leaf positions, in the order they were compressed
+    /// * `leaves` - Opened leaf values
+    /// * `compressed` - Output of `compress_openings`
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// One opening per index, verifiable with `verify_inclusion_proof`
+    ///
+    /// # Errors
+    /// When the inputs have different lengths or `compressed` is malformed
+    pub fn decompress_openings(
+        &self,
+        indices: &[usize],
+        leaves: Vec<Vec<P::Scalar>>,
+        compressed: &[u8],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<Vec<LeafOpening<P::Scalar>>, FriVailError> {
+        if indices.len() != leaves.len() {
+            return Err(FriVailError::InvalidParams(format!(
+                "Got {} indices but {} leaves",
+                indices.len(),
+                leaves.len()
+            )));
+        }
+
+        let depth = Self::merkle_tree_depth(fri_params);
+        let digest_len = std::mem::size_of::<digest::Output<H::Digest>>();
+        let mut openings: Vec<LeafOpening<P::Scalar>> = indices
+            .iter()
+            .zip(leaves)
+            .map(|(&index, leaf)| LeafOpening {
+                index,
+                leaf,
+                proof: VerifierTranscript::new(StdChallenger::default(), Vec::new()),
+            })
+            .collect();
+
+        let mut paths = vec![Vec::with_capacity(depth * digest_len); openings.len()];
+        let mut chunks = compressed.chunks_exact(digest_len);
+        self.walk_opening_paths(&openings, depth, |i, _level, known| {
+            let sibling = match known {
+                Some(sibling) => sibling,
+                None => digest::Output::<H::Digest>::clone_from_slice(chunks.next().ok_or_else(
+                    || FriVailError::Deserialize("Compressed openings are truncated".into()),
+                )?),
+            };
+            paths[i].extend_from_slice(&sibling);
+            Ok(sibling)
+        })?;
+        if chunks.next().is_some() || !chunks.remainder().is_empty() {
+            return Err(FriVailError::Deserialize(
+                "Unexpected trailing bytes in compressed openings".into(),
+            ));
+        }
+
+        for (opening, path) in openings.iter_mut().zip(paths) {
+            opening.proof = VerifierTranscript::new(StdChallenger::default(), path);
+        }
+        Ok(openings)
+    }
+
+    /// Walk the authentication paths of several leaves level by level
+    ///
+    /// `sibling(i, level, known)` supplies the sibling of opening `i` at `level`,
+    /// where `known` is the sibling digest when another path already determines it.
+    fn walk_opening_paths(
+        &self,
+        openings: &[LeafOpening<P::Scalar>],
+        depth: usize,
+        mut sibling: impl FnMut(
+            usize,
+            usize,
+            Option<digest::Output<H::Digest>>,
+        ) -> Result<digest::Output<H::Digest>, FriVailError>,
+    ) -> Result<(), FriVailError> {
+        let mut positions: Vec<usize> = openings.iter().map(|opening| opening.index).collect();
+        let mut nodes: Vec<digest::Output<H::Digest>> = openings
+            .iter()
+            .map(|opening| self.leaf_digest(&opening.leaf))
+            .collect::<Result<_, _>>()?;
+
+        for level in 0..depth {
+            let known: HashMap<usize, digest::Output<H::Digest>> = positions
+                .iter()
+                .copied()
+                .zip(nodes.iter().copied())
+                .collect();
+
+            for i in 0..openings.len() {
+                let sibling = sibling(i, level, known.get(&(positions[i] ^ 1)).copied())?;
+                nodes[i] = if positions[i] & 1 == 0 {
+                    Self::compress_nodes(nodes[i], sibling)
+                } else {
+                    Self::compress_nodes(sibling, nodes[i])
+                };
+                positions[i] >>= 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Claim a data length for a commitment
+    ///
+    /// See `MinSizeClaim`: this is a capacity check, not a proof of the length.
+    ///
+    /// # Arguments
+    /// * `commit_output` - Commitment the data was committed under
+    /// * `claimed_bytes` - Length of the original data in bytes
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Claim checkable with `verify_min_size`
+    ///
+    /// # Errors
+    /// When the commitment cannot hold `claimed_bytes` bytes
+    pub fn prove_min_size(
+        &self,
+        commit_output: &CommitmentOutput<P, H>,
+        claimed_bytes: usize,
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<MinSizeClaim, FriVailError> {
+        let n_vars = fri_params.rs_code().log_dim() + fri_params.log_batch_size();
+        let capacity = Self::data_capacity(n_vars);
+        if claimed_bytes > capacity {
+            return Err(FriVailError::InvalidParams(format!(
+                "Claimed {} bytes exceeds the {} bytes a {}-variable commitment holds",
+                claimed_bytes, capacity, n_vars
+            )));
+        }
+
+        let mut root = [0u8; 32];
+        root.copy_from_slice(&commit_output.commitment);
+        Ok(MinSizeClaim {
+            root,
+            claimed_bytes,
+            n_vars,
+        })
+    }
+
+    /// Check a data length claim against a commitment
+    ///
+    /// The committed MLE has `n_vars` variables, so the commitment covers
+    /// `2^n_vars` field elements of `BYTES_PER_ELEMENT` bytes each.
+    ///
+    /// # Arguments
+    /// * `claim` - Claim from `prove_min_size`
+    /// * `commitment` - Merkle tree root commitment
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Ok(()) if the claim names `commitment` and fits its capacity
+    ///
+    /// # Errors
+    /// When the claim is for another commitment or exceeds its capacity
+    pub fn verify_min_size(
+        &self,
+        claim: &MinSizeClaim,
+        commitment: [u8; 32],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<(), FriVailError> {
+        let n_vars = fri_params.rs_code().log_dim() + fri_params.log_batch_size();
+        if claim.root != commitment || claim.n_vars != n_vars {
+            return Err(FriVailError::Verify(
+                "Size claim is not for this commitment".into(),
+            ));
+        }
+
+        let capacity = Self::data_capacity(n_vars);
+        if claim.claimed_bytes > capacity {
+            return Err(FriVailError::Verify(format!(
+                "Claimed {} bytes exceeds the {} bytes a {}-variable commitment holds",
+                claim.claimed_bytes, capacity, n_vars
+            )));
+        }
+        Ok(())
+    }
+
+    /// Number of data bytes an MLE with `n_vars` variables holds
+    fn data_capacity(n_vars: usize) -> usize {
+        1usize
+            .checked_shl(n_vars as u32)
+            .and_then(|elements| elements.checked_mul(BYTES_PER_ELEMENT))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Export proof components in the EVM ABI format
+    ///
+    /// # Arguments
+    /// * `transcript_bytes` - Serialized evaluation proof transcript
+    /// * `terminate_codeword` - Scalars of the terminal codeword from `prove`
+    /// * `layers` - Merkle tree layers from the query prover
+    ///
+    /// # Returns
+    /// Proof with fixed-size big-endian fields, see [`crate::evm`] for the layout
+    pub fn export_proof_evm(
+        &self,
+        transcript_bytes: &[u8],
+        terminate_codeword: &[P::Scalar],
+        layers: &[Vec<digest::Output<H::Digest>>],
+    ) -> EvmProof {
+        let elements: Vec<u128> = terminate_codeword.iter().map(|&x| u128::from(x)).collect();
+
+        EvmProof {
+            transcript: transcript_bytes.to_vec(),
+            terminate_codeword_len: elements.len(),
+            terminate_codeword: EvmProof::pack_elements(&elements),
+            layers: layers
+                .iter()
+                .map(|layer| {
+                    layer
+                        .iter()
+                        .map(|digest| {
+                            let mut root = [0u8; 32];
+                            root.copy_from_slice(digest);
+                            root
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Import proof components from the EVM ABI format
+    ///
+    /// # Arguments
+    /// * `proof` - Proof produced by `export_proof_evm`
+    ///
+    /// # Returns
+    /// Tuple of transcript bytes, terminal codeword and Merkle layers
+    ///
+    /// # Errors
+    /// When the packed terminal codeword is malformed
+    pub fn import_proof_evm(
+        &self,
+        proof: &EvmProof,
+    ) -> Result<
+        (
+            Vec<u8>,
+            TerminalCodeword<P::Scalar>,
+            Vec<Vec<digest::Output<H::Digest>>>,
+        ),
+        FriVailError,
+    > {
+        let terminate_codeword = proof
+            .unpack_elements()?
+            .into_iter()
+            .map(P::Scalar::from)
+            .collect::<Vec<_>>()
+            .into();
+        let layers = proof
+            .layers
+            .iter()
+            .map(|layer| layer.iter().map(|&root| root.into()).collect())
+            .collect();
+
+        Ok((proof.transcript.clone(), terminate_codeword, layers))
+    }
+
+    /// Issue a signed certificate attesting the availability of a commitment
+    ///
+    /// # Arguments
+    /// * `report` - Sampling report listing the verified sample indices
+    /// * `signing_key` - Key of the verifying node
+    ///
+    /// # Returns
+    /// Certificate over the report's root and samples, timestamped now
+    #[cfg(feature = "ed25519")]
+    pub fn issue_certificate(
+        &self,
+        report: &AvailabilityReport,
+        signing_key: &SigningKey,
+    ) -> AvailabilityCertificate {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+
+        AvailabilityCertificate::sign(
+            report.root,
+            report.sample_indices.clone(),
+            timestamp,
+            signing_key,
+        )
+    }
+
+    /// Verify the signature on an availability certificate
+    ///
+    /// # Arguments
+    /// * `cert` - Certificate to check
+    /// * `verifying_key` - Public key of the node that issued it
+    ///
+    /// # Returns
+    /// Whether the signature covers the certificate's current contents
+    #[cfg(feature = "ed25519")]
+    pub fn verify_certificate(
+        &self,
+        cert: &AvailabilityCertificate,
+        verifying_key: &VerifyingKey,
+    ) -> bool {
+        cert.verify(verifying_key)
+    }
+
+    /// Compute the Merkle root `commit` would produce for a codeword
+    fn codeword_root(
+        &self,
+        codeword: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<digest::Output<H::Digest>, FriVailError> {
+        let (commitment, _committed) = self
+            .merkle_prover
+            .commit(codeword, 1 << fri_params.log_batch_size())
+            .map_err(|e| FriVailError::Merkle(e.to_string()))?;
+        Ok(commitment.root)
+    }
+
+    /// Prove evaluations of several commitments at one point with one FRI proof
+    ///
+    /// A challenge `r` is drawn from a transcript over the roots, claims and
+    /// point, and the polynomials are combined as `sum_i r^i f_i`. The combined
+    /// polynomial is committed and proven at the point once. Its codeword is the
+    /// same combination of the batched codewords, so the leaves of every
+    /// commitment and of the combined codeword are opened at `num_test_queries`
+    /// positions derived from all roots, for the verifier to check the
+    /// combination without the codewords.
+    ///
+    /// # Arguments
+    /// * `packed_mles` - Packed multilinear extension of each commitment
+    /// * `commit_outputs` - Commitment output of each polynomial, in the same order
+    /// * `evaluation_point` - Point every polynomial is evaluated at
+    /// * `fri_params` - FRI protocol parameters shared by the commitments
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Claimed evaluation of each polynomial and the batch proof
+    ///
+    /// # Errors
+    /// When the batch is empty or mismatched, or commitment or proving fails
+    pub fn prove_batch(
+        &self,
+        packed_mles: &[FieldBuffer<P>],
+        commit_outputs: &[CommitmentOutput<P, H>],
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<(Vec<P::Scalar>, BatchProof<P::Scalar>), FriVailError> {
+        if packed_mles.is_empty() || packed_mles.len() != commit_outputs.len() {
+            return Err(FriVailError::InvalidParams(format!(
+                "Got {} polynomials for {} commitments",
+                packed_mles.len(),
+                commit_outputs.len()
+            )));
+        }
+
+        let claims = packed_mles
+            .iter()
+            .map(|packed_mle| self.evaluate(packed_mle, evaluation_point))
+            .collect::<Result<Vec<_>, _>>()?;
+        let roots: Vec<_> = commit_outputs.iter().map(|c| c.commitment).collect();
+        let coefficients = Self::batch_coefficients(&roots, &claims, evaluation_point);
+
+        let mut combined = vec![P::Scalar::zero(); 1 << packed_mles[0].log_len()];
+        for (packed_mle, &coefficient) in packed_mles.iter().zip(&coefficients) {
+            if packed_mle.log_len() != packed_mles[0].log_len() {
+                return Err(FriVailError::InvalidParams(
+                    "Batched polynomials must have the same number of variables".into(),
+                ));
+            }
+            for (acc, x) in combined.iter_mut().zip(P::iter_slice(packed_mle.as_ref())) {
+                *acc += coefficient * x;
+            }
+        }
+        let combined_mle = FieldBuffer::<P>::from_values(&combined);
+        let combined_output = self.commit(combined_mle.clone(), fri_params.clone(), ntt)?;
+        let (_, _, transcript_bytes, _) = self.prove(
+            combined_mle,
+            fri_params,
+            ntt,
+            &combined_output,
+            evaluation_point,
+        )?;
+
+        let leaf_width = 1 << fri_params.log_batch_size();
+        let leaf = |commit_output: &CommitmentOutput<P, H>, index: usize| -> Vec<P::Scalar> {
+            P::iter_slice(&commit_output.codeword)
+                .skip(index * leaf_width)
+                .take(leaf_width)
+                .collect()
+        };
+        let openings = Self::batch_query_positions(
+            &roots,
+            &combined_output.commitment,
+            1 << Self::merkle_tree_depth(fri_params),
+            self.num_test_queries,
+        )
+        .into_iter()
+        .map(|index| {
+            Ok(BatchOpening {
+                index,
+                leaves: commit_outputs.iter().map(|c| leaf(c, index)).collect(),
+                proofs: commit_outputs
+                    .iter()
+                    .map(|c| self.inclusion_proof(&c.committed, index))
+                    .collect::<Result<Vec<_>, FriVailError>>()?,
+                combined_leaf: leaf(&combined_output, index),
+                combined_proof: self.inclusion_proof(&combined_output.committed, index)?,
+            })
+        })
+        .collect::<Result<Vec<_>, FriVailError>>()?;
+
+        Ok((
+            claims,
+            BatchProof {
+                transcript_bytes,
+                openings,
+            },
+        ))
+    }
+
+    /// Verify evaluation claims of several commitments with one FRI check
+    ///
+    /// Recomputes the batching challenge `r` of `prove_batch`, verifies the
+    /// single evaluation proof against the combined claim `sum_i r^i y_i`, and
+    /// checks at every query that the combined leaf is the same combination of
+    /// the opened leaves. The verifier never sees a full codeword.
+    ///
+    /// Soundness: a false claim makes the combined claim false except with
+    /// probability `roots.len() / 2^128` over `r`. A combined codeword that is
+    /// not the combination of the batched codewords differs from it on at least
+    /// the code distance, as FRI bounds it close to a codeword, so each query
+    /// misses the difference with probability at most the code rate.
+    ///
+    /// # Arguments
+    /// * `roots` - Commitment root of each polynomial
+    /// * `claims` - Claimed evaluation of each polynomial
+    /// * `evaluation_point` - Point every polynomial is evaluated at
+    /// * `proof` - Proof from `prove_batch`
+    /// * `fri_params` - FRI protocol parameters shared by the commitments
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Ok(()) if every claim holds
+    ///
+    /// # Errors
+    /// When the batch is malformed, the evaluation proof fails, or an opening
+    /// is invalid or inconsistent with the combination
+    pub fn batch_verify_commitments(
+        &self,
+        roots: &[digest::Output<H::Digest>],
+        claims: &[P::Scalar],
+        evaluation_point: &[P::Scalar],
+        proof: &mut BatchProof<P::Scalar>,
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+    ) -> Result<(), FriVailError> {
+        if roots.is_empty() || roots.len() != claims.len() {
+            return Err(FriVailError::InvalidParams(format!(
+                "Got {} claims for {} commitments",
+                claims.len(),
+                roots.len()
+            )));
+        }
+
+        let coefficients = Self::batch_coefficients(roots, claims, evaluation_point);
+        let combined_claim =
+            inner_product::<P::Scalar>(claims.iter().copied(), coefficients.iter().copied());
+
+        let mut verifier_transcript =
+            self.reconstruct_transcript_from_bytes(proof.transcript_bytes.clone());
+        let (combined_root, _) = self.verify_header(
+            &mut verifier_transcript.clone(),
+            evaluation_point,
+            fri_params,
+        )?;
+        self.verify(
+            &mut verifier_transcript,
+            combined_claim,
+            evaluation_point,
+            fri_params,
+            ntt,
+            None,
+            None,
+            None,
+            None,
+        )?;
+
+        let positions = Self::batch_query_positions(
+            roots,
+            &combined_root,
+            1 << Self::merkle_tree_depth(fri_params),
+            self.num_test_queries,
+        );
+        if proof.openings.len() != positions.len()
+            || proof
+                .openings
+                .iter()
+                .zip(&positions)
+                .any(|(opening, &index)| opening.index != index)
+        {
+            return Err(FriVailError::Verify(
+                "Openings do not answer the batch queries".into(),
+            ));
+        }
+
+        for opening in proof.openings.iter_mut() {
+            if opening.leaves.len() != roots.len() || opening.proofs.len() != roots.len() {
+                return Err(FriVailError::Verify(format!(
+                    "Opening at {} has {} leaves for {} commitments",
+                    opening.index,
+                    opening.leaves.len(),
+                    roots.len()
+                )));
+            }
+            for ((leaf, inclusion), root) in opening
+                .leaves
+                .iter()
+                .zip(opening.proofs.iter_mut())
+                .zip(roots)
+            {
+                self.verify_inclusion_proof(inclusion, leaf, opening.index, fri_params, *root)?;
+            }
+            self.verify_inclusion_proof(
+                &mut opening.combined_proof,
+                &opening.combined_leaf,
+                opening.index,
+                fri_params,
+                combined_root,
+            )?;
+
+            let consistent = opening.combined_leaf.iter().enumerate().all(|(k, &value)| {
+                inner_product::<P::Scalar>(
+                    opening.leaves.iter().map(|leaf| leaf[k]),
+                    coefficients.iter().copied(),
+                ) == value
+            });
+            if !consistent {
+                return Err(FriVailError::Verify(format!(
+                    "Combined leaf at {} is not the batched combination",
+                    opening.index
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Powers `1, r, r^2, ...` of the batching challenge, one per commitment
+    fn batch_coefficients(
+        roots: &[digest::Output<H::Digest>],
+        claims: &[P::Scalar],
+        evaluation_point: &[P::Scalar],
+    ) -> Vec<P::Scalar> {
+        let mut transcript = ProverTranscript::new(StdChallenger::default());
+        transcript.message().write_bytes(BATCH_TAG);
+        for (root, claim) in roots.iter().zip(claims) {
+            transcript.message().write(root);
+            transcript.message().write(claim);
+        }
+        for coordinate in evaluation_point {
+            transcript.message().write(coordinate);
+        }
+        let challenge: P::Scalar = transcript.sample();
+
+        let mut coefficient = P::Scalar::ONE;
+        roots
+            .iter()
+            .map(|_| {
+                let current = coefficient;
+                coefficient *= challenge;
+                current
+            })
+            .collect()
+    }
+
+    /// Leaf indices opened by a batch proof, derived from every root
+    fn batch_query_positions(
+        roots: &[digest::Output<H::Digest>],
+        combined_root: &digest::Output<H::Digest>,
+        num_leaves: usize,
+        count: usize,
+    ) -> Vec<usize> {
+        let mut hasher = StdDigest::new().chain_update(BATCH_TAG);
+        for root in roots {
+            hasher.update(root);
+        }
+        let digest = hasher.chain_update(combined_root).finalize();
+        let mut rng_seed = [0u8; 32];
+        rng_seed.copy_from_slice(&digest);
+        let mut rng = StdRng::from_seed(rng_seed);
+        rand::seq::index::sample(&mut rng, num_leaves, count.min(num_leaves)).into_vec()
+    }
+
+    /// Encode data using Reed-Solomon code with NTT
+    ///
+    /// Produces the same codeword as `commit` without building a Merkle tree.
+    ///
+    /// # Arguments
+    /// * `data` - Message of `2^(log_dim + log_batch_size)` field elements
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `layout` - Order to encode in; `BitReversed` matches `commit`
+    ///
+    /// # Returns
+    /// The Reed-Solomon codeword
+    ///
+    /// # Errors
+    /// When encoding fails
+    pub fn encode_codeword(
+        &self,
+        data: &[P::Scalar],
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        layout: EncodingLayout,
+    ) -> Result<Vec<P::Scalar>, FriVailError> {
+        let mut encoded = Vec::new();
+        match layout {
+            EncodingLayout::BitReversed => {
+                self.encode_codeword_into(data, &fri_params, ntt, &mut encoded)?
+            }
+            EncodingLayout::NaturalOrder => {
+                // Pre-reversing cancels the reversal encode_batch applies
+                let data_log_len = fri_params.rs_code().log_dim() + fri_params.log_batch_size();
+                let mut reversed = data.to_vec();
+                bit_reverse_packed(FieldSliceMut::from_slice(
+                    data_log_len,
+                    reversed.as_mut_slice(),
+                ));
+                self.encode_codeword_into(&reversed, &fri_params, ntt, &mut encoded)?
+            }
+        }
+        Ok(encoded)
+    }
+
+    /// Encode data into a caller-provided buffer
+    ///
+    /// `out` is cleared and refilled, keeping its allocation across calls.
+    ///
+    /// # Arguments
+    /// * `data` - Message of `2^(log_dim + log_batch_size)` field elements
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `out` - Buffer receiving the codeword
+    ///
+    /// # Returns
+    /// Ok(()) once `out` holds the codeword
+    ///
+    /// # Errors
+    /// `FriVailError::InvalidParams` when `data` has the wrong length
+    pub fn encode_codeword_into(
+        &self,
+        data: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        out: &mut Vec<P::Scalar>,
+    ) -> Result<(), FriVailError> {
+        let rs_code = fri_params.rs_code();
+        let log_inv = rs_code.log_inv_rate();
+        let log_batch_size = fri_params.log_batch_size();
+        let data_log_len = rs_code.log_dim() + log_batch_size;
+        if data.len() != 1 << data_log_len {
+            return Err(FriVailError::InvalidParams(format!(
+                "Message has {} scalars, expected {}",
+                data.len(),
+                1 << data_log_len
+            )));
+        }
+
+        // Same steps as encode_batch, but in `out`: bit-reverse the message,
+        // repeat it once per coset and run the forward NTT in place
+        out.clear();
+        out.extend_from_slice(data);
+        bit_reverse_packed(FieldSliceMut::from_slice(data_log_len, out.as_mut_slice()));
+        for _ in 1..1 << log_inv {
+            out.extend_from_within(..data.len());
+        }
+        ntt.forward_transform(
+            FieldSliceMut::from_slice(data_log_len + log_inv, out.as_mut_slice()),
+            log_inv,
+            log_batch_size,
+        );
+
+        Ok(())
+    }
+
+    /// Decode a codeword straight into a packed buffer
+    ///
+    /// The message is decoded into a single allocation, truncated in place and
+    /// packed once, and the bit-reversal of `encode_batch` is undone on the packed
+    /// buffer, so the result can be passed to `commit` without conversion.
+    ///
+    /// # Arguments
+    /// * `codeword` - Encoded codeword to decode
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Packed message of `2^(log_dim + log_batch_size)` values, as `decode_codeword`
+    ///
+    /// # Errors
+    /// When the codeword length does not match `fri_params`
+    pub fn decode_codeword_buffer(
+        &self,
+        codeword: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<FieldBuffer<P>, FriVailError> {
+        let rs_code = fri_params.rs_code();
+        let log_batch_size = fri_params.log_batch_size();
+        let len = 1 << (rs_code.log_len() + log_batch_size - P::LOG_WIDTH);
+
+        let mut decoded = Vec::with_capacity(len);
+        let initialized = self.decode_batch(
+            rs_code.log_len(),
+            rs_code.log_inv_rate(),
+            log_batch_size,
+            ntt,
+            codeword,
+            &mut decoded.spare_capacity_mut()[..len],
+        )?;
+        unsafe {
+            // Safety: decode_batch initialized the first `initialized` elements
+            decoded.set_len(initialized);
+        }
+        decoded.truncate(1 << (rs_code.log_dim() + log_batch_size - P::LOG_WIDTH));
+
+        let mut buffer = FieldBuffer::<P>::from_values(&decoded);
+        bit_reverse_packed(buffer.to_mut());
+        Ok(buffer)
+    }
+
+    /// Decode a codeword and trim it to the committed payload
+    ///
+    /// Unlike `decode_codeword`, zero padding is dropped while zero elements
+    /// that belong to the payload are kept.
+    ///
+    /// # Arguments
+    /// * `codeword` - Encoded codeword to decode
+    /// * `original_byte_len` - Payload length, `PayloadCommitment::original_byte_len`
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// The `original_byte_len.div_ceil(16)` elements holding payload data
+    ///
+    /// # Errors
+    /// When decoding fails or the payload is longer than the decoded message
+    pub fn decode_payload(
+        &self,
+        codeword: &[P::Scalar],
+        original_byte_len: usize,
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> FieldResult<P> {
+        let mut decoded =
+            self.decode_codeword(codeword, fri_params, ntt, EncodingLayout::BitReversed)?;
+        let num_data_elements = original_byte_len.div_ceil(BYTES_PER_ELEMENT);
+        if num_data_elements > decoded.len() {
+            return Err(FriVailError::Decode {
+                expected: num_data_elements,
+                got: decoded.len(),
+            });
+        }
+        decoded.truncate(num_data_elements);
+        Ok(decoded)
+    }
+
+    /// Decode a codeword into a buffer that is zeroed when dropped
+    ///
+    /// # Arguments
+    /// * `codeword` - Encoded codeword to decode
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Decoded values, the same as `decode_codeword`
+    ///
+    /// # Errors
+    /// When decoding fails
+    #[cfg(feature = "zeroize")]
+    pub fn decode_codeword_zeroizing(
+        &self,
+        codeword: &[P::Scalar],
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<zeroize::Zeroizing<crate::poly::ScalarBuffer<P::Scalar>>, FriVailError> {
+        let decoded =
+            self.decode_codeword(codeword, fri_params, ntt, EncodingLayout::BitReversed)?;
+        Ok(zeroize::Zeroizing::new(crate::poly::ScalarBuffer(decoded)))
+    }
+
+    /// Check whether two codewords encode the same data
+    ///
+    /// Both codewords are decoded to their systematic data, so they may use
+    /// different code rates.
+    ///
+    /// # Arguments
+    /// * `a` - First codeword
+    /// * `a_params` - FRI protocol parameters `a` was encoded with
+    /// * `b` - Second codeword
+    /// * `b_params` - FRI protocol parameters `b` was encoded with
+    /// * `ntt` - NTT instance whose domain covers the longer codeword
+    ///
+    /// # Returns
+    /// Whether the decoded data of both codewords is equal
+    ///
+    /// # Errors
+    /// When either codeword cannot be decoded
+    pub fn codewords_encode_same_data(
+        &self,
+        a: &[P::Scalar],
+        a_params: &FRIParams<P::Scalar>,
+        b: &[P::Scalar],
+        b_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<bool, FriVailError> {
+        let a_dim = a_params.rs_code().log_dim() + a_params.log_batch_size();
+        let b_dim = b_params.rs_code().log_dim() + b_params.log_batch_size();
+        if a_dim != b_dim {
+            return Ok(false);
+        }
+
+        let a_data = self.decode_codeword(a, a_params.clone(), ntt, EncodingLayout::BitReversed)?;
+        let b_data = self.decode_codeword(b, b_params.clone(), ntt, EncodingLayout::BitReversed)?;
+        Ok(a_data == b_data)
+    }
+
+    /// Depth of the Merkle tree committing a codeword
+    fn merkle_tree_depth(fri_params: &FRIParams<P::Scalar>) -> usize {
+        fri_params.rs_code().log_len()
+    }
+
+    /// Number of sibling digests in the authentication path of a codeword index
+    ///
+    /// # Arguments
+    /// * `index` - Index in the codeword
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Path length from leaf to root, or 0 if `index` is not a leaf of the tree
+    pub fn auth_path_len(&self, index: usize, fri_params: &FRIParams<P::Scalar>) -> usize {
+        let tree_depth = Self::merkle_tree_depth(fri_params);
+        if index >= 1 << tree_depth {
+            return 0;
+        }
+        // Openings are proven against the root layer, so no path levels are skipped
+        tree_depth
+    }
+
+    /// Verify a codeword leaf against a published root from serialized bytes
+    ///
+    /// Light clients need only the root and tree depth, not `fri_params`.
+    ///
+    /// # Arguments
+    /// * `root` - Commitment root the leaf must be under
+    /// * `tree_depth` - Depth of the Merkle tree
+    /// * `index` - Leaf index
+    /// * `leaf` - Leaf values
+    /// * `opening_bytes` - Transcript bytes of the opening from `inclusion_proof`
+    ///
+    /// # Returns
+    /// Ok(()) if the opening is valid
+    ///
+    /// # Errors
+    /// `FriVailError::Verification` when the depth is too large, the index is
+    /// out of range or the opening does not verify
+    pub fn verify_leaf(
+        &self,
+        root: [u8; 32],
+        tree_depth: usize,
+        index: usize,
+        leaf: &[P::Scalar],
+        opening_bytes: &[u8],
+    ) -> Result<(), FriVailError> {
+        let mut transcript = self.reconstruct_transcript_from_bytes(opening_bytes.to_vec());
+        Ok(verifier::verify_leaf_opening(
+            self.merkle_scheme(),
+            &mut transcript,
+            leaf,
+            index,
+            tree_depth,
+            CommitmentRoot::from(root).into(),
+        )?)
+    }
+
+    /// Number of Merkle trees in an evaluation proof
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// One tree for the codeword plus one per fold round
+    pub fn num_merkle_layers(&self, fri_params: &FRIParams<P::Scalar>) -> usize {
+        1 + fri_params.fold_arities().len()
+    }
+
+    /// Estimate the bytes a sampler downloads for one codeword leaf
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Authentication path of `tree_depth` digests plus the leaf values
+    pub fn estimated_inclusion_proof_size(&self, fri_params: &FRIParams<P::Scalar>) -> usize {
+        let digest_len = std::mem::size_of::<digest::Output<H::Digest>>();
+        let leaf_len = (1 << fri_params.log_batch_size()) * std::mem::size_of::<P::Scalar>();
+        Self::merkle_tree_depth(fri_params) * digest_len + leaf_len
+    }
+
+    /// Estimate the transcript size of an evaluation proof from `prove`
+    ///
+    /// Counts the header, two coefficients per sumcheck round, one commitment
+    /// per fold round and the terminal codeword. Query proofs from `open` are
+    /// not included.
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Estimated length of the proof's `transcript_bytes`
+    pub fn estimated_fri_proof_size(&self, fri_params: &FRIParams<P::Scalar>) -> usize {
+        let digest_len = std::mem::size_of::<digest::Output<H::Digest>>();
+        let scalar_len = std::mem::size_of::<P::Scalar>();
+        let fold_arities = fri_params.fold_arities();
+
+        let header_len = digest_len + (1 + fold_arities.len()) * std::mem::size_of::<u64>();
+        let n_packed_vars = fri_params.rs_code().log_dim() + fri_params.log_batch_size();
+        let sumcheck_len = n_packed_vars * 2 * scalar_len;
+        let commitments_len = fold_arities.len() * digest_len;
+        let terminal_log_len = (fri_params.rs_code().log_len() + fri_params.log_batch_size())
+            .saturating_sub(fold_arities.iter().sum::<usize>());
+        let terminal_len = (1 << terminal_log_len) * scalar_len;
+
+        header_len + sumcheck_len + commitments_len + terminal_len
+    }
+
+    /// Position in the encoding transform that holds a data scalar
+    ///
+    /// `commit` bit-reverses the message over `log_dim + log_batch_size` bits
+    /// before the NTT, so data scalar `i` is the transform coefficient at the
+    /// bit-reversed position. The codeword is not systematic: the coefficient is
+    /// recovered by decoding the first `2^(log_dim + log_batch_size)` codeword
+    /// positions, and it lies in Merkle leaf `index >> log_batch_size`.
+    ///
+    /// # Arguments
+    /// * `logical_index` - Index of the scalar in the committed data
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Codeword position of the scalar's coefficient, or `None` when
+    /// `logical_index` is not below the message length
+    pub fn codeword_index_for(
+        &self,
+        logical_index: usize,
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Option<usize> {
+        let message_log_len = fri_params.rs_code().log_dim() + fri_params.log_batch_size();
+        if logical_index >= 1 << message_log_len {
+            return None;
+        }
+        if message_log_len == 0 {
+            return Some(0);
+        }
+        Some(logical_index.reverse_bits() >> (usize::BITS as usize - message_log_len))
+    }
+
+    /// Merkle leaf holding a codeword position
+    ///
+    /// Each leaf commits `2^log_batch_size` consecutive codeword scalars, so
+    /// the tree has fewer leaves than the codeword has scalars whenever
+    /// `log_batch_size > 0`. `inclusion_proof` and `verify_inclusion_proof`
+    /// take the leaf index and the full leaf returned here.
+    ///
+    /// # Arguments
+    /// * `codeword` - Committed codeword
+    /// * `codeword_index` - Position in the codeword
+    /// * `fri_params` - FRI protocol parameters the commitment was made with
+    ///
+    /// # Returns
+    /// Leaf index and the scalars of that leaf, or `None` when
+    /// `codeword_index` is out of range
+    pub fn leaf_for<'c>(
+        &self,
+        codeword: &'c [P::Scalar],
+        codeword_index: usize,
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Option<(usize, &'c [P::Scalar])> {
+        let log_batch_size = fri_params.log_batch_size();
+        if codeword_index >= codeword.len() {
+            return None;
+        }
+        let leaf_index = codeword_index >> log_batch_size;
+        let leaf_start = leaf_index << log_batch_size;
+        codeword
+            .get(leaf_start..leaf_start + (1 << log_batch_size))
+            .map(|leaf| (leaf_index, leaf))
+    }
+
+    /// Maximum number of erasures the Reed-Solomon code can recover from
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Codeword length minus code dimension, `n - k`
+    pub fn max_recoverable_erasures(&self, fri_params: &FRIParams<P::Scalar>) -> usize {
+        let rs_code = fri_params.rs_code();
+        let n = 1 << (rs_code.log_len() + fri_params.log_batch_size());
+        let k = 1 << (rs_code.log_dim() + fri_params.log_batch_size());
+        n - k
+    }
+
+    /// Minimum distance of the Reed-Solomon code
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Codeword length minus code dimension plus one, `d = n - k + 1`
+    pub fn code_distance(&self, fri_params: &FRIParams<P::Scalar>) -> usize {
+        self.max_recoverable_erasures(fri_params) + 1
+    }
+
+    /// Number of symbol errors the Reed-Solomon code can correct
+    ///
+    /// Errors at unknown positions cost twice as much as erasures, so this is
+    /// half of what `max_recoverable_erasures` allows.
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Correction capability `(d - 1) / 2`
+    pub fn error_correction_capability(&self, fri_params: &FRIParams<P::Scalar>) -> usize {
+        (self.code_distance(fri_params) - 1) / 2
+    }
+
+    /// Erase a random fraction of codeword positions, simulating lost samples
+    ///
+    /// # Arguments
+    /// * `codeword` - Codeword to erase positions of, modified in place
+    /// * `fraction` - Fraction of positions to erase, from 0.0 to 1.0
+    /// * `seed` - Seed for a reproducible erasure pattern, the current time if `None`
+    ///
+    /// # Returns
+    /// Indices of the zeroed positions, in sampling order
+    #[cfg(any(test, feature = "testing"))]
+    pub fn simulate_erasures(
+        &self,
+        codeword: &mut [P::Scalar],
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Vec<usize> {
+        let num_erasures =
+            ((codeword.len() as f64 * fraction.clamp(0.0, 1.0)) as usize).min(codeword.len());
+        if num_erasures == 0 {
+            return Vec::new();
+        }
+
+        let seed = seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+        });
+        let mut rng = StdRng::seed_from_u64(seed);
+        let erased = rand::seq::index::sample(&mut rng, codeword.len(), num_erasures).into_vec();
+
+        for &index in &erased {
+            codeword[index] = P::Scalar::zero();
+        }
+        erased
+    }
+
+    /// Code parameters of a FRI context
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Dimension, length, rate, batch size, leaf count and codeword length
+    pub fn code_info(&self, fri_params: &FRIParams<P::Scalar>) -> CodeInfo {
+        let rs_code = fri_params.rs_code();
+        let log_batch_size = fri_params.log_batch_size();
+        CodeInfo {
+            log_dim: rs_code.log_dim(),
+            log_len: rs_code.log_len(),
+            log_inv_rate: rs_code.log_inv_rate(),
+            log_batch_size,
+            num_leaves: 1 << Self::merkle_tree_depth(fri_params),
+            codeword_len: 1 << (rs_code.log_len() + log_batch_size),
+        }
+    }
+
+    /// Reed-Solomon code parameters for reconstructing outside of `FriVail`
+    ///
+    /// # Arguments
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Code dimension, length, rate and domain dimension of the codeword
+    pub fn reconstruction_params(&self, fri_params: &FRIParams<P::Scalar>) -> ReconstructionParams {
+        let rs_code = fri_params.rs_code();
+        let domain_dim = rs_code.log_len() + fri_params.log_batch_size();
+        ReconstructionParams {
+            dim: 1 << (rs_code.log_dim() + fri_params.log_batch_size()),
+            len: 1 << domain_dim,
+            log_inv_rate: rs_code.log_inv_rate(),
+            domain_dim,
+        }
+    }
+
+    /// Check whether a set of erasures can be recovered
+    ///
+    /// # Arguments
+    /// * `codeword_len` - Length of the codeword
+    /// * `corrupted_indices` - Indices of erased elements in the codeword
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Whether the codeword has the expected length, every index is in range,
+    /// and the distinct erasures do not exceed `max_recoverable_erasures`
+    pub fn can_reconstruct(
+        &self,
+        codeword_len: usize,
+        corrupted_indices: &[usize],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> bool {
+        let rs_code = fri_params.rs_code();
+        if codeword_len != 1 << (rs_code.log_len() + fri_params.log_batch_size()) {
+            return false;
+        }
+        if corrupted_indices.iter().any(|&i| i >= codeword_len) {
+            return false;
+        }
+
+        let erasures = corrupted_indices.iter().collect::<HashSet<_>>().len();
+        erasures <= self.max_recoverable_erasures(fri_params)
+    }
+
+    /// Reconstruct erased positions after checking they are recoverable
+    ///
+    /// # Arguments
+    /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
+    /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    /// * `fri_params` - FRI protocol parameters
+    ///
+    /// # Returns
+    /// Ok(()) if reconstruction succeeds
+    ///
+    /// # Errors
+    /// `FriVailError::Unrecoverable` when `can_reconstruct` fails, leaving the
+    /// codeword untouched
+    pub fn reconstruct_codeword_checked(
+        &self,
+        corrupted_codeword: &mut [P::Scalar],
+        corrupted_indices: &[usize],
+        fri_params: &FRIParams<P::Scalar>,
+    ) -> Result<(), FriVailError> {
+        if !self.can_reconstruct(corrupted_codeword.len(), corrupted_indices, fri_params) {
+            return Err(FriVailError::Unrecoverable {
+                erasures: corrupted_indices.len(),
+                max: self.max_recoverable_erasures(fri_params),
+            });
+        }
+
+        self.reconstruct_codeword_naive(corrupted_codeword, corrupted_indices, None)
+    }
+
+    /// Reconstruct erased positions and check the result against a commitment
+    ///
+    /// Interpolation trusts the known values, so a sampler supplying a wrong one
+    /// yields a well-formed but wrong codeword; recomputing the Merkle root catches
+    /// that.
+    ///
+    /// # Arguments
+    /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
+    /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    /// * `fri_params` - FRI protocol parameters
+    /// * `commitment` - Merkle root the codeword was committed under
+    ///
+    /// # Returns
+    /// Ok(()) if the reconstructed codeword has the committed root
+    ///
+    /// # Errors
+    /// `FriVailError::Unrecoverable` when there are too many erasures, or
+    /// `FriVailError::Merkle` when the reconstructed root differs from `commitment`
+    pub fn reconstruct_and_verify(
+        &self,
+        corrupted_codeword: &mut [P::Scalar],
+        corrupted_indices: &[usize],
+        fri_params: &FRIParams<P::Scalar>,
+        commitment: impl Into<CommitmentRoot>,
+    ) -> Result<(), FriVailError> {
+        self.reconstruct_codeword_checked(corrupted_codeword, corrupted_indices, fri_params)?;
+
+        let root = CommitmentRoot::from(self.codeword_root(corrupted_codeword, fri_params)?);
+        let commitment = commitment.into();
+        if root != commitment {
+            return Err(FriVailError::Merkle(format!(
+                "Reconstructed codeword has root {}, expected {}",
+                root, commitment
+            )));
+        }
+        Ok(())
+    }
+
+    /// Recover the data from sampled codeword openings
+    ///
+    /// Any `2^(log_dim + log_batch_size)` distinct positions determine the
+    /// codeword, so the first that many samples by index are interpolated to fill
+    /// in every other position before decoding. Samples should be verified
+    /// against the commitment first; a wrong value yields wrong data.
+    ///
+    /// # Arguments
+    /// * `samples` - Verified `(codeword index, value)` openings
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Decoded data, the same as `decode_codeword` of the full codeword
+    ///
+    /// # Errors
+    /// `FriVailError::InvalidParams` when an index is out of range or sampled
+    /// twice with different values, `FriVailError::Unrecoverable` when fewer
+    /// distinct positions than the code dimension are sampled, or a decoding error
+    pub fn reconstruct_from_samples(
+        &self,
+        samples: &[(usize, P::Scalar)],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> FieldResult<P> {
+        let info = self.code_info(fri_params);
+        let dimension = 1 << (info.log_dim + info.log_batch_size);
+
+        let mut distinct: HashMap<usize, P::Scalar> = HashMap::with_capacity(samples.len());
+        for &(index, value) in samples {
+            if index >= info.codeword_len {
+                return Err(FriVailError::InvalidParams(format!(
+                    "Sample index {} out of range for codeword of length {}",
+                    index, info.codeword_len
+                )));
+            }
+            if distinct
+                .insert(index, value)
+                .is_some_and(|other| other != value)
+            {
+                return Err(FriVailError::InvalidParams(format!(
+                    "Index {} sampled with two different values",
+                    index
+                )));
+            }
+        }
+        if distinct.len() < dimension {
+            return Err(FriVailError::Unrecoverable {
+                erasures: info.codeword_len - distinct.len(),
+                max: info.codeword_len - dimension,
+            });
+        }
+
+        let domain = codec::codeword_domain::<P::Scalar>(info.codeword_len);
+        let mut sampled: Vec<(usize, P::Scalar)> = distinct
+            .iter()
+            .map(|(&index, &value)| (index, value))
+            .collect();
+        sampled.sort_unstable_by_key(|&(index, _)| index);
+        let known: Vec<(P::Scalar, P::Scalar)> = sampled[..dimension]
+            .iter()
+            .map(|&(index, value)| (domain[index], value))
+            .collect();
+        let inverse_denominators = codec::lagrange_inverse_denominators(&known);
+
+        #[cfg(feature = "parallel")]
+        let positions = (0..info.codeword_len).into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let positions = 0..info.codeword_len;
+
+        let codeword: Vec<P::Scalar> = positions
+            .map(|index| match distinct.get(&index) {
+                Some(&value) => value,
+                None => codec::interpolate_with_denominators(
+                    domain[index],
+                    &known,
+                    &inverse_denominators,
+                ),
+            })
+            .collect();
+
+        self.decode_codeword(
+            &codeword,
+            fri_params.clone(),
+            ntt,
+            EncodingLayout::BitReversed,
+        )
+    }
+
+    /// Locate corrupted positions of a codeword without an erasure map
+    ///
+    /// Runs the decoder of `correct_errors` with the largest uniquely decodable
+    /// error count, `(n - k) / 2` for codeword length `n` and dimension `k`,
+    /// and leaves the codeword unchanged.
+    ///
+    /// # Arguments
+    /// * `codeword` - Codeword with possibly wrong values at unknown positions
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    ///
+    /// # Returns
+    /// Sorted indices of the wrong values, empty for a valid codeword
+    ///
+    /// # Errors
+    /// `FriVailError::Decode` when the codeword length does not match `fri_params`,
+    /// or `FriVailError::Unrecoverable` when more than `(n - k) / 2` values are wrong
+    pub fn detect_errors(
+        &self,
+        codeword: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+    ) -> Result<Vec<usize>, FriVailError> {
+        let max_errors = self.max_correctable_errors(fri_params);
+        let corrections = self.locate_errors(codeword, fri_params, ntt, max_errors)?;
+        Ok(corrections.into_iter().map(|(index, _)| index).collect())
+    }
+
+    /// Correct wrong values at unknown positions of a codeword
+    ///
+    /// Decodes with Berlekamp-Welch, which fixes up to `(n - k) / 2` errors for
+    /// codeword length `n` and dimension `k`. More errors than `max_errors` are
+    /// reported as unrecoverable rather than miscorrected, except in the rare case
+    /// that the received word lies within `max_errors` of another codeword.
+    ///
+    /// # Arguments
+    /// * `codeword` - Codeword to correct in place
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `max_errors` - Number of errors to correct, at most `(n - k) / 2`
+    ///
+    /// # Returns
+    /// Number of corrected positions
+    ///
+    /// # Errors
+    /// `FriVailError::InvalidParams` when `max_errors` exceeds `(n - k) / 2`,
+    /// `FriVailError::Decode` when the codeword length does not match `fri_params`,
+    /// or `FriVailError::Unrecoverable` when more than `max_errors` values are wrong
+    pub fn correct_errors(
+        &self,
+        codeword: &mut [P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        max_errors: usize,
+    ) -> Result<usize, FriVailError> {
+        let limit = self.max_correctable_errors(fri_params);
+        if max_errors > limit {
+            return Err(FriVailError::InvalidParams(format!(
+                "Cannot correct {} errors, the code corrects at most {}",
+                max_errors, limit
+            )));
+        }
+
+        let corrections = self.locate_errors(codeword, fri_params, ntt, max_errors)?;
+        for &(index, value) in &corrections {
+            codeword[index] = value;
+        }
+        Ok(corrections.len())
+    }
+
+    /// Largest number of errors at unknown positions the code can correct
+    fn max_correctable_errors(&self, fri_params: &FRIParams<P::Scalar>) -> usize {
+        let info = self.code_info(fri_params);
+        (info.codeword_len - (1 << (info.log_dim + info.log_batch_size))) / 2
+    }
+
+    /// Find the wrong positions of a codeword and their correct values
+    ///
+    /// A codeword that re-encodes to itself after decoding has no errors.
+    /// Otherwise Berlekamp-Welch finds an error locator `E` of degree
+    /// `max_errors` with `Q(x_i) = y_i E(x_i)` at every position. Positions where
+    /// `E` is nonzero are correct, so the data polynomial is interpolated from `k`
+    /// of them and compared against the roots of `E`. Solving the linear system
+    /// takes `O(n^3)` field operations, which limits this to small codewords.
+    fn locate_errors(
+        &self,
+        codeword: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        max_errors: usize,
+    ) -> Result<Vec<(usize, P::Scalar)>, FriVailError> {
+        let info = self.code_info(fri_params);
+        if codeword.len() != info.codeword_len {
+            return Err(FriVailError::Decode {
+                expected: info.codeword_len,
+                got: codeword.len(),
+            });
+        }
+
+        // Syndrome check: a valid codeword survives decoding and re-encoding
+        let decoded = self.decode_codeword(
+            codeword,
+            fri_params.clone(),
+            ntt,
+            EncodingLayout::BitReversed,
+        )?;
+        let mut reencoded = Vec::with_capacity(codeword.len());
+        self.encode_codeword_into(&decoded, fri_params, ntt, &mut reencoded)?;
+        if reencoded == codeword {
+            return Ok(Vec::new());
+        }
+
+        let n = codeword.len();
+        let dimension = 1 << (info.log_dim + info.log_batch_size);
+        let domain = codec::codeword_domain::<P::Scalar>(n);
+        let unrecoverable = FriVailError::Unrecoverable {
+            erasures: max_errors + 1,
+            max: max_errors,
+        };
+
+        let locator = Self::error_locator(codeword, &domain, dimension, max_errors)
+            .ok_or(unrecoverable.clone())?;
+
+        // Horner evaluation of the monic locator at every position
+        let is_root: Vec<bool> = domain
+            .iter()
+            .map(|&x| {
+                locator
+                    .iter()
+                    .rev()
+                    .fold(P::Scalar::ONE, |acc, &coeff| acc * x + coeff)
+                    == P::Scalar::zero()
+            })
+            .collect();
+
+        let known: Vec<(P::Scalar, P::Scalar)> = (0..n)
+            .filter(|&i| !is_root[i])
+            .take(dimension)
+            .map(|i| (domain[i], codeword[i]))
+            .collect();
+        if known.len() < dimension {
+            return Err(unrecoverable);
+        }
+        let inverse_denominators = codec::lagrange_inverse_denominators(&known);
+
+        let corrections: Vec<(usize, P::Scalar)> = (0..n)
+            .filter(|&i| is_root[i])
+            .map(|i| {
+                let value =
+                    codec::interpolate_with_denominators(domain[i], &known, &inverse_denominators);
+                (i, value)
+            })
+            .filter(|&(i, value)| value != codeword[i])
+            .collect();
+        if corrections.len() > max_errors {
+            return Err(FriVailError::Unrecoverable {
+                erasures: corrections.len(),
+                max: max_errors,
+            });
+        }
+        Ok(corrections)
+    }
+
+    /// Solve the Berlekamp-Welch system for the error locator
+    ///
+    /// The unknowns are the `dimension + max_errors` coefficients of `Q` and the
+    /// low `max_errors` coefficients of the monic `E`, one equation per position.
+    ///
+    /// # Returns
+    /// Coefficients of `E` below its leading one, or `None` if the system is inconsistent
+    fn error_locator(
+        codeword: &[P::Scalar],
+        domain: &[P::Scalar],
+        dimension: usize,
+        max_errors: usize,
+    ) -> Option<Vec<P::Scalar>> {
+        let q_len = dimension + max_errors;
+        let cols = q_len + max_errors;
+
+        // Row i: sum_j q_j x_i^j + y_i sum_j e_j x_i^j = y_i x_i^max_errors
+        let mut rows: Vec<Vec<P::Scalar>> = domain
+            .iter()
+            .zip(codeword)
+            .map(|(&x, &y)| {
+                let mut row = Vec::with_capacity(cols + 1);
+                let mut power = P::Scalar::ONE;
+                for _ in 0..q_len {
+                    row.push(power);
+                    power *= x;
+                }
+                power = P::Scalar::ONE;
+                for _ in 0..=max_errors {
+                    row.push(y * power);
+                    power *= x;
+                }
+                row
+            })
+            .collect();
+
+        let mut pivots = Vec::with_capacity(cols);
+        for col in 0..cols {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..rows.len()).find(|&r| rows[r][col] != P::Scalar::zero())
+            else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+            let inverse = rows[rank][col].invert().expect("Pivot is nonzero");
+            for value in &mut rows[rank][col..] {
+                *value *= inverse;
+            }
+
+            let pivot_row = rows[rank].clone();
+            #[cfg(feature = "parallel")]
+            let others = rows.par_iter_mut();
+            #[cfg(not(feature = "parallel"))]
+            let others = rows.iter_mut();
+            others.enumerate().for_each(|(r, row)| {
+                let factor = row[col];
+                if r != rank && factor != P::Scalar::zero() {
+                    for (value, &pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                        *value -= factor * pivot_value;
+                    }
+                }
+            });
+            pivots.push(col);
+        }
+
+        if rows[pivots.len()..]
+            .iter()
+            .any(|row| row[cols] != P::Scalar::zero())
+        {
+            return None;
+        }
+
+        // Free unknowns are set to zero
+        let mut solution = vec![P::Scalar::zero(); cols];
+        for (row, &col) in rows.iter().zip(&pivots) {
+            solution[col] = row[cols];
+        }
+        Some(solution.split_off(q_len))
+    }
+
+    /// Reconstruct erased positions in batches, stopping early when cancelled
+    ///
+    /// The token is checked before each batch of `RECONSTRUCTION_BATCH` positions.
+    /// On cancellation the codeword holds the values of every completed batch and
+    /// the remaining erased positions are untouched; known points are fixed up
+    /// front, so the written values match an uncancelled run.
+    ///
+    /// # Arguments
+    /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
+    /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    /// * `token` - Cancellation token
+    ///
+    /// # Returns
+    /// Ok(()) if every position was reconstructed
+    ///
+    /// # Errors
+    /// `FriVailError::Cancelled` when the token is cancelled, or
+    /// `FriVailError::Reconstruction` when no known points are available
+    pub fn reconstruct_codeword_naive_cancellable(
+        &self,
+        corrupted_codeword: &mut [P::Scalar],
+        corrupted_indices: &[usize],
+        token: &CancellationToken,
+    ) -> Result<(), FriVailError> {
+        if corrupted_indices.is_empty() {
+            return Ok(());
+        }
+
+        let domain = codec::codeword_domain::<P::Scalar>(corrupted_codeword.len());
+        let known = codec::known_points(corrupted_codeword, &domain, corrupted_indices)?;
+        let inverse_denominators = codec::lagrange_inverse_denominators(&known);
+        for batch in corrupted_indices.chunks(RECONSTRUCTION_BATCH) {
+            token.check()?;
+
+            #[cfg(feature = "parallel")]
+            let batch_iter = batch.par_iter();
+            #[cfg(not(feature = "parallel"))]
+            let batch_iter = batch.iter();
+
+            let values: Vec<(usize, P::Scalar)> = batch_iter
+                .map(|&missing| {
+                    let x_e = domain[missing];
+                    (
+                        missing,
+                        codec::interpolate_with_denominators(x_e, &known, &inverse_denominators),
+                    )
+                })
+                .collect();
+            for (missing, value) in values {
+                corrupted_codeword[missing] = value;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reconstruct erased positions for up to `max_duration`, resumable from a checkpoint
+    ///
+    /// Each pass reconstructs at least one position, so repeated passes always
+    /// finish. Positions already reconstructed stay excluded from the known
+    /// points, giving the same result as `reconstruct_codeword_naive`.
+    ///
+    /// # Arguments
+    /// * `state` - Checkpoint from `ReconstructionState::new` or a previous pass
+    /// * `codeword` - Codeword the reconstructed values are written to
+    /// * `max_duration` - Time budget for this pass
+    ///
+    /// # Returns
+    /// `Done` when finished, otherwise `Incomplete` with the checkpoint to resume from
+    ///
+    /// # Errors
+    /// When an erased position is out of range or no known points remain
+    #[cfg(feature = "std")]
+    pub fn reconstruct_codeword_resumable(
+        &self,
+        mut state: ReconstructionState,
+        codeword: &mut [P::Scalar],
+        max_duration: Duration,
+    ) -> Result<ReconstructionProgress, FriVailError> {
+        if let Some(&index) = state.erased.iter().find(|&&i| i >= codeword.len()) {
+            return Err(FriVailError::InvalidParams(format!(
+                "Erased index {} out of range for codeword of length {}",
+                index,
+                codeword.len()
+            )));
+        }
+        if state.is_done() {
+            return Ok(ReconstructionProgress::Done);
+        }
+
+        let start = Instant::now();
+        let domain = codec::codeword_domain::<P::Scalar>(codeword.len());
+        let known = codec::known_points(codeword, &domain, &state.erased)?;
+        let inverse_denominators = codec::lagrange_inverse_denominators(&known);
+        while !state.is_done() {
+            let missing = state.erased[state.completed];
+            codeword[missing] = codec::interpolate_with_denominators(
+                domain[missing],
+                &known,
+                &inverse_denominators,
+            );
+            state.completed += 1;
+
+            if start.elapsed() >= max_duration {
+                break;
+            }
+        }
+
+        if state.is_done() {
+            Ok(ReconstructionProgress::Done)
+        } else {
+            Ok(ReconstructionProgress::Incomplete(state))
+        }
+    }
+
+    /// Check a reconstructed codeword against freshly fetched samples
+    ///
+    /// # Arguments
+    /// * `reconstructed` - Codeword after reconstruction
+    /// * `fresh_samples` - `(index, value)` samples fetched after reconstruction
+    ///
+    /// # Returns
+    /// Ok(()) if every sample matches the reconstructed codeword
+    ///
+    /// # Errors
+    /// On the first sample that is out of range or differs from the reconstruction
+    pub fn spot_check_reconstruction(
+        &self,
+        reconstructed: &[P::Scalar],
+        fresh_samples: &[(usize, P::Scalar)],
+    ) -> Result<(), FriVailError> {
+        for &(index, value) in fresh_samples {
+            match reconstructed.get(index) {
+                Some(&reconstructed_value) if reconstructed_value == value => {}
+                Some(reconstructed_value) => {
+                    return Err(FriVailError::Verify(format!(
+                        "Spot check failed at index {}: reconstructed {:?}, sampled {:?}",
+                        index, reconstructed_value, value
+                    )));
+                }
+                None => {
+                    return Err(FriVailError::InvalidParams(format!(
+                        "Spot check index {} out of range for codeword of length {}",
+                        index,
+                        reconstructed.len()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a point set for `prove_multi` is non-empty and of one dimension
+    fn check_multi_points(points: &[Vec<P::Scalar>]) -> Result<(), FriVailError> {
+        let Some(first) = points.first() else {
+            return Err(FriVailError::InvalidParams(
+                "At least one evaluation point is required".into(),
+            ));
+        };
+        if let Some(i) = points.iter().position(|p| p.len() != first.len()) {
+            return Err(FriVailError::InvalidParams(format!(
+                "Point {} has {} coordinates, expected {}",
+                i,
+                points[i].len(),
+                first.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Degree of the polynomial restricted to the curve through `points`
+    fn multi_point_degree(points: &[Vec<P::Scalar>]) -> usize {
+        points[0].len() * (points.len() - 1)
+    }
+
+    /// Lagrange basis over the nodes `0..num_nodes` evaluated at `t`
+    fn lagrange_weights(num_nodes: usize, t: P::Scalar) -> Vec<P::Scalar> {
+        let nodes: Vec<P::Scalar> = (0..num_nodes).map(|i| P::Scalar::from(i as u128)).collect();
+        nodes
+            .iter()
+            .enumerate()
+            .map(|(j, &x_j)| {
+                nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(m, _)| m != j)
+                    .fold(P::Scalar::ONE, |acc, (_, &x_m)| {
+                        acc * (t - x_m) * (x_j - x_m).invert().unwrap()
+                    })
+            })
+            .collect()
+    }
+
+    /// Point at `t` on the curve taking the value `points[i]` at node `i`
+    fn curve_point(points: &[Vec<P::Scalar>], t: P::Scalar) -> Vec<P::Scalar> {
+        let weights = Self::lagrange_weights(points.len(), t);
+        (0..points[0].len())
+            .map(|v| {
+                inner_product::<P::Scalar>(points.iter().map(|p| p[v]), weights.iter().copied())
+            })
+            .collect()
+    }
+
+    /// Batching challenge of a multi-point proof
+    fn multi_point_challenge(
+        root: &digest::Output<H::Digest>,
+        points: &[Vec<P::Scalar>],
+        evaluations: &[P::Scalar],
+    ) -> P::Scalar {
+        let mut transcript = ProverTranscript::new(StdChallenger::default());
+        transcript.message().write_bytes(MULTI_POINT_TAG);
+        transcript.message().write(root);
+        for point in points {
+            for coordinate in point {
+                transcript.message().write(coordinate);
+            }
+        }
+        for evaluation in evaluations {
+            transcript.message().write(evaluation);
+        }
+        transcript.sample()
+    }
+
+    /// Compute Lagrange interpolation at a specific point, rejecting degenerate inputs
+    ///
+    /// Same result as the interpolation used by reconstruction, but a repeated
+    /// x-coordinate is reported instead of panicking, so `known` may come from
+    /// an untrusted source.
+    ///
+    /// Timing: the loops run `k * (k - 1)` iterations whatever the values, and the
+    /// y-coordinates never affect control flow. Field inversion is not guaranteed
+    /// to be constant time, and the error return leaves early, so the timing may
+    /// reveal the x-coordinates and the position of a duplicate; these are public
+    /// in erasure reconstruction.
+    ///
+    /// # Arguments
+    /// * `x_e` - Point to evaluate the interpolating polynomial at
+    /// * `known` - Known `(x, y)` points
+    /// * `k` - Number of leading points of `known` to interpolate through
+    ///
+    /// # Returns
+    /// Value at `x_e` of the polynomial of degree below `k` through the points
+    ///
+    /// # Errors
+    /// `FriVailError::Reconstruction` when `known` has fewer than `k` points, or
+    /// `FriVailError::DuplicatePoint` when two of the points share an x-coordinate
+    pub fn interpolate_at_point_checked(
+        x_e: P::Scalar,
+        known: &[(P::Scalar, P::Scalar)],
+        k: usize,
+    ) -> Result<P::Scalar, FriVailError> {
+        if known.len() < k {
+            return Err(FriVailError::Reconstruction {
+                known_points: known.len(),
+            });
+        }
+
+        let mut value = P::Scalar::zero();
+        for j in 0..k {
+            let (x_j, y_j) = known[j];
+            let mut l_j = P::Scalar::ONE;
+            for m in 0..k {
+                if m == j {
+                    continue;
+                }
+                let (x_m, _) = known[m];
+                let inverse = (x_j - x_m).invert().ok_or(FriVailError::DuplicatePoint {
+                    first: j.min(m),
+                    second: j.max(m),
+                })?;
+                l_j = l_j * (x_e - x_m) * inverse;
+            }
+            value = value + y_j * l_j;
+        }
+        Ok(value)
+    }
+}
+
+impl<'a, P, VCS, NTT, H> FriVailSampling<P, NTT, H> for FriVail<'a, P, VCS, NTT, H>
+where
+    NTT: AdditiveNTT<Field = B128> + Sync,
+    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
+    VCS: MerkleTreeScheme<P::Scalar>,
+    H: MerkleHash<Scheme = VCS>,
+{
+    /// Decode a Reed-Solomon codeword with error correction for missing points
+    ///
+    /// The erasure count is not checked against the code's `n - k` bound, so
+    /// too many erasures yield a wrong codeword; use `reconstruct_codeword_checked`
+    /// to reject them with `FriVailError::Unrecoverable` first.
+    ///
+    /// # Arguments
+    /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
+    /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    /// * `progress` - Called with `(done, total)` erased positions every
+    ///   `PROGRESS_INTERVAL` positions and once at the end
+    ///
+    /// # Returns
+    /// Ok(()) if reconstruction succeeds
+    ///
+    /// # Errors
+    /// When no known points are available for reconstruction
+    fn reconstruct_codeword_naive(
+        &self,
+        corrupted_codeword: &mut [P::Scalar],
+        corrupted_indices: &[usize],
+        progress: Option<&dyn Fn(usize, usize)>,
+    ) -> Result<(), FriVailError> {
+        if corrupted_indices.is_empty() {
+            return Ok(());
+        }
+        // Same evaluation points as the encoder's NTT
+        let domain = codec::codeword_domain::<P::Scalar>(corrupted_codeword.len());
+
+        // Collect known points (x_j, y_j)
+        let known = codec::known_points(corrupted_codeword, &domain, corrupted_indices)?;
+        // The denominators depend only on the known points, so compute them once
+        let inverse_denominators = codec::lagrange_inverse_denominators(&known);
+
+        // Without a callback every position goes into a single batch
+        let total = corrupted_indices.len();
+        let batch_len = progress.map_or(total, |_| PROGRESS_INTERVAL);
+        let mut done = 0;
+        for batch in corrupted_indices.chunks(batch_len) {
+            // For each erased position, interpolate and evaluate
+            #[cfg(feature = "parallel")]
+            {
+                // Parallel version using rayon
+                let reconstructed_values: Vec<(usize, P::Scalar)> = batch
+                    .par_iter()
+                    .map(|&missing| {
+                        debug!("Calculating value for missing index: {}", missing);
+                        let x_e = domain[missing];
+                        let value = codec::interpolate_with_denominators(
+                            x_e,
+                            &known,
+                            &inverse_denominators,
+                        );
+
+                        debug!(
+                            "Reconstructed value for missing index {}: {:?}",
+                            missing, value
+                        );
+                        (missing, value)
+                    })
+                    .collect();
+
+                // Apply the reconstructed values to the codeword
+                for (missing, value) in reconstructed_values {
+                    corrupted_codeword[missing] = value;
+                }
+            }
+
+            #[cfg(not(feature = "parallel"))]
+            {
+                // Sequential version
+                for &missing in batch {
+                    debug!("Calculating value for missing index: {}", missing);
+                    let x_e = domain[missing];
+                    let value =
+                        codec::interpolate_with_denominators(x_e, &known, &inverse_denominators);
+
+                    debug!(
+                        "Reconstructed value for missing index {}: {:?}",
+                        missing, value
+                    );
+                    corrupted_codeword[missing] = value;
+                }
+            }
+
+            done += batch.len();
+            if let Some(progress) = progress {
+                progress(done, total);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reconstruct a corrupted codeword with additive-NTT erasure decoding
+    ///
+    /// Runs `codec::reconstruct_erasures_fft` with this instance's NTT thread count.
+    ///
+    /// # Arguments
+    /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
+    /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    ///
+    /// # Returns
+    /// Ok(()) if reconstruction succeeds
+    ///
+    /// # Errors
+    /// When the codeword length is not a power of two, an index is out of range,
+    /// or no known points are available
+    fn reconstruct_codeword_fft(
+        &self,
+        corrupted_codeword: &mut [P::Scalar],
+        corrupted_indices: &[usize],
+    ) -> Result<(), FriVailError> {
+        codec::reconstruct_erasures_fft(corrupted_codeword, corrupted_indices, self.log_num_shares)
+    }
+
+    /// Reconstruct only selected erased positions of a codeword
+    ///
+    /// # Arguments
+    /// * `codeword` - Codeword with erased positions
+    /// * `erased` - Indices of erased elements in the codeword
+    /// * `wanted` - Indices whose values should be recovered
+    ///
+    /// # Returns
+    /// `(index, value)` pairs in the order of `wanted`
+    ///
+    /// # Errors
+    /// When a wanted index is out of range or no known points are available
+    fn reconstruct_positions(
+        &self,
+        codeword: &[P::Scalar],
+        erased: &[usize],
+        wanted: &[usize],
+    ) -> Result<Vec<(usize, P::Scalar)>, FriVailError> {
+        if let Some(&index) = wanted.iter().find(|&&i| i >= codeword.len()) {
+            return Err(FriVailError::InvalidParams(format!(
+                "Wanted index {} out of range for codeword of length {}",
+                index,
+                codeword.len()
+            )));
+        }
+
+        let domain = codec::codeword_domain::<P::Scalar>(codeword.len());
+        let known = codec::known_points(codeword, &domain, erased)?;
+        let inverse_denominators = if wanted.iter().any(|index| erased.contains(index)) {
+            codec::lagrange_inverse_denominators(&known)
+        } else {
+            Vec::new()
+        };
+
+        Ok(wanted
+            .iter()
+            .map(|&index| {
+                if erased.contains(&index) {
+                    let x_e = domain[index];
+                    (
+                        index,
+                        codec::interpolate_with_denominators(x_e, &known, &inverse_denominators),
+                    )
+                } else {
+                    // Known positions need no interpolation
+                    (index, codeword[index])
+                }
+            })
+            .collect())
+    }
+
+    /// Verify an evaluation proof for the committed polynomial
+    ///
+    /// # Arguments
+    /// * `verifier_transcript` - Verifier transcript containing the proof
+    /// * `evaluation_claim` - Claimed evaluation result
+    /// * `evaluation_point` - Point at which polynomial was evaluated
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `extra_index` - Optional index for extra query verification
+    /// * `terminate_codeword` - Optional terminal codeword from `prove`
+    /// * `layers` - Optional Merkle tree layers for verification
+    /// * `extra_transcript` - Optional extra transcript for query verification
+    ///
+    /// # Returns
+    /// Ok(()) if verification succeeds
+    ///
+    /// # Errors
+    /// When verification fails due to invalid proof or parameters
+    fn verify(
+        &self,
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+        evaluation_claim: P::Scalar,
+        evaluation_point: &[P::Scalar],
+        fri_params: &FRIParams<P::Scalar>,
+        ntt: &NTT,
+        extra_index: Option<usize>,
+        terminate_codeword: Option<&TerminalCodeword<P::Scalar>>,
+        layers: Option<&[Vec<digest::Output<H::Digest>>]>,
+        extra_transcript: Option<&mut VerifierTranscript<StdChallenger>>,
+    ) -> Result<(), FriVailError> {
+        self.verify_recorded(
+            verifier_transcript,
+            evaluation_claim,
+            evaluation_point,
+            fri_params,
+            ntt,
+            extra_index,
+            terminate_codeword,
+            layers,
+            extra_transcript,
+            &[],
+            None,
+        )
+    }
+
+    /// Generate a Merkle inclusion proof for a specific codeword position
+    ///
+    /// # Arguments
+    /// * `committed` - Committed Merkle tree
+    /// * `index` - Index in the codeword to generate proof for
+    ///
+    /// # Returns
+    /// Verifier transcript containing the inclusion proof
+    ///
+    /// # Errors
+    /// When proof generation fails
+    fn inclusion_proof(
+        &self,
+        committed: &<H::Prover as MerkleTreeProver<B128>>::Committed,
+        index: usize,
+    ) -> TranscriptResult {
+        let mut proof_writer = ProverTranscript::new(StdChallenger::default());
+        self.merkle_prover
+            .prove_opening(committed, 0, index, &mut proof_writer.message())
+            .map_err(|e| FriVailError::Merkle(e.to_string()))?;
+
+        let proof_reader = proof_writer.into_verifier();
+
+        Ok(proof_reader)
+    }
+
+    /// Open a commitment at a specific index using FRI query prover
+    ///
+    /// # Arguments
+    /// * `index` - Index in the codeword to open
+    /// * `commitment` - Codeword commitment the query prover was built from
+    /// * `query_prover` - FRI query prover instance
+    ///
+    /// # Returns
+    /// Verifier transcript containing the opening proof
+    ///
+    /// # Errors
+    /// `FriVailError::InvalidParams` when `commitment` is not the root the query
+    /// prover was built from, or `FriVailError::Prove` when opening fails
+    fn open<'b>(
+        &self,
+        index: usize,
+        commitment: &digest::Output<H::Digest>,
+        query_prover: &FRIQueryProverAlias<'b, P, H>,
+    ) -> TranscriptResult {
+        // The first optimal layer is the codeword tree cut at its own depth, so
+        // it only verifies against the root the query prover committed to
+        let layers = query_prover
+            .vcs_optimal_layers()
+            .map_err(|e| FriVailError::Prove(e.to_string()))?;
+        let codeword_layer = layers
+            .first()
+            .ok_or_else(|| FriVailError::Prove("Query prover has no codeword layer".into()))?;
+        self.merkle_scheme()
+            .verify_layer(
+                commitment,
+                codeword_layer.len().ilog2() as usize,
+                codeword_layer,
+            )
+            .map_err(|_| {
+                FriVailError::InvalidParams("Commitment does not match the query prover".into())
+            })?;
+
+        // Create new transcript for the query proof
+        let mut proof_transcript = ProverTranscript::new(StdChallenger::default());
+
+        // Label the opening with its commitment; see the trait docs on binding
+        proof_transcript.message().write(commitment);
+        proof_transcript.message().write_bytes(OPEN_CONTEXT_TAG);
+
+        let mut advice = proof_transcript.decommitment();
+
+        // Generate proof for specific index
+        query_prover
+            .prove_query(index, &mut advice)
+            .map_err(|e| FriVailError::Prove(e.to_string()))?;
+
+        // Return verifier transcript
+        Ok(proof_transcript.into_verifier())
+    }
+
+    /// Verify a Merkle inclusion proof for a codeword value
+    ///
+    /// # Arguments
+    /// * `verifier_transcript` - Verifier transcript containing the inclusion proof
+    /// * `data` - Leaf values, `2^log_batch_size` scalars per leaf
+    /// * `index` - Index in the codeword
+    /// * `fri_params` - FRI protocol parameters
+    /// * `commitment` - Merkle tree root commitment
+    ///
+    /// # Returns
+    /// Ok(()) if inclusion proof is valid
+    ///
+    /// # Errors
+    /// `FriVailError::Decode` when `data` is not one full leaf, or
+    /// `FriVailError::Verification` when inclusion proof verification fails
+    fn verify_inclusion_proof(
+        &self,
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+        data: &[P::Scalar],
+        index: usize,
+        fri_params: &FRIParams<P::Scalar>,
+        commitment: impl Into<CommitmentRoot>,
+    ) -> Result<(), FriVailError> {
+        let leaf_width = 1 << fri_params.log_batch_size();
+        if data.len() != leaf_width {
+            return Err(FriVailError::Decode {
+                expected: leaf_width,
+                got: data.len(),
+            });
+        }
+
+        Ok(verifier::verify_leaf_opening(
+            self.merkle_scheme(),
+            verifier_transcript,
+            data,
+            index,
+            Self::merkle_tree_depth(fri_params),
+            commitment.into().into(),
+        )?)
+    }
+
+    /// Decode a Reed-Solomon encoded codeword back to original data
+    ///
+    /// # Arguments
+    /// * `codeword` - Encoded codeword to decode
+    /// * `fri_params` - FRI protocol parameters
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `layout` - Order the codeword was encoded in
+    ///
+    /// # Returns
+    /// Decoded packed field values
+    ///
+    /// # Errors
+    /// When decoding fails
+    fn decode_codeword(
+        &self,
+        codeword: &[P::Scalar],
+        fri_params: FRIParams<P::Scalar>,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        layout: EncodingLayout,
+    ) -> FieldResult<P> {
+        let rs_code = fri_params.rs_code();
+        let len = 1 << (rs_code.log_len() + fri_params.log_batch_size() - P::LOG_WIDTH);
+
+        let mut decoded = Vec::with_capacity(len);
+        let initialized = self.decode_batch(
+            rs_code.log_len(),
+            rs_code.log_inv_rate(),
+            fri_params.log_batch_size(),
+            ntt,
+            codeword.as_ref(),
+            &mut decoded.spare_capacity_mut()[..len],
+        )?;
+
+        unsafe {
+            // Safety: decode_batch initialized the first `initialized` elements
+            decoded.set_len(initialized);
+        }
+
+        // Trim to original data size (remove redundancy)
+        let trim_len = 1 << (rs_code.log_dim() + fri_params.log_batch_size() - P::LOG_WIDTH);
+        decoded.resize(trim_len, P::Scalar::zero());
+
+        // Undo bit-reversal that encode_batch applied internally
+        if layout == EncodingLayout::BitReversed {
+            let data_log_len = rs_code.log_dim() + fri_params.log_batch_size();
+            bit_reverse_packed(FieldSliceMut::from_slice(
+                data_log_len,
+                decoded.as_mut_slice(),
+            ));
+        }
+
+        Ok(decoded)
+    }
+
+    /// Extract commitment from verifier transcript
+    ///
+    /// # Arguments
+    /// * `verifier_transcript` - Verifier transcript to extract commitment from
+    ///
+    /// # Returns
+    /// Commitment bytes
+    ///
+    /// # Errors
+    /// When commitment extraction fails
+    #[allow(dead_code)]
+    fn extract_commitment(
+        &self,
+        verifier_transcript: &mut VerifierTranscript<StdChallenger>,
+    ) -> ByteResult {
+        verifier_transcript
+            .message()
+            .read()
+            .map_err(|e| FriVailError::Transcript(e.to_string()))
+    }
+
+    /// Low-level batch decoding using inverse NTT
+    ///
+    /// # Arguments
+    /// * `log_len` - Logarithm of dimension
+    /// * `log_inv` - Logarithm of inverse rate
+    /// * `log_batch_size` - Logarithm of batch size
+    /// * `ntt` - Number Theoretic Transform instance
+    /// * `data` - Input data to decode
+    /// * `output` - Output buffer for decoded data
+    ///
+    /// # Returns
+    /// Ok(()) if decoding succeeds
+    ///
+    /// # Errors
+    /// When decoding fails due to invalid parameters
+    fn decode_batch(
+        &self,
+        log_len: usize,
+        log_inv: usize,
+        log_batch_size: usize,
+        ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        data: &[P::Scalar],
+        output: &mut [MaybeUninit<P::Scalar>],
+    ) -> Result<usize, FriVailError> {
+        inverse_rs_decode(log_len, log_inv, log_batch_size, ntt, data, output)?;
+        Ok(data.len())
+    }
+}
+
+impl<'a, P, VCS, NTT, H> FriVailUtils for FriVail<'a, P, VCS, NTT, H>
+where
+    NTT: AdditiveNTT<Field = B128> + Sync,
+    P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
+    VCS: MerkleTreeScheme<P::Scalar>,
+    H: MerkleHash<Scheme = VCS>,
+{
+    fn get_transcript_bytes(&self, transcript: &VerifierTranscript<StdChallenger>) -> Vec<u8> {
+        let mut cloned = transcript.clone();
+        let mut message_reader = cloned.message();
+        let buffer = message_reader.buffer();
+        let remaining = buffer.remaining();
+
+        if remaining == 0 {
+            return Vec::new();
+        }
+
+        // Read all remaining bytes
+        let mut bytes = vec![0u8; remaining];
+        buffer.copy_to_slice(&mut bytes);
+        bytes
+    }
+    fn reconstruct_transcript_from_bytes(
+        &self,
+        bytes: Vec<u8>,
+    ) -> VerifierTranscript<StdChallenger> {
+        VerifierTranscript::new(StdChallenger::default(), bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::poly::Utils;
+    use crate::testing::{synthetic_blob, DataPattern};
+    use binius_math::ntt::{domain_context::GenericPreExpanded, NeighborsLastMultiThread};
+    use binius_prover::hash::parallel_compression::ParallelCompressionAdaptor;
+    use binius_prover::merkle_tree::prover::BinaryMerkleTreeProver;
+    use binius_verifier::{
+        config::B128,
+        hash::{StdCompression, StdDigest},
+        merkle_tree::BinaryMerkleTreeScheme,
+    };
+    use sha3::Sha3_256;
+    use std::time::Instant;
+
+    fn create_test_data(size_bytes: usize) -> Vec<u8> {
+        synthetic_blob(size_bytes, DataPattern::Sequential)
+    }
+
+    #[test]
+    fn test_friveil_new() {
+        const LOG_INV_RATE: usize = 1;
+        const NUM_TEST_QUERIES: usize = 3;
+        const N_VARS: usize = 10;
+        const LOG_NUM_SHARES: usize = 2;
+
+        let friVail = TestFriVail::new(LOG_INV_RATE, NUM_TEST_QUERIES, 2, N_VARS, LOG_NUM_SHARES);
+
+        assert_eq!(friVail.log_inv_rate, LOG_INV_RATE);
+        assert_eq!(friVail.num_test_queries, NUM_TEST_QUERIES);
+        assert_eq!(friVail.n_vars, N_VARS);
+        assert_eq!(friVail.log_num_shares, LOG_NUM_SHARES);
+    }
+
+    #[test]
+    fn test_builder_happy_path() {
+        let friVail = TestFriVail::builder()
+            .log_inv_rate(1)
+            .num_test_queries(3)
+            .arity(2)
+            .data_len(1024)
+            .log_num_shares(3)
+            .build()
+            .expect("Failed to build FriVail");
+
+        assert_eq!(friVail.log_inv_rate, 1);
+        assert_eq!(friVail.num_test_queries, 3);
+        assert_eq!(friVail.arity, 2);
+        assert_eq!(friVail.n_vars, 6);
+        assert_eq!(friVail.log_num_shares, 3);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_config() {
+        let valid = || TestFriVail::builder().num_test_queries(3).n_vars(6);
+
+        for result in [
+            valid().arity(0).build(),
+            valid().num_test_queries(0).build(),
+            valid().log_inv_rate(0).build(),
+            valid().data_len(4096).build(),
+            TestFriVail::builder().num_test_queries(3).build(),
+        ] {
+            assert!(matches!(result, Err(FriVailError::InvalidConfig(_))));
+        }
+    }
+
+    #[test]
+    fn test_fri_context_memory_limit() {
+        let friVail = TestFriVail::new(1, 3, 2, 10, 2).with_max_memory(1 << 30);
+
+        assert_eq!(friVail.estimate_fri_context_memory(10), (1 << 11) * 16);
+        assert_eq!(friVail.estimate_fri_context_memory(usize::MAX), usize::MAX);
+
+        // Rejected from the estimate alone, before any domain is allocated
+        let result = friVail.initialize_fri_context(60);
+        assert!(matches!(
+            result,
+            Err(FriVailError::ContextTooLarge { max, .. }) if max == 1 << 30
+        ));
+
+        assert!(friVail.initialize_fri_context(10).is_ok());
+    }
+
+    #[test]
+    fn test_assert_secure() {
+        const N_VARS: usize = 10;
+
+        let insecure = TestFriVail::new(1, 3, 2, N_VARS, 2);
+        let (fri_params, _ntt) = insecure
+            .initialize_fri_context(N_VARS)
+            .expect("Failed to initialize FRI context");
+        assert!(matches!(
+            insecure.assert_secure(&fri_params, 80.0),
+            Err(FriVailError::InsufficientSecurity { .. })
+        ));
+
+        let secure = TestFriVail::new(1, 128, 2, N_VARS, 2);
+        let (fri_params, _ntt) = secure
+            .initialize_fri_context(N_VARS)
+            .expect("Failed to initialize FRI context");
+        assert!(secure.assert_secure(&fri_params, 80.0).is_ok());
+        assert!(secure.security_bits() >= 80.0);
+    }
+
+    #[test]
+    fn test_security_bits_monotone_in_queries() {
+        let bits = |queries| TestFriVail::new(1, queries, 2, 10, 2).security_bits();
+        for queries in 1..128 {
+            assert!(bits(queries + 1) > bits(queries));
+        }
+
+        // The integration test configuration
+        assert_eq!(bits(128), 128.0);
+
+        let friVail = TestFriVail::new(2, 3, 2, 10, 2);
+        assert_eq!(friVail.min_queries_for_bits(100.0), 50);
+        assert_eq!(friVail.min_queries_for_bits(101.0), 51);
+        assert!(TestFriVail::new(2, 50, 2, 10, 2).security_bits() >= 100.0);
+    }
+
+    #[test]
+    fn test_calculate_evaluation_point_random() {
+        const N_VARS: usize = 8;
+        let friVail = TestFriVail::new(1, 3, 2, N_VARS, 2);
+
+        let result = friVail.calculate_evaluation_point_random();
+        assert!(result.is_ok());
+
+        let evaluation_point = result.unwrap();
+        assert_eq!(evaluation_point.len(), N_VARS);
+
+        // Test deterministic behavior with fixed seed
+        let result2 = friVail.calculate_evaluation_point_random();
+        assert!(result2.is_ok());
+        let evaluation_point2 = result2.unwrap();
+        assert_eq!(evaluation_point, evaluation_point2);
+    }
+
+    #[test]
+    fn test_evaluation_point_with_rng() {
+        let friVail = TestFriVail::new(1, 3, 2, 12, 3);
+
+        let point_a = friVail
+            .calculate_evaluation_point_with_rng(&mut StdRng::seed_from_u64(1))
+            .expect("Failed to generate evaluation point");
+        let point_a_again = friVail
+            .calculate_evaluation_point_with_rng(&mut StdRng::seed_from_u64(1))
+            .expect("Failed to generate evaluation point");
+        let point_b = friVail
+            .calculate_evaluation_point_with_rng(&mut StdRng::seed_from_u64(2))
+            .expect("Failed to generate evaluation point");
+
+        assert_eq!(point_a.len(), 12);
+        assert_eq!(point_a, point_a_again);
+        assert_ne!(point_a, point_b);
+
+        // The fixed-seed method is the zero-seeded special case
+        assert_eq!(
+            friVail.calculate_evaluation_point_random(),
+            friVail.calculate_evaluation_point_with_rng(&mut StdRng::from_seed([0; 32]))
+        );
+    }
+
+    #[test]
+    fn test_evaluation_point_from_transcript() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let n_vars = packed_mle_values.packed_mle.log_len();
+
+        let friVail = TestFriVail::new(1, 3, 2, n_vars, 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(packed_mle_values.packed_mle.clone(), fri_params, &ntt)
+            .expect("Failed to commit");
+
+        let mut prover_transcript = ProverTranscript::new(StdChallenger::default());
+        prover_transcript.message().write(&commit_output.commitment);
+        let prover_point = friVail
+            .calculate_evaluation_point_from_transcript(&mut prover_transcript)
+            .expect("Failed to derive evaluation point");
+        let transcript_bytes = prover_transcript.finalize();
+
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
+        let commitment: digest::Output<StdDigest> = verifier_transcript
+            .message()
+            .read()
+            .expect("Failed to read commitment");
+        assert_eq!(commitment, commit_output.commitment);
+        let verifier_point = friVail
+            .calculate_evaluation_point_from_transcript(&mut verifier_transcript)
+            .expect("Failed to derive evaluation point");
+
+        assert_eq!(prover_point.len(), n_vars);
+        assert_eq!(prover_point, verifier_point);
+        assert_ne!(
+            prover_point,
+            friVail
+                .calculate_evaluation_point_random()
+                .expect("Failed to generate evaluation point")
+        );
+    }
+
+    #[test]
+    fn test_evaluation_point_from_beacon() {
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let n_vars = packed_mle_values.packed_mle.log_len();
+        let friVail = TestFriVail::new(1, 3, 2, n_vars, 3);
+
+        let beacon_output = [0xabu8; 96];
+        let evaluation_point = friVail
+            .calculate_evaluation_point_from_beacon(&beacon_output)
+            .expect("Failed to derive evaluation point");
+        assert_eq!(evaluation_point.len(), n_vars);
+        assert_eq!(
+            friVail.calculate_evaluation_point_from_beacon(&beacon_output),
+            Ok(evaluation_point.clone())
+        );
+        assert_ne!(
+            friVail.calculate_evaluation_point_from_beacon(&[0xacu8; 96]),
+            Ok(evaluation_point.clone())
+        );
+        assert!(friVail.calculate_evaluation_point_from_beacon(&[]).is_err());
+
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        let (_terminate_codeword, _query_prover, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let mut verifier_transcript = friVail.reconstruct_transcript_from_bytes(transcript_bytes);
+        friVail
+            .verify(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("Proof at beacon-derived point should verify");
+    }
+
+    #[test]
+    fn test_initialize_fri_context() {
+        let friVail = TestFriVail::new(1, 3, 2, 12, 2);
+
+        // Create test data
+        let test_data = create_test_data(1024); // 1KB test data
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let result = friVail.initialize_fri_context(packed_mle_values.packed_mle.log_len());
+        assert!(result.is_ok());
+
+        let (fri_params, _ntt) = result.unwrap();
+
+        // Verify FRI parameters are reasonable
+        assert_eq!(fri_params.rs_code().log_inv_rate(), friVail.log_inv_rate);
+        assert_eq!(fri_params.n_test_queries(), friVail.num_test_queries);
+    }
+
+    #[test]
+    fn test_params_for_codeword_len() {
+        const LOG_INV_RATE: usize = 1;
+        const TARGET_LEN: usize = 1000;
+
+        let (n_vars, padded_data_len) =
+            TestFriVail::params_for_codeword_len(TARGET_LEN, LOG_INV_RATE)
+                .expect("Failed to compute parameters");
+        assert_eq!(padded_data_len, (1 << n_vars) * 16);
+
+        let values: Vec<B128> = (0..1u128 << n_vars).map(B128::from).collect();
+        let packed_mle = FieldBuffer::<B128>::from_values(&values);
+
+        let friVail = TestFriVail::new(LOG_INV_RATE, 3, 2, n_vars, 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(packed_mle, fri_params, &ntt)
+            .expect("Failed to commit");
+
+        assert_eq!(commit_output.codeword.len(), 1 << (n_vars + LOG_INV_RATE));
+        assert!(commit_output.codeword.len() >= TARGET_LEN);
+        assert!(commit_output.codeword.len() < 2 * TARGET_LEN);
+
+        assert!(TestFriVail::params_for_codeword_len(0, LOG_INV_RATE).is_err());
+    }
+
+    #[test]
+    fn test_commit_batch_proves_each_polynomial() {
+        let blobs: Vec<Vec<u8>> = (0..3)
+            .map(|i| synthetic_blob(1024, DataPattern::Random(i)))
+            .collect();
+        let packed: Vec<_> = blobs
+            .iter()
+            .map(|blob| {
+                Utils::<B128>::new()
+                    .bytes_to_packed_mle(blob)
+                    .expect("Failed to create packed MLE")
+            })
+            .collect();
+        let n_vars = packed[0].packed_mle.log_len();
+
+        let friVail = TestFriVail::new(1, 3, 2, n_vars, 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+
+        let mles: Vec<_> = packed.iter().map(|p| p.packed_mle.clone()).collect();
+        let commit_outputs = friVail
+            .commit_batch(&mles, &fri_params, &ntt)
+            .expect("Failed to batch commit");
+        assert_eq!(commit_outputs.len(), 3);
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        for (packed_mle_values, commit_output) in packed.iter().zip(&commit_outputs) {
+            // Same commitment as committing the polynomial on its own
+            let single = friVail
+                .commit(
+                    packed_mle_values.packed_mle.clone(),
+                    fri_params.clone(),
+                    &ntt,
+                )
+                .expect("Failed to commit");
+            assert_eq!(single.commitment, commit_output.commitment);
+
+            let evaluation_claim = friVail
+                .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+                .expect("Failed to calculate evaluation claim");
+            let (_, _, transcript_bytes, _) = friVail
+                .prove(
+                    packed_mle_values.packed_mle.clone(),
+                    &fri_params,
+                    &ntt,
+                    commit_output,
+                    &evaluation_point,
+                )
+                .expect("Failed to generate proof");
+
+            let mut verifier_transcript =
+                friVail.reconstruct_transcript_from_bytes(transcript_bytes);
+            friVail
+                .verify(
+                    &mut verifier_transcript,
+                    evaluation_claim,
+                    &evaluation_point,
+                    &fri_params,
+                    &ntt,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .expect("Proof should verify");
+        }
+    }
+
+    #[test]
+    fn test_commit_root_only_matches_commit() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let root = friVail
+            .commit_root_only(packed_mle_values.packed_mle, fri_params, &ntt)
+            .expect("Failed to compute root");
+
+        assert_eq!(root.as_slice(), commit_output.commitment.as_slice());
+    }
+
+    /// SHA3-256 compression of two sibling digests
+    #[derive(Debug, Clone, Copy, Default)]
+    struct Sha3Compression;
+
+    impl PseudoCompressionFunction<digest::Output<Sha3_256>, 2> for Sha3Compression {
+        fn compress(&self, input: [digest::Output<Sha3_256>; 2]) -> digest::Output<Sha3_256> {
+            Sha3_256::new()
+                .chain_update(input[0])
+                .chain_update(input[1])
+                .finalize()
+        }
+    }
+
+    /// Merkle hash sharing nothing with the SHA-256 default
+    struct Sha3MerkleHash;
+
+    impl MerkleHash for Sha3MerkleHash {
+        type Digest = Sha3_256;
+        type Compression = Sha3Compression;
+        type Scheme = BinaryMerkleTreeScheme<B128, Sha3_256, Sha3Compression>;
+        type Prover =
+            BinaryMerkleTreeProver<B128, Sha3_256, ParallelCompressionAdaptor<Sha3Compression>>;
+
+        fn merkle_prover() -> Self::Prover {
+            BinaryMerkleTreeProver::new(ParallelCompressionAdaptor::new(Sha3Compression))
+        }
+    }
+
+    #[test]
+    fn test_custom_merkle_hash_round_trip() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = FriVail::<
+            'static,
+            B128,
+            <Sha3MerkleHash as MerkleHash>::Scheme,
+            NeighborsLastMultiThread<GenericPreExpanded<B128>>,
+            Sha3MerkleHash,
+        >::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        // The same codeword commits to a different root under the default hash
+        let std_fri_vail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let std_output = std_fri_vail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        assert_eq!(std_output.codeword, commit_output.codeword);
+        assert_ne!(std_output.commitment, commit_output.commitment);
+
+        let root = friVail
+            .commit_root_only(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to compute root");
+        assert_eq!(root.as_slice(), commit_output.commitment.as_slice());
+
+        let index = 5;
+        let mut inclusion_proof = friVail
+            .inclusion_proof(&commit_output.committed, index)
+            .expect("Failed to generate inclusion proof");
+        friVail
+            .verify_inclusion_proof(
+                &mut inclusion_proof,
+                &[commit_output.codeword[index]],
+                index,
+                &fri_params,
+                commit_output.commitment,
+            )
+            .expect("Inclusion proof should verify");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let (_, _, transcript_bytes, claim) = friVail
+            .prove(
+                packed_mle_values.packed_mle,
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes.clone());
+        friVail
+            .verify(
+                &mut verifier_transcript,
+                claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("Proof should verify under its own hash");
+
+        // The default hash cannot verify a proof built with another hash
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
+        assert!(std_fri_vail
+            .verify(
+                &mut verifier_transcript,
+                claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_commit_sparse_matches_dense() {
+        let n_vars = 12;
+        let friVail = TestFriVail::new(1, 3, 2, n_vars, 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+
+        let nonzero: Vec<(usize, B128)> = [3, 100, 1024, 2049, 4095]
+            .into_iter()
+            .map(|index| (index, B128::from(index as u128 + 1)))
+            .collect();
+        let mut dense = vec![B128::zero(); 1 << n_vars];
+        for &(index, value) in &nonzero {
+            dense[index] = value;
+        }
+
+        let sparse_output = friVail
+            .commit_sparse(&nonzero, n_vars, fri_params.clone(), &ntt)
+            .expect("Failed to commit sparse MLE");
+        let dense_output = friVail
+            .commit(FieldBuffer::from_values(&dense), fri_params.clone(), &ntt)
+            .expect("Failed to commit dense MLE");
+        assert_eq!(sparse_output.commitment, dense_output.commitment);
+
+        assert!(friVail
+            .commit_sparse(
+                &[(1 << n_vars, B128::ONE)],
+                n_vars,
+                fri_params.clone(),
+                &ntt
+            )
+            .is_err());
+        assert!(friVail
+            .commit_sparse(&[(3, B128::ONE), (3, B128::ONE)], n_vars, fri_params, &ntt)
+            .is_err());
+    }
+
+    #[test]
+    fn test_commit_coeffs_matches_evaluation_form() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        // A constant polynomial evaluates to its constant everywhere
+        let mut constant = vec![B128::zero(); 8];
+        constant[0] = B128::from(5u128);
+        assert_eq!(
+            TestFriVail::multilinear_coeffs_to_evals(&constant),
+            vec![B128::from(5u128); 8]
+        );
+
+        // The transform is an involution, so applying it to evaluations yields coefficients
+        let coeffs = TestFriVail::multilinear_coeffs_to_evals(&packed_mle_values.packed_values);
+        assert_eq!(
+            TestFriVail::multilinear_coeffs_to_evals(&coeffs),
+            packed_mle_values.packed_values
+        );
+
+        let coeffs_commitment = friVail
+            .commit_coeffs(&coeffs, fri_params.clone(), &ntt)
+            .expect("Failed to commit coefficients");
+        let evals_commitment = friVail
+            .commit(packed_mle_values.packed_mle.clone(), fri_params, &ntt)
+            .expect("Failed to commit");
+
+        assert_eq!(coeffs_commitment.commitment, evals_commitment.commitment);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_commit_and_inclusion_proofs() {
+        // Create test data
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        // Test commit
+        let commit_result = friVail.commit(
+            packed_mle_values.packed_mle.clone(),
+            fri_params.clone(),
+            &ntt,
+        );
+        assert!(commit_result.is_ok());
+
+        let commit_output = commit_result.unwrap();
+        assert!(!commit_output.commitment.is_empty());
+        assert!(commit_output.codeword.len() > 0);
+
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
+        // Test inclusion proofs for first few elements
+        for i in 0..std::cmp::min(5, commit_output.codeword.len()) {
+            let value = commit_output.codeword[i];
+
+            // Generate inclusion proof
+            let inclusion_proof_result = friVail.inclusion_proof(&commit_output.committed, i);
+            assert!(inclusion_proof_result.is_ok());
+
+            let mut inclusion_proof = inclusion_proof_result.unwrap();
+
+            // Verify inclusion proof
+            let verify_result = friVail.verify_inclusion_proof(
+                &mut inclusion_proof,
+                &[value],
+                i,
+                &fri_params,
+                commitment_bytes,
+            );
+            assert!(
+                verify_result.is_ok(),
+                "Inclusion proof verification failed for index {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_samples_parallel_preserves_order() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
+
+        // Every third sample carries a wrong value
+        let samples: Vec<(usize, Vec<B128>, Vec<u8>)> = (0..32)
+            .map(|i| {
+                let index = i * 3;
+                let proof = friVail
+                    .inclusion_proof(&commit_output.committed, index)
+                    .expect("Failed to generate inclusion proof");
+                let mut value = commit_output.codeword[index];
+                if i % 3 == 0 {
+                    value += B128::ONE;
+                }
+                (index, vec![value], friVail.get_transcript_bytes(&proof))
+            })
+            .collect();
+
+        let results =
+            friVail.verify_samples_parallel(commitment_bytes, &fri_params, &samples, None);
+        assert_eq!(results.len(), samples.len());
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(
+                result.is_ok(),
+                i % 3 != 0,
+                "Unexpected result for sample {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_progress_callbacks_report_completion() {
+        let test_data = create_test_data(4096);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let record = |done: usize, total: usize| calls.borrow_mut().push((done, total));
+
+        let erased: Vec<usize> = (0..150).map(|i| i * 3).collect();
+        let mut corrupted_codeword = commit_output.codeword.clone();
+        for &index in &erased {
+            corrupted_codeword[index] = B128::zero();
+        }
+        friVail
+            .reconstruct_codeword_naive(&mut corrupted_codeword, &erased, Some(&record))
+            .expect("Failed to reconstruct codeword");
+        assert_eq!(corrupted_codeword, commit_output.codeword);
+        assert_eq!(calls.take(), vec![(64, 150), (128, 150), (150, 150)]);
+
+        let commitment_bytes: [u8; 32] = CommitmentRoot::from(commit_output.commitment).into();
+        let samples: Vec<(usize, Vec<B128>, Vec<u8>)> = (0..100)
+            .map(|index| {
+                let proof = friVail
+                    .inclusion_proof(&commit_output.committed, index)
+                    .expect("Failed to generate inclusion proof");
+                (
+                    index,
+                    vec![commit_output.codeword[index]],
+                    friVail.get_transcript_bytes(&proof),
+                )
+            })
+            .collect();
+        let results =
+            friVail.verify_samples_parallel(commitment_bytes, &fri_params, &samples, Some(&record));
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(calls.take().last(), Some(&(100, 100)));
+    }
+
+    #[test]
+    fn test_merkle_scheme_verifies_inclusion_proof() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let index = 3;
+        let mut inclusion_proof = friVail
+            .inclusion_proof(&commit_output.committed, index)
+            .expect("Failed to generate inclusion proof");
+
+        let scheme: &BinaryMerkleTreeScheme<B128, StdDigest, StdCompression> =
+            friVail.merkle_scheme();
+        scheme
+            .verify_opening(
+                index,
+                &[commit_output.codeword[index]],
+                0,
+                fri_params.rs_code().log_len(),
+                &[commit_output.commitment],
+                &mut inclusion_proof.message(),
+            )
+            .expect("Scheme should verify the inclusion proof");
+    }
+
+    #[test]
+    fn test_inclusion_proof_from_codeword_matches_committed() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        // Keep only the codeword
+        let codeword: Vec<B128> = commit_output.codeword.clone();
+
+        for index in [0, 9, codeword.len() - 1] {
+            let from_tree = friVail
+                .inclusion_proof(&commit_output.committed, index)
+                .expect("Failed to generate inclusion proof");
+            let mut from_codeword = friVail
+                .inclusion_proof_from_codeword(&codeword, index, &fri_params)
+                .expect("Failed to generate inclusion proof from codeword");
+
+            assert_eq!(
+                friVail.get_transcript_bytes(&from_codeword),
+                friVail.get_transcript_bytes(&from_tree)
+            );
+            friVail
+                .verify_inclusion_proof(
+                    &mut from_codeword,
+                    &[codeword[index]],
+                    index,
+                    &fri_params,
+                    commit_output.commitment,
+                )
+                .expect("Rebuilt inclusion proof should verify");
+        }
+
+        assert!(friVail
+            .inclusion_proof_from_codeword(&codeword, codeword.len(), &fri_params)
+            .is_err());
+    }
+
+    #[test]
+    fn test_compressed_openings_round_trip() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
+
+        let indices = [4, 5, 6, 7, 20];
+        let openings: Vec<LeafOpening<B128>> = indices
+            .iter()
+            .map(|&index| LeafOpening {
+                index,
+                leaf: vec![commit_output.codeword[index]],
+                proof: friVail
+                    .inclusion_proof(&commit_output.committed, index)
+                    .expect("Failed to generate inclusion proof"),
+            })
+            .collect();
+        let proof_bytes: Vec<Vec<u8>> = openings
+            .iter()
+            .map(|opening| friVail.get_transcript_bytes(&opening.proof))
+            .collect();
+
+        let compressed = friVail
+            .compress_openings(&openings, &fri_params)
+            .expect("Failed to compress openings");
+        assert!(compressed.len() < proof_bytes.iter().map(Vec::len).sum());
+
+        let leaves = openings
+            .iter()
+            .map(|opening| opening.leaf.clone())
+            .collect();
+        let mut decompressed = friVail
+            .decompress_openings(&indices, leaves, &compressed, &fri_params)
+            .expect("Failed to decompress openings");
+
+        for (opening, original) in decompressed.iter_mut().zip(&proof_bytes) {
+            assert_eq!(&friVail.get_transcript_bytes(&opening.proof), original);
+            friVail
+                .verify_inclusion_proof(
+                    &mut opening.proof,
+                    &opening.leaf,
+                    opening.index,
+                    &fri_params,
+                    commitment_bytes,
+                )
+                .expect("Decompressed opening should verify");
+        }
+
+        let leaves = openings
+            .iter()
+            .map(|opening| opening.leaf.clone())
+            .collect();
+        assert!(friVail
+            .decompress_openings(&indices, leaves, &compressed[1..], &fri_params)
+            .is_err());
+    }
+
+    #[test]
+    fn test_auth_path_len_matches_inclusion_proof() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        for index in [0, 1, commit_output.codeword.len() - 1] {
+            let inclusion_proof = friVail
+                .inclusion_proof(&commit_output.committed, index)
+                .expect("Failed to generate inclusion proof");
+            let proof_bytes = friVail.get_transcript_bytes(&inclusion_proof);
+            let digest_len = std::mem::size_of::<digest::Output<StdDigest>>();
+
+            assert_eq!(proof_bytes.len() % digest_len, 0);
+            assert_eq!(
+                friVail.auth_path_len(index, &fri_params),
+                proof_bytes.len() / digest_len
+            );
+        }
+
+        assert_eq!(
+            friVail.auth_path_len(commit_output.codeword.len(), &fri_params),
+            0
+        );
+    }
+
+    #[test]
+    fn test_verify_leaf_from_bytes() {
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&create_test_data(1024))
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        // Everything the light client receives is plain bytes
+        let root: [u8; 32] = CommitmentRoot::from(commit_output.commitment).into();
+        let tree_depth = commit_output.codeword.len().ilog2() as usize;
+        let index = 5;
+        let opening_bytes = friVail.get_transcript_bytes(
+            &friVail
+                .inclusion_proof(&commit_output.committed, index)
+                .expect("Failed to generate inclusion proof"),
+        );
+        let leaf = [commit_output.codeword[index]];
+
+        friVail
+            .verify_leaf(root, tree_depth, index, &leaf, &opening_bytes)
+            .expect("Leaf should verify");
+
+        assert_eq!(
+            friVail.verify_leaf(
+                root,
+                tree_depth,
+                index,
+                &[leaf[0] + B128::ONE],
+                &opening_bytes
+            ),
+            Err(FriVailError::Verification(VerifyError::InclusionProof))
+        );
+        assert_eq!(
+            friVail.verify_leaf(root, tree_depth, index + 1, &leaf, &opening_bytes),
+            Err(FriVailError::Verification(VerifyError::InclusionProof))
+        );
+        assert!(matches!(
+            friVail.verify_leaf(root, tree_depth, 1 << tree_depth, &leaf, &opening_bytes),
+            Err(FriVailError::Verification(VerifyError::LeafIndex { .. }))
+        ));
+        // A depth past the width of usize is rejected rather than overflowing the shift
+        assert_eq!(
+            friVail.verify_leaf(root, usize::BITS as usize, index, &leaf, &opening_bytes),
+            Err(FriVailError::Verification(VerifyError::MalformedHeader))
+        );
+    }
+
+    #[test]
+    fn test_proof_size_estimates() {
+        let test_data = create_test_data(9 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 128, 4, packed_mle_values.total_n_vars, 80);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let (_, query_prover, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let layers = query_prover
+            .vcs_optimal_layers()
+            .expect("Failed to get Merkle layers");
+        assert_eq!(friVail.num_merkle_layers(&fri_params), layers.len());
+
+        // Leaf values travel beside the proof, so the estimate exceeds it by one leaf
+        let inclusion_proof = friVail
+            .inclusion_proof(&commit_output.committed, 0)
+            .expect("Failed to generate inclusion proof");
+        let proof_len = friVail.get_transcript_bytes(&inclusion_proof).len();
+        let leaf_len = (1 << fri_params.log_batch_size()) * std::mem::size_of::<B128>();
+        assert_eq!(
+            friVail.estimated_inclusion_proof_size(&fri_params),
+            proof_len + leaf_len
+        );
+
+        let estimate = friVail.estimated_fri_proof_size(&fri_params);
+        let delta = estimate.abs_diff(transcript_bytes.len());
+        assert!(
+            delta * 10 <= transcript_bytes.len(),
+            "Estimated {} bytes, proof has {}",
+            estimate,
+            transcript_bytes.len()
+        );
+    }
+
+    #[test]
+    fn test_open_column_of_batched_commitment() {
+        const LOG_BATCH_SIZE: usize = 2;
+        const LOG_COLUMN_LEN: usize = 6;
+        let num_columns = 1 << LOG_BATCH_SIZE;
+        let column_len = 1 << LOG_COLUMN_LEN;
+
+        // Interleave the columns element by element
+        let mut message = vec![B128::zero(); num_columns * column_len];
+        for row in 0..column_len {
+            for column in 0..num_columns {
+                message[row * num_columns + column] = B128::from((column * 1000 + row) as u128);
+            }
+        }
+        let packed_mle = FieldBuffer::<B128>::from_values(&message);
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context_batched(packed_mle.log_len(), LOG_BATCH_SIZE)
+            .expect("Failed to initialize FRI context");
+        assert_eq!(fri_params.log_batch_size(), LOG_BATCH_SIZE);
+
+        let commit_output = friVail
+            .commit(packed_mle, fri_params.clone(), &ntt)
+            .expect("Failed to commit");
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
+
+        for index in [0, 5, (1 << fri_params.rs_code().log_len()) - 1] {
+            for column in 0..num_columns {
+                let mut opening = friVail
+                    .open_column(&commit_output, column, index, &fri_params)
+                    .expect("Failed to open column");
+                let value = friVail
+                    .verify_column_opening(&mut opening, &fri_params, commitment_bytes)
+                    .expect("Column opening failed to verify");
+                assert_eq!(value, commit_output.codeword[index * num_columns + column]);
+            }
+        }
+
+        assert!(friVail
+            .open_column(&commit_output, num_columns, 0, &fri_params)
+            .is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proof_of_batched_leaf() {
+        const LOG_BATCH_SIZE: usize = 2;
+        let leaf_width = 1 << LOG_BATCH_SIZE;
+
+        let message: Vec<B128> = (0..256u128).map(B128::from).collect();
+        let packed_mle = FieldBuffer::<B128>::from_values(&message);
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context_batched(packed_mle.log_len(), LOG_BATCH_SIZE)
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(packed_mle, fri_params.clone(), &ntt)
+            .expect("Failed to commit");
+
+        let index = 7;
+        let leaf = &commit_output.codeword[index * leaf_width..(index + 1) * leaf_width];
+        let mut inclusion_proof = friVail
+            .inclusion_proof(&commit_output.committed, index)
+            .expect("Failed to generate inclusion proof");
+        friVail
+            .verify_inclusion_proof(
+                &mut inclusion_proof.clone(),
+                leaf,
+                index,
+                &fri_params,
+                commit_output.commitment,
+            )
+            .expect("Leaf of 4 scalars should verify");
+
+        // A single scalar is not a full leaf
+        assert_eq!(
+            friVail.verify_inclusion_proof(
+                &mut inclusion_proof,
+                &leaf[..1],
+                index,
+                &fri_params,
+                commit_output.commitment,
+            ),
+            Err(FriVailError::Decode {
+                expected: leaf_width,
+                got: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_commit_append_matches_fresh_commit() {
+        let utils = Utils::<B128>::new();
+        let prev_mle = utils
+            .bytes_to_packed_mle(&create_test_data(512))
+            .expect("Failed to create packed MLE");
+        let new_mle = utils
+            .bytes_to_packed_mle(&synthetic_blob(512, DataPattern::Random(7)))
+            .expect("Failed to create packed MLE");
+
+        let combined_log_len = prev_mle.packed_mle.log_len() + 1;
+        let friVail = TestFriVail::new(1, 3, 2, combined_log_len, 3);
+        let (prev_params, prev_ntt) = friVail
+            .initialize_fri_context(prev_mle.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(combined_log_len)
+            .expect("Failed to initialize FRI context");
+
+        let prev = friVail
+            .commit(prev_mle.packed_mle.clone(), prev_params.clone(), &prev_ntt)
+            .expect("Failed to commit");
+        let appended = friVail
+            .commit_append(
+                &prev,
+                &prev_params,
+                new_mle.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+            )
+            .expect("Failed to append");
+
+        let combined = [
+            prev_mle.packed_values.clone(),
+            new_mle.packed_values.clone(),
+        ]
+        .concat();
+        let fresh = friVail
+            .commit(
+                FieldBuffer::<B128>::from_values(&combined),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        assert_eq!(appended.commitment, fresh.commitment);
+        assert_eq!(appended.codeword, fresh.codeword);
+
+        // Appending past what the parameters encode is rejected
+        assert!(matches!(
+            friVail.commit_append(
+                &appended,
+                &fri_params,
+                new_mle.packed_mle,
+                &fri_params,
+                &ntt
+            ),
+            Err(FriVailError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_segmented_append_keeps_untouched_segments() {
+        let segment_log_len = 6;
+        let segment_len = 1 << segment_log_len;
+        let values: Vec<B128> = (0..3 * segment_len as u128 / 2).map(B128::from).collect();
+
+        let friVail = TestFriVail::new(1, 3, 2, segment_log_len, 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(segment_log_len)
+            .expect("Failed to initialize FRI context");
+
+        let mut segmented = SegmentedCommitment::new(segment_log_len);
+        let dirty = friVail
+            .append_segment(&mut segmented, &values, &fri_params, &ntt)
+            .expect("Failed to append");
+        assert_eq!(dirty, 0..2);
+        let old_root = segmented.segment_root(0).unwrap();
+        let old_partial_root = segmented.segment_root(1).unwrap();
+
+        // Fill the partial segment and start a new one
+        let dirty = friVail
+            .append_segment(&mut segmented, &values[..segment_len], &fri_params, &ntt)
+            .expect("Failed to append");
+        assert_eq!(dirty, 1..3);
+        assert_eq!(segmented.num_segments(), 3);
+        assert_eq!(segmented.segment_root(0), Some(old_root));
+        assert_ne!(segmented.segment_root(1), Some(old_partial_root));
+
+        // An old position in the untouched segment still opens under its sub-root
+        let segment = segmented.segment(0).unwrap();
+        let index = 5;
+        let mut proof = friVail
+            .inclusion_proof(&segment.committed, index)
+            .expect("Failed to generate inclusion proof");
+        friVail
+            .verify_inclusion_proof(
+                &mut proof,
+                &[segment.codeword[index]],
+                index,
+                &fri_params,
+                old_root,
+            )
+            .expect("Inclusion proof against unchanged sub-root failed");
+
+        // Parameters for a different message length are rejected
+        let (other_params, other_ntt) = friVail
+            .initialize_fri_context(segment_log_len + 1)
+            .expect("Failed to initialize FRI context");
+        assert!(friVail
+            .append_segment(&mut segmented, &values, &other_params, &other_ntt)
+            .is_err());
+    }
+
+    #[test]
+    #[ignore]
+    fn test_open_method() {
+        // Create test data
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        // Test commit
+        let commit_result = friVail.commit(
+            packed_mle_values.packed_mle.clone(),
+            fri_params.clone(),
+            &ntt,
+        );
+        assert!(commit_result.is_ok());
+
+        let commit_output = commit_result.unwrap();
+        assert!(!commit_output.commitment.is_empty());
+        assert!(commit_output.codeword.len() > 0);
+
+        // Generate evaluation point for prove
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        // Generate proof to get query_prover
+        let prove_result = friVail.prove(
+            packed_mle_values.packed_mle.clone(),
+            &fri_params,
+            &ntt,
+            &commit_output,
+            &evaluation_point,
+        );
+        assert!(prove_result.is_ok());
+
+        let (_, query_prover, _, _) = prove_result.unwrap();
+
+        // Test that open() method works with query_prover
+        for i in 0..std::cmp::min(5, commit_output.codeword.len()) {
+            let open_result = friVail.open(i, &commit_output.commitment, &query_prover);
+            assert!(open_result.is_ok(), "open() method failed for index {}", i);
+        }
+    }
+
+    #[test]
+    fn test_calculate_evaluation_claim() {
+        let test_data = create_test_data(1024); // 1mb test data
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        println!("evaluation point {:?}", evaluation_point.len());
+        let eval_point_eq = eq_ind_partial_eval(&evaluation_point);
+        println!("eval_point_eq {:?}", eval_point_eq.len());
+        println!("mle value {:?}", packed_mle_values.packed_mle.len());
+        let evaluation_claim = inner_product_buffers(&packed_mle_values.packed_mle, &eval_point_eq);
+
+        println!("evaluation claim {:?}", evaluation_claim);
+
+        let result =
+            friVail.calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point);
+        assert!(result.is_ok());
+
+        let evaluation_claim = result.unwrap();
+        // The evaluation claim should be a valid field element
+        assert_ne!(evaluation_claim, B128::default()); // Should not be zero for random inputs
+    }
+
+    #[test]
+    fn test_full_prove_verify_workflow() {
+        // Create test data
+        let test_data = create_test_data(1024 * 1024); // 2KB test data
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        // Initialize FRI context
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        // Generate evaluation point
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let eval_point_eq = eq_ind_partial_eval(&evaluation_point);
+        let evaluation_claim = inner_product_buffers(&packed_mle_values.packed_mle, &eval_point_eq);
+
+        println!("evaluation claim {:?}", evaluation_claim);
+        // The evaluation claim should be a valid field element
+        assert_ne!(evaluation_claim, B128::default()); // Should not be zero for random inputs
+
+        // Commit to MLE
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        // Generate proof
+        let prove_result = friVail.prove(
+            packed_mle_values.packed_mle.clone(),
+            &fri_params,
+            &ntt,
+            &commit_output,
+            &evaluation_point,
+        );
+        assert!(prove_result.is_ok());
+
+        let (terminate_codeword, query_prover, transcript_bytes, _) = prove_result.unwrap();
+
+        // Extract layers directly from query_prover
+        let layers = query_prover
+            .vcs_optimal_layers()
+            .expect("Failed to get layers");
+
+        // Reconstruct verifier transcript from bytes
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
+
+        // Generate extra query proof using open()
+        let mut extra_transcript = friVail
+            .open(0, &commit_output.commitment, &query_prover)
+            .expect("Failed to generate extra query proof");
+
+        // Verify proof with extra parameters
+        let verify_result = friVail.verify(
+            &mut verifier_transcript,
+            evaluation_claim,
+            &evaluation_point,
+            &fri_params,
+            &ntt,                        // ntt instance
+            Some(0),                     // extra_index - use 0 for testing
+            Some(&terminate_codeword),   // terminate_codeword
+            Some(&layers),               // layers
+            Some(&mut extra_transcript), // extra query transcript
+        );
+        assert!(
+            verify_result.is_ok(),
+            "Verification failed: {:?}",
+            verify_result
+        );
+    }
+
+    #[test]
+    fn test_prove_full_and_verify_proof() {
+        let test_data = create_test_data(64 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let proof = friVail
+            .prove_full(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+                0,
+            )
+            .expect("Failed to generate proof");
+
+        friVail
+            .verify_proof(
+                &proof,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+            )
+            .expect("Proof should verify");
+
+        // A wrong claim or a truncated layer list is rejected
+        assert!(friVail
+            .verify_proof(
+                &proof,
+                evaluation_claim + B128::ONE,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+            )
+            .is_err());
+        let mut truncated = proof.clone();
+        truncated.layers.pop();
+        assert!(friVail
+            .verify_proof(
+                &truncated,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_with_mode() {
+        let test_data = create_test_data(64 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let proof = friVail
+            .prove_full(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+                3,
+            )
+            .expect("Failed to generate proof");
+        let minimal = FriVailProof::claim_only(proof.transcript_bytes.clone());
+
+        let verify = |proof: &FriVailProof<B128>, claim, mode| {
+            friVail.verify_with_mode(proof, claim, &evaluation_point, &fri_params, &ntt, mode)
+        };
+
+        // Claim-only verification needs no extra query data
+        verify(&minimal, evaluation_claim, VerifyMode::ClaimOnly)
+            .expect("Claim-only proof should verify");
+        assert!(verify(
+            &minimal,
+            evaluation_claim + B128::ONE,
+            VerifyMode::ClaimOnly
+        )
+        .is_err());
+
+        let with_query = VerifyMode::WithExtraQuery { index: 3 };
+        verify(&proof, evaluation_claim, with_query).expect("Full proof should verify");
+        assert!(verify(&proof, evaluation_claim + B128::ONE, with_query).is_err());
+        assert!(verify(
+            &proof,
+            evaluation_claim,
+            VerifyMode::WithExtraQuery { index: 4 }
+        )
+        .is_err());
+        assert!(verify(&minimal, evaluation_claim, with_query).is_err());
+    }
+
+    #[test]
+    fn test_verify_extra_query_wrapper() {
+        let test_data = create_test_data(64 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        let proof = friVail
+            .prove_full(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+                5,
+            )
+            .expect("Failed to generate proof");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+
+        friVail
+            .verify_extra_query(
+                5,
+                &fri_params,
+                &ntt,
+                &proof,
+                evaluation_claim,
+                &evaluation_point,
+            )
+            .expect("Extra query should verify");
+
+        let mut tampered = proof.clone();
+        tampered.layers[0][0][0] ^= 1;
+        assert!(friVail
+            .verify_extra_query(
+                5,
+                &fri_params,
+                &ntt,
+                &tampered,
+                evaluation_claim,
+                &evaluation_point,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_prove_multi_three_points() {
+        let test_data = create_test_data(64 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let points: Vec<Vec<B128>> = (0..3)
+            .map(|_| {
+                friVail
+                    .calculate_evaluation_point_random()
+                    .expect("Failed to generate evaluation point")
+            })
+            .collect();
+        let claims: Vec<B128> = points
+            .iter()
+            .map(|point| {
+                friVail
+                    .calculate_evaluation_claim(&packed_mle_values.packed_values, point)
+                    .expect("Failed to calculate evaluation claim")
+            })
+            .collect();
+
+        let proof = friVail
+            .prove_multi(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &points,
+            )
+            .expect("Failed to generate multi-point proof");
+        assert_eq!(&proof.evaluations[..3], claims.as_slice());
+
+        friVail
+            .verify_multi(&proof, &claims, &points, &fri_params, &ntt)
+            .expect("Multi-point proof should verify");
+
+        // Altering any single claim is rejected
+        for i in 0..claims.len() {
+            let mut altered = claims.clone();
+            altered[i] += B128::ONE;
+            assert!(friVail
+                .verify_multi(&proof, &altered, &points, &fri_params, &ntt)
+                .is_err());
+
+            // Also when the proof is altered to match the claim
+            let mut forged = proof.clone();
+            forged.evaluations[i] = altered[i];
+            assert!(friVail
+                .verify_multi(&forged, &altered, &points, &fri_params, &ntt)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_prove_returns_evaluation_claim() {
+        let test_data = create_test_data(64 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        let (_, _, transcript_bytes, evaluation_claim) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let recomputed = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        assert_eq!(evaluation_claim, recomputed);
+
+        let mut verifier_transcript = friVail.reconstruct_transcript_from_bytes(transcript_bytes);
+        friVail
+            .verify(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("Proof should verify against the returned claim");
+    }
+
+    #[test]
+    fn test_verify_reports_typed_errors() {
+        let test_data = create_test_data(64 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let (_, _, transcript_bytes, evaluation_claim) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let verify = |claim: B128, point: &[B128]| {
+            let mut verifier_transcript =
+                friVail.reconstruct_transcript_from_bytes(transcript_bytes.clone());
+            friVail.verify(
+                &mut verifier_transcript,
+                claim,
+                point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        verify(evaluation_claim, &evaluation_point).expect("Valid proof should verify");
+
+        assert_eq!(
+            verify(evaluation_claim + B128::ONE, &evaluation_point),
+            Err(FriVailError::Verification(VerifyError::EvaluationProof))
+        );
+
+        let result = verify(evaluation_claim, &evaluation_point[..1]);
+        assert!(matches!(
+            result,
+            Err(FriVailError::Verification(VerifyError::PointTooShort {
+                got: 1,
+                ..
+            }))
+        ));
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_commit_file_matches_in_memory_commit() {
+        let test_data = create_test_data(1234);
+        let path = std::env::temp_dir().join(format!("frivail-commit-{}.bin", std::process::id()));
+        std::fs::write(&path, &test_data).expect("Failed to write temp file");
+
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let file_result = friVail.commit_file(&path, fri_params.clone(), &ntt);
+        std::fs::remove_file(&path).expect("Failed to remove temp file");
+        let (file_output, original_byte_len) = file_result.expect("Failed to commit file");
+
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        assert_eq!(original_byte_len, 1234);
+        assert_eq!(file_output.commitment, commit_output.commitment);
+        assert_eq!(file_output.codeword, commit_output.codeword);
+
+        assert!(matches!(
+            friVail.commit_file(&path, fri_params, &ntt),
+            Err(FriVailError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_commit_prebuilt_scalars() {
+        let values: Vec<B128> = (0..3000u128)
+            .map(|i| B128::from(i.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (i << 64)))
+            .collect();
+        let packed_mle_values = Utils::<B128>::new().scalars_to_packed_mle(&values);
+
+        assert_eq!(packed_mle_values.total_n_vars, 12);
+        assert_eq!(packed_mle_values.num_data_elements(), values.len());
+        assert_eq!(packed_mle_values.original_byte_len, values.len() * 16);
+        assert_eq!(
+            &packed_mle_values.packed_values[..values.len()],
+            &values[..]
+        );
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let proof = friVail
+            .prove_full(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+                0,
+            )
+            .expect("Failed to generate proof");
+
+        friVail
+            .verify_proof(
+                &proof,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+            )
+            .expect("Proof should verify");
+    }
+
+    #[test]
+    fn test_terminal_codeword_conversions() {
+        let values: Vec<B128> = (1..=8u128).map(B128::from).collect();
+        let terminal = TerminalCodeword::from(FieldBuffer::<B128>::from_values(&values));
+
+        assert_eq!(terminal.as_scalars(), &values[..]);
+        assert_eq!(terminal.to_vec(), values);
+        assert_eq!(terminal, TerminalCodeword::from(values.clone()));
+        assert_eq!(terminal.len(), 8);
+        assert!(!terminal.is_empty());
+
+        assert!(terminal.check_len(8).is_ok());
+        assert_eq!(
+            terminal.check_len(4),
+            Err(FriVailError::Decode {
+                expected: 4,
+                got: 8
+            })
+        );
+    }
+
+    #[test]
+    fn test_proof_of_retrievability() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
+
+        let seed = [7u8; 32];
+        let count = commit_output.codeword.len() / 2;
+        let positions = friVail.por_challenge(&commit_output, seed, count);
+        assert_eq!(positions.len(), count);
+        assert_eq!(
+            positions,
+            friVail.por_challenge(&commit_output, seed, count)
+        );
+
+        // Honest storer
+        let mut responses = friVail
+            .por_respond(
+                &commit_output.codeword,
+                &commit_output.committed,
+                &positions,
+            )
+            .expect("Failed to respond");
+        friVail
+            .por_verify(commitment_bytes, seed, count, &mut responses, &fri_params)
+            .expect("Honest storer should pass the audit");
+
+        // Storer that dropped every odd position and answers with zeros
+        let mut dropped_codeword = commit_output.codeword.clone();
+        for value in dropped_codeword.iter_mut().skip(1).step_by(2) {
+            *value = B128::zero();
+        }
+        let mut responses = friVail
+            .por_respond(&dropped_codeword, &commit_output.committed, &positions)
+            .expect("Failed to respond");
+        assert!(friVail
+            .por_verify(commitment_bytes, seed, count, &mut responses, &fri_params)
+            .is_err());
+
+        // Answers to a different challenge are rejected
+        let mut responses = friVail
+            .por_respond(
+                &commit_output.codeword,
+                &commit_output.committed,
+                &positions,
+            )
+            .expect("Failed to respond");
+        assert!(friVail
+            .por_verify(
+                commitment_bytes,
+                [8u8; 32],
+                count,
+                &mut responses,
+                &fri_params
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_min_size_claim() {
+        let test_data = create_test_data(1000);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
+
+        let claim = friVail
+            .prove_min_size(&commit_output, test_data.len(), &fri_params)
+            .expect("Failed to create size claim");
+        assert!(friVail
+            .verify_min_size(&claim, commitment_bytes, &fri_params)
+            .is_ok());
+
+        // More bytes than the commitment holds
+        let capacity = packed_mle_values.packed_values.len() * BYTES_PER_ELEMENT;
+        assert!(friVail
+            .prove_min_size(&commit_output, capacity + 1, &fri_params)
+            .is_err());
+        let mut inflated = claim.clone();
+        inflated.claimed_bytes = capacity + 1;
+        assert!(friVail
+            .verify_min_size(&inflated, commitment_bytes, &fri_params)
+            .is_err());
+
+        // Only the capacity is checked, so the padding counts too
+        let mut padded = claim.clone();
+        padded.claimed_bytes = capacity;
+        assert!(friVail
+            .verify_min_size(&padded, commitment_bytes, &fri_params)
+            .is_ok());
+
+        // A claim about another commitment is rejected
+        let mut other_root = commitment_bytes;
+        other_root[0] ^= 1;
+        assert!(friVail
+            .verify_min_size(&claim, other_root, &fri_params)
+            .is_err());
+    }
+
+    #[test]
+    fn test_evm_proof_round_trip() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let (terminate_codeword, query_prover, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+        let layers = query_prover
+            .vcs_optimal_layers()
+            .expect("Failed to get layers");
+        let evm_proof =
+            friVail.export_proof_evm(&transcript_bytes, terminate_codeword.as_scalars(), &layers);
+        let abi_bytes = evm_proof.to_abi_bytes();
+        assert_eq!(abi_bytes.len() % 32, 0);
+
+        let parsed = EvmProof::from_abi_bytes(&abi_bytes).expect("Failed to parse ABI bytes");
+        assert_eq!(parsed, evm_proof);
+
+        let (imported_transcript, imported_codeword, imported_layers) = friVail
+            .import_proof_evm(&parsed)
+            .expect("Failed to import EVM proof");
+        assert_eq!(imported_transcript, transcript_bytes);
+        assert_eq!(imported_codeword, terminate_codeword);
+        assert_eq!(imported_layers, layers);
+
+        let mut truncated = parsed.clone();
+        truncated.terminate_codeword_len += 2;
+        assert!(matches!(
+            friVail.import_proof_evm(&truncated),
+            Err(FriVailError::Deserialize(_))
+        ));
+
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        let mut extra_transcript = friVail
+            .open(0, &commit_output.commitment, &query_prover)
+            .expect("Failed to open");
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), imported_transcript);
+        let verify_result = friVail.verify(
+            &mut verifier_transcript,
+            evaluation_claim,
+            &evaluation_point,
+            &fri_params,
+            &ntt,
+            Some(0),
+            Some(&imported_codeword),
+            Some(&imported_layers),
+            Some(&mut extra_transcript),
+        );
+        assert!(
+            verify_result.is_ok(),
+            "Verification of imported proof failed: {:?}",
+            verify_result
+        );
+    }
+
+    #[test]
+    fn test_open_from_other_commitment_rejected() {
+        let packed_a = Utils::<B128>::new()
+            .bytes_to_packed_mle(&create_test_data(1024))
+            .expect("Failed to create packed MLE");
+        let data_b: Vec<u8> = create_test_data(1024).iter().map(|b| b ^ 0xff).collect();
+        let packed_b = Utils::<B128>::new()
+            .bytes_to_packed_mle(&data_b)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_a.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_a.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let commit_a = friVail
+            .commit(packed_a.packed_mle.clone(), fri_params.clone(), &ntt)
+            .expect("Failed to commit");
+        let commit_b = friVail
+            .commit(packed_b.packed_mle.clone(), fri_params.clone(), &ntt)
+            .expect("Failed to commit");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        let (terminate_codeword_b, query_prover_b, transcript_bytes_b, evaluation_claim_b) =
+            friVail
+                .prove(
+                    packed_b.packed_mle.clone(),
+                    &fri_params,
+                    &ntt,
+                    &commit_b,
+                    &evaluation_point,
+                )
+                .expect("Failed to generate proof");
+        let layers_b = query_prover_b
+            .vcs_optimal_layers()
+            .expect("Failed to get layers");
+
+        // A valid opening of B: commitment, context tag, then the decommitment
+        let opening_b = friVail.get_transcript_bytes(
+            &friVail
+                .open(0, &commit_b.commitment, &query_prover_b)
+                .expect("Failed to open"),
+        );
+        let verify_with_opening = |opening: Vec<u8>| {
+            let mut verifier_transcript =
+                VerifierTranscript::new(StdChallenger::default(), transcript_bytes_b.clone());
+            friVail.verify(
+                &mut verifier_transcript,
+                evaluation_claim_b,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                Some(0),
+                Some(&terminate_codeword_b),
+                Some(&layers_b),
+                Some(&mut friVail.reconstruct_transcript_from_bytes(opening)),
+            )
+        };
+        verify_with_opening(opening_b.clone()).expect("Opening of B should verify");
+
+        // Same decommitment re-bound to commitment A
+        let mut rebound = opening_b.clone();
+        rebound[..32].copy_from_slice(&commit_a.commitment);
+        assert_eq!(
+            verify_with_opening(rebound),
+            Err(VerifyError::UnboundQuery.into())
+        );
+
+        // Same opening with only the context tag changed
+        let mut retagged = opening_b;
+        retagged[32] ^= 1;
+        assert_eq!(
+            verify_with_opening(retagged),
+            Err(VerifyError::UnboundQuery.into())
+        );
+
+        // Opening B's query prover under A's commitment is refused outright
+        assert!(matches!(
+            friVail.open(0, &commit_a.commitment, &query_prover_b),
+            Err(FriVailError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_missing_layer_rejected() {
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&create_test_data(1024))
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+
+        let (terminate_codeword, query_prover, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+        let mut layers = query_prover
+            .vcs_optimal_layers()
+            .expect("Failed to get layers");
+        let mut extra_transcript = friVail
+            .open(0, &commit_output.commitment, &query_prover)
+            .expect("Failed to open");
+
+        // Drop the last round's layer, as a truncated network message would
+        layers.pop();
+
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
+        let verify_result = friVail.verify(
+            &mut verifier_transcript,
+            evaluation_claim,
+            &evaluation_point,
+            &fri_params,
+            &ntt,
+            Some(0),
+            Some(&terminate_codeword),
+            Some(&layers),
+            Some(&mut extra_transcript),
+        );
+
+        assert!(
+            matches!(
+                verify_result,
+                Err(FriVailError::Verification(VerifyError::LayerCount { .. }))
+            ),
+            "Missing layer should be rejected, got {:?}",
+            verify_result
+        );
+    }
+
+    #[test]
+    fn test_proof_context_separates_proofs() {
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&create_test_data(1024))
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        let (_, _, transcript_bytes, evaluation_claim) = friVail
+            .prove_with_context(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+                b"block-42/row-0",
+            )
+            .expect("Failed to generate proof");
+
+        let verify_under = |context: &[u8]| {
+            let mut verifier_transcript =
+                VerifierTranscript::new(StdChallenger::default(), transcript_bytes.clone());
+            friVail.verify_with_context(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+                context,
+            )
+        };
+
+        verify_under(b"block-42/row-0").expect("Proof should verify under its own context");
+        assert!(verify_under(b"block-42/row-1").is_err());
+        assert!(verify_under(&[]).is_err());
+
+        // An empty context leaves `prove` and `verify` unchanged
+        let (_, _, plain_bytes, _) = friVail
+            .prove_with_context(
+                packed_mle_values.packed_mle,
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+                &[],
+            )
+            .expect("Failed to generate proof");
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), plain_bytes);
+        friVail
+            .verify(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("Proof with empty context should verify with verify");
+    }
+
+    #[test]
+    fn test_verify_traced_reports_layer_mismatch() {
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&create_test_data(1024))
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        let (terminate_codeword, query_prover, transcript_bytes, evaluation_claim) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+        let layers = query_prover
+            .vcs_optimal_layers()
+            .expect("Failed to get layers");
+
+        let trace = |layers: &[Vec<digest::Output<StdDigest>>]| {
+            let mut extra_transcript = friVail
+                .open(0, &commit_output.commitment, &query_prover)
+                .expect("Failed to open");
+            let mut verifier_transcript =
+                VerifierTranscript::new(StdChallenger::default(), transcript_bytes.clone());
+            friVail.verify_traced(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                Some(0),
+                Some(&terminate_codeword),
+                Some(layers),
+                Some(&mut extra_transcript),
+            )
+        };
+
+        let (result, steps) = trace(&layers);
+        result.expect("Valid proof should verify");
+        assert_eq!(
+            steps.iter().map(|step| step.stage).collect::<Vec<_>>(),
+            vec![
+                VerifyStage::Header,
+                VerifyStage::EvaluationProof,
+                VerifyStage::LayerMatch,
+                VerifyStage::Query,
+            ]
+        );
+        assert!(steps.iter().all(|step| step.result.is_ok()));
+
+        let mut tampered = layers.clone();
+        tampered[0][0][0] ^= 1;
+        let (result, steps) = trace(&tampered);
+        let failed = steps.last().expect("Trace should not be empty");
+        assert_eq!(failed.stage, VerifyStage::LayerMatch);
+        assert_eq!(
+            failed.result,
+            Err(FriVailError::Verification(VerifyError::LayerMismatch))
+        );
+        assert_eq!(result, failed.result);
+        assert!(steps[..steps.len() - 1]
+            .iter()
+            .all(|step| step.result.is_ok()));
+    }
+    #[test]
+    fn test_evaluate_batch_matches_individual_evaluations() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let n_vars = packed_mle_values.packed_mle.log_len();
+        let friVail = TestFriVail::new(1, 3, 2, n_vars, 2);
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let points: Vec<Vec<B128>> = (0..10)
+            .map(|_| {
+                (0..n_vars)
+                    .map(|_| <B128 as Random>::random(&mut rng))
+                    .collect()
+            })
+            .collect();
+
+        let batch = friVail
+            .evaluate_batch(&packed_mle_values.packed_mle, &points)
+            .expect("Failed to evaluate batch");
+        assert_eq!(batch.len(), points.len());
+        for (point, value) in points.iter().zip(batch) {
+            let expected = friVail
+                .evaluate(&packed_mle_values.packed_mle, point)
+                .expect("Failed to evaluate");
+            assert_eq!(value, expected);
+            assert_eq!(
+                value,
+                friVail
+                    .calculate_evaluation_claim(&packed_mle_values.packed_values, point)
+                    .expect("Failed to calculate evaluation claim")
+            );
+        }
+
+        assert!(friVail
+            .evaluate_batch(
+                &packed_mle_values.packed_mle,
+                &[vec![B128::zero(); n_vars + 1]]
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_eq_cache_computes_eq_once() {
+        let n_vars = 6;
+        let friVail = TestFriVail::new(1, 3, 2, n_vars, 2);
+        let point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let mles: Vec<Vec<B128>> = (0..5u128)
+            .map(|k| {
+                (0..1u128 << n_vars)
+                    .map(|i| B128::from(i * 31 + k))
+                    .collect()
+            })
+            .collect();
+
+        let eq_computations = || EQ_COMPUTATIONS.with(|count| count.get());
+        let before = eq_computations();
+        let eq_cache = EqCache::for_point(&point);
+        let cached: Vec<B128> = mles
+            .iter()
+            .map(|values| {
+                friVail
+                    .calculate_evaluation_claim_cached(values, &eq_cache)
+                    .expect("Failed to calculate cached claim")
+            })
+            .collect();
+        assert_eq!(eq_computations() - before, 1);
+        assert_eq!(eq_cache.point(), point.as_slice());
+
+        for (values, cached_claim) in mles.iter().zip(cached) {
+            let uncached = friVail
+                .calculate_evaluation_claim(values, &point)
+                .expect("Failed to calculate evaluation claim");
+            assert_eq!(cached_claim, uncached);
+        }
+        assert_eq!(eq_computations() - before, 1 + mles.len());
+    }
+
+    #[test]
+    fn test_verify_with_precomputed_eq() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let (_, _, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let eq = friVail.precompute_eq(&evaluation_point);
+
+        // Precomputed claim matches the standard path
+        assert_eq!(
+            friVail
+                .calculate_evaluation_claim_with_eq(&packed_mle_values.packed_values, &eq)
+                .unwrap(),
+            friVail
+                .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+                .unwrap()
+        );
+
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes.clone());
+        friVail
+            .verify_with_eq(
+                &mut verifier_transcript,
+                &packed_mle_values.packed_values,
+                &evaluation_point,
+                &eq,
+                &fri_params,
+                &ntt,
+            )
+            .expect("Verification with precomputed eq failed");
+
+        // An eq computed for a different point yields a claim the verifier rejects
+        let mut wrong_point = evaluation_point.clone();
+        wrong_point[0] += B128::ONE;
+        let wrong_eq = friVail.precompute_eq(&wrong_point);
+
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
+        let verify_result = friVail.verify_with_eq(
+            &mut verifier_transcript,
+            &packed_mle_values.packed_values,
+            &evaluation_point,
+            &wrong_eq,
+            &fri_params,
+            &ntt,
+        );
+        assert!(
+            matches!(
+                verify_result,
+                Err(FriVailError::Verification(VerifyError::EvaluationProof))
+            ),
+            "Spartan verification should fail with a wrong eq"
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_commitments() {
+        let n_vars = Utils::<B128>::new()
+            .bytes_to_packed_mle(&create_test_data(1024))
+            .expect("Failed to create packed MLE")
+            .total_n_vars;
+        let friVail = TestFriVail::new(1, 3, 2, n_vars, 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        let mut packed_mles = Vec::new();
+        let mut commit_outputs = Vec::new();
+        for seed in 0..4u8 {
+            let data: Vec<u8> = create_test_data(1024)
+                .iter()
+                .map(|b| b.wrapping_add(seed))
+                .collect();
+            let packed_mle_values = Utils::<B128>::new()
+                .bytes_to_packed_mle(&data)
+                .expect("Failed to create packed MLE");
+            commit_outputs.push(
+                friVail
+                    .commit(
+                        packed_mle_values.packed_mle.clone(),
+                        fri_params.clone(),
+                        &ntt,
+                    )
+                    .expect("Failed to commit"),
+            );
+            packed_mles.push(packed_mle_values.packed_mle);
+        }
+        let roots: Vec<_> = commit_outputs.iter().map(|c| c.commitment).collect();
+
+        let prove = || {
+            friVail
+                .prove_batch(
+                    &packed_mles,
+                    &commit_outputs,
+                    &evaluation_point,
+                    &fri_params,
+                    &ntt,
+                )
+                .expect("Failed to prove batch")
+        };
+
+        let (claims, mut proof) = prove();
+        for (packed_mle, &claim) in packed_mles.iter().zip(&claims) {
+            assert_eq!(
+                friVail
+                    .evaluate(packed_mle, &evaluation_point)
+                    .expect("Failed to evaluate"),
+                claim
+            );
+        }
+        friVail
+            .batch_verify_commitments(
+                &roots,
+                &claims,
+                &evaluation_point,
+                &mut proof,
+                &fri_params,
+                &ntt,
+            )
+            .expect("Batch of valid claims should verify");
+
+        // One invalid claim makes the combined claim fail the single FRI check
+        let (mut claims, mut proof) = prove();
+        claims[2] += B128::ONE;
+        assert!(
+            matches!(
+                friVail.batch_verify_commitments(
+                    &roots,
+                    &claims,
+                    &evaluation_point,
+                    &mut proof,
+                    &fri_params,
+                    &ntt,
+                ),
+                Err(FriVailError::Verification(VerifyError::EvaluationProof))
+            ),
+            "Batch with an invalid claim should be rejected"
+        );
+
+        // A leaf that is not the committed one fails its inclusion proof
+        let (claims, mut proof) = prove();
+        proof.openings[0].leaves[1][0] += B128::ONE;
+        assert!(friVail
+            .batch_verify_commitments(
+                &roots,
+                &claims,
+                &evaluation_point,
+                &mut proof,
+                &fri_params,
+                &ntt,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_malformed_proof_rejected_before_spartan_verify() {
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        let (_terminate_codeword, _query_prover, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let spartan_calls = || SPARTAN_VERIFY_CALLS.with(|calls| calls.get());
+        let verify = |bytes: Vec<u8>, point: &[B128]| {
+            let mut verifier_transcript = friVail.reconstruct_transcript_from_bytes(bytes);
+            friVail.verify(
+                &mut verifier_transcript,
+                evaluation_claim,
+                point,
+                &fri_params,
+                &ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        // Transcript truncated inside the commitment header
+        assert!(verify(transcript_bytes[..16].to_vec(), &evaluation_point).is_err());
+        assert_eq!(spartan_calls(), 0);
+
+        // Evaluation point with too few coordinates
+        assert!(verify(transcript_bytes.clone(), &evaluation_point[1..]).is_err());
+        assert_eq!(spartan_calls(), 0);
+
+        verify(transcript_bytes, &evaluation_point).expect("Valid proof should verify");
+        assert_eq!(spartan_calls(), 1);
+    }
+
+    #[test]
+    fn test_verify_uses_embedded_folding_schedule() {
+        let test_data = create_test_data(2048);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let n_vars = packed_mle_values.packed_mle.log_len();
+
+        let prover = TestFriVail::new(1, 3, 4, n_vars, 3);
+        let (prover_params, prover_ntt) = prover
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+        let verifier = TestFriVail::new(1, 3, 2, n_vars, 3);
+        let (verifier_params, verifier_ntt) = verifier
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+        assert_ne!(prover_params.fold_arities(), verifier_params.fold_arities());
+
+        let commit_output = prover
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                prover_params.clone(),
+                &prover_ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = prover
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = prover
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        let (_terminate_codeword, _query_prover, transcript_bytes) = prover
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &prover_params,
+                &prover_ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let mut header_transcript =
+            verifier.reconstruct_transcript_from_bytes(transcript_bytes.clone());
+        let (_, embedded_params) = verifier
+            .verify_header(&mut header_transcript, &evaluation_point, &verifier_params)
+            .expect("Failed to read proof header");
+        assert_eq!(embedded_params.fold_arities(), prover_params.fold_arities());
+
+        let mut verifier_transcript = verifier.reconstruct_transcript_from_bytes(transcript_bytes);
+        verifier
+            .verify(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &verifier_params,
+                &verifier_ntt,
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("Proof should verify under the embedded schedule");
+    }
+
+    #[test]
+    fn test_fri_folding_challenges_match_across_sides() {
+        let test_data = create_test_data(2048);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let n_vars = packed_mle_values.packed_mle.log_len();
+
+        let prover = TestFriVail::new(1, 3, 2, n_vars, 3);
+        let (fri_params, ntt) = prover
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+        let verifier = TestFriVail::new(1, 3, 2, n_vars, 3);
+        let (verifier_params, _verifier_ntt) = verifier
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+
+        let commit_output = prover
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = prover
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = prover
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        let (_terminate_codeword, _query_prover, transcript_bytes) = prover
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let mut prover_transcript =
+            prover.reconstruct_transcript_from_bytes(transcript_bytes.clone());
+        let prover_challenges = prover
+            .fri_folding_challenges(
+                &mut prover_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+            )
+            .expect("Failed to replay prover transcript");
+
+        let mut verifier_transcript =
+            verifier.reconstruct_transcript_from_bytes(transcript_bytes.clone());
+        let verifier_challenges = verifier
+            .fri_folding_challenges(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &verifier_params,
+            )
+            .expect("Failed to replay verifier transcript");
+
+        assert!(!prover_challenges.is_empty());
+        assert_eq!(prover_challenges, verifier_challenges);
+
+        // A wrong claim does not yield challenges
+        let mut tampered_transcript = verifier.reconstruct_transcript_from_bytes(transcript_bytes);
+        assert!(verifier
+            .fri_folding_challenges(
+                &mut tampered_transcript,
+                evaluation_claim + B128::ONE,
+                &evaluation_point,
+                &verifier_params,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_with_root_accepts_any_root_representation() {
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        let evaluation_claim = friVail
+            .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
+            .expect("Failed to calculate evaluation claim");
+        let (_terminate_codeword, _query_prover, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        let root = CommitmentRoot::from(commit_output.commitment);
+        let from_hex: CommitmentRoot = root.to_string().parse().expect("Failed to parse hex");
+        let from_vec =
+            CommitmentRoot::try_from(commit_output.commitment.to_vec()).expect("Wrong length");
+        for expected in [root, from_hex, from_vec] {
+            let mut verifier_transcript =
+                friVail.reconstruct_transcript_from_bytes(transcript_bytes.clone());
+            friVail
+                .verify_with_root(
+                    &mut verifier_transcript,
+                    evaluation_claim,
+                    &evaluation_point,
+                    &fri_params,
+                    &ntt,
+                    expected,
+                )
+                .expect("Proof should verify against its root");
+        }
+
+        let mut verifier_transcript = friVail.reconstruct_transcript_from_bytes(transcript_bytes);
+        assert!(friVail
+            .verify_with_root(
+                &mut verifier_transcript,
+                evaluation_claim,
+                &evaluation_point,
+                &fri_params,
+                &ntt,
+                [0u8; 32],
+            )
+            .is_err());
+
+        // Inclusion proofs take the same root representations
+        let mut inclusion_proof = friVail
+            .inclusion_proof(&commit_output.committed, 0)
+            .expect("Failed to generate inclusion proof");
+        friVail
+            .verify_inclusion_proof(
+                &mut inclusion_proof,
+                &[commit_output.codeword[0]],
+                0,
+                &fri_params,
+                root,
+            )
+            .expect("Inclusion proof should verify against CommitmentRoot");
+    }
+
+    #[test]
+    fn test_invalid_verification_fails() {
+        // Create test data
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+
+        let (_terminate_codeword, _query_prover, transcript_bytes, _) = friVail
+            .prove(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+            )
+            .expect("Failed to generate proof");
+
+        // Reconstruct verifier transcript from bytes
+        let mut verifier_transcript =
+            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
+
+        // Use wrong evaluation claim (should cause verification to fail)
+        let wrong_evaluation_claim = B128::from(42u128);
+
+        let verify_result = friVail.verify(
+            &mut verifier_transcript,
+            wrong_evaluation_claim,
+            &evaluation_point,
+            &fri_params,
+            &ntt, // ntt instance
+            None,
+            None,
+            None,
+            None, // no extra transcript
+        );
+
+        // Verification should fail with wrong claim
+        assert!(
+            verify_result.is_err(),
+            "Verification should fail with wrong evaluation claim"
+        );
+    }
+
+    #[test]
+    fn test_data_availability_sampling() {
+        use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+        use tracing::Level;
+
+        // Initialize logging for the test
+        let _ = tracing_subscriber::fmt()
+            .with_max_level(Level::DEBUG)
+            .with_test_writer()
+            .try_init();
+
+        // Create test data
+        let test_data = create_test_data(512); // 512 bytes test data
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+
+        // Initialize FRI context
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        // Commit to MLE
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        println!(
+            "commit output codeword len {:?}",
+            commit_output.codeword.len()
+        );
+
+        let total_samples = commit_output.codeword.len();
+        let sample_size = std::cmp::min(5, total_samples / 4); // Limit to 5 samples or 1/4 of total
+        let indices =
+            sample(&mut StdRng::from_seed([0; 32]), total_samples, sample_size).into_vec();
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
+
+        let mut successful_samples = 0;
+        let mut failed_samples = Vec::new();
+
+        for &sample_index in indices.iter() {
+            println!("sample index {sample_index}");
+            match friVail.inclusion_proof(&commit_output.committed, sample_index) {
+                Ok(mut inclusion_proof) => {
+                    let value = commit_output.codeword[sample_index];
+                    match friVail.verify_inclusion_proof(
+                        &mut inclusion_proof,
+                        &[value],
+                        sample_index,
+                        &fri_params,
+                        commitment_bytes,
+                    ) {
+                        Ok(_) => {
+                            successful_samples += 1;
+                        }
+                        Err(e) => {
+                            failed_samples
+                                .push((sample_index, format!("Verification failed: {}", e)));
+                        }
+                    }
+                }
+                Err(e) => {
+                    failed_samples.push((
+                        sample_index,
+                        format!("Inclusion proof generation failed: {}", e),
+                    ));
+                }
+            }
+        }
+
+        assert_eq!(failed_samples.len(), 0, "Some samples failed verification");
+        assert_eq!(
+            successful_samples, sample_size,
+            "Not all samples were verified"
+        );
+
+        println!("Successfully verified {} samples", successful_samples);
+    }
+
+    #[test]
+    fn test_sample_indices_with_stratified_strategy() {
+        use crate::sampling::{StratifiedSampling, UniformSampling};
+
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(packed_mle_values.packed_mle, fri_params.clone(), &ntt)
+            .expect("Failed to commit");
+        let commitment = CommitmentRoot::from(commit_output.commitment);
+
+        let log_buckets = 2;
+        let bucket_len = commit_output.codeword.len() >> log_buckets;
+        let indices = friVail.sample_indices(
+            &StratifiedSampling::new(log_buckets),
+            &commit_output,
+            8,
+            [9; 32],
+        );
+        assert_eq!(indices.len(), 8);
+
+        let mut buckets_hit = HashSet::new();
+        for &index in &indices {
+            buckets_hit.insert(index / bucket_len);
+            let mut inclusion_proof = friVail
+                .inclusion_proof(&commit_output.committed, index)
+                .expect("Failed to generate inclusion proof");
+            friVail
+                .verify_inclusion_proof(
+                    &mut inclusion_proof,
+                    &[commit_output.codeword[index]],
+                    index,
+                    &fri_params,
+                    commitment,
+                )
+                .expect("Sampled index should verify");
+        }
+        assert_eq!(buckets_hit.len(), 1 << log_buckets);
+
+        let uniform = friVail.sample_indices(&UniformSampling, &commit_output, 8, [9; 32]);
+        assert_eq!(uniform.len(), 8);
+    }
+    #[test]
+    #[cfg(feature = "ed25519")]
+    fn test_availability_certificate_tamper_detection() {
+        use crate::sampling::AvailabilityReport;
+        use ed25519_dalek::SigningKey;
+
+        let friVail = TestFriVail::new(1, 3, 2, 10, 2);
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let verifying_key = signing_key.verifying_key();
+
+        let report = AvailabilityReport {
+            root: [0xab; 32],
+            sample_indices: vec![3, 17, 42],
+        };
+        let cert = friVail.issue_certificate(&report, &signing_key);
+        assert_eq!(cert.root, report.root);
+        assert_eq!(cert.sample_indices, report.sample_indices);
+        assert!(friVail.verify_certificate(&cert, &verifying_key));
+
+        let mut tampered_root = cert.clone();
+        tampered_root.root[0] ^= 1;
+        assert!(!friVail.verify_certificate(&tampered_root, &verifying_key));
+
+        let mut tampered_samples = cert.clone();
+        tampered_samples.sample_indices.push(99);
+        assert!(!friVail.verify_certificate(&tampered_samples, &verifying_key));
+
+        let other_key = SigningKey::from_bytes(&[8u8; 32]).verifying_key();
+        assert!(!friVail.verify_certificate(&cert, &other_key));
+    }
+
+    #[test]
+    fn test_verify_ntt_inverse_correctness() {
+        for log_len in 4..=10 {
+            let friVail = TestFriVail::new(1, 3, 2, log_len - 1, 2);
+            let (_fri_params, ntt) = friVail
+                .initialize_fri_context(log_len - 1)
+                .expect("Failed to initialize FRI context");
+
+            friVail
+                .verify_ntt_inverse_correctness(&ntt, log_len, 0)
+                .unwrap_or_else(|e| panic!("log_len {}: {}", log_len, e));
+        }
+    }
+
+    #[test]
+    fn test_codeword_decode() {
+        // Create test data
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+
+        // Initialize FRI context
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        // Encode codeword
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+
+        // Decode codeword
+        let decoded_codeword = friVail
+            .decode_codeword(
+                &encoded_codeword,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to decode codeword");
+
+        // Verify decoded codeword matches original values
+        assert_eq!(
+            decoded_codeword, packed_mle_values.packed_values,
+            "Decoded codeword should match original packed values"
+        );
+
+        println!("✅ Codeword decode test passed");
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_decode_codeword_zeroizing() {
+        use zeroize::Zeroize;
+
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+
+        let mut decoded = friVail
+            .decode_codeword_zeroizing(&encoded_codeword, fri_params, &ntt)
+            .expect("Failed to decode codeword");
+        assert_eq!(&decoded[..], &packed_mle_values.packed_values[..]);
+
+        // Dropping runs the same zeroize, inspected here before the memory is freed
+        decoded.zeroize();
+        assert!(decoded.iter().all(|&value| value == B128::zero()));
+    }
+
+    #[test]
+    fn test_decode_codeword_rejects_wrong_length() {
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+        let len = encoded_codeword.len();
+
+        // Too short and too long inputs are both rejected before any output is read
+        for data in [
+            &encoded_codeword[..len / 2],
+            &[encoded_codeword.clone(), encoded_codeword.clone()].concat()[..],
+        ] {
+            assert_eq!(
+                friVail.decode_codeword(
+                    data,
+                    fri_params.clone(),
+                    &ntt,
+                    EncodingLayout::BitReversed
+                ),
+                Err(FriVailError::Decode {
+                    expected: len,
+                    got: data.len(),
+                })
+            );
+        }
+
+        // An output buffer of the wrong size is rejected as well
+        let rs_code = fri_params.rs_code();
+        let mut output = Vec::with_capacity(len - 1);
+        let result = friVail.decode_batch(
+            rs_code.log_len(),
+            rs_code.log_inv_rate(),
+            fri_params.log_batch_size(),
+            &ntt,
+            &encoded_codeword,
+            &mut output.spare_capacity_mut()[..len - 1],
+        );
+        assert_eq!(
+            result,
+            Err(FriVailError::Decode {
+                expected: len,
+                got: len - 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_trims_to_original_byte_len() {
+        let test_data = synthetic_blob(1500, DataPattern::Random(7));
+        let utils = Utils::<B128>::new();
+        let packed_mle_values = utils
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        assert_eq!(packed_mle_values.original_byte_len, 1500);
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let decoded_values = friVail
+            .decode_codeword(
+                &commit_output.codeword,
+                fri_params,
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to decode codeword");
+        let decoded_bytes =
+            utils.values_to_bytes(&decoded_values, packed_mle_values.original_byte_len);
+
+        // 1500 bytes leave a partially filled last element
+        assert_eq!(decoded_bytes.len(), 1500);
+        assert_eq!(decoded_bytes, test_data);
+    }
+
+    #[test]
+    fn test_decode_payload_keeps_trailing_zeros() {
+        // The last 40 bytes are genuine zeros: two whole elements and half of a third
+        let mut test_data = synthetic_blob(1504, DataPattern::Random(11));
+        test_data[1464..].fill(0);
+        let utils = Utils::<B128>::new();
+        let packed_mle_values = utils
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commitment = friVail
+            .commit_payload(
+                packed_mle_values.packed_mle.clone(),
+                packed_mle_values.original_byte_len,
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        assert_eq!(commitment.num_data_elements(), 94);
+
+        let decoded = friVail
+            .decode_payload(
+                &commitment.output.codeword,
+                commitment.original_byte_len,
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to decode payload");
+        assert_eq!(decoded.len(), 94);
+        assert_eq!(decoded, packed_mle_values.packed_values[..94]);
+        assert_eq!(decoded[92..], [B128::zero(); 2]);
+        assert_eq!(
+            utils.values_to_bytes(&decoded, commitment.original_byte_len),
+            test_data
+        );
+
+        // The full decode still carries the padding up to a power of two
+        let padded = friVail
+            .decode_codeword(
+                &commitment.output.codeword,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to decode codeword");
+        assert_eq!(padded.len(), 128);
+
+        assert!(matches!(
+            friVail.decode_payload(&commitment.output.codeword, 128 * 16 + 1, fri_params, &ntt),
+            Err(FriVailError::Decode {
+                expected: 129,
+                got: 128
+            })
+        ));
+    }
+
+    #[test]
+    fn test_custom_padding_is_stripped_after_decode() {
+        // 900 bytes fill 57 elements, padded to 64
+        let test_data = create_test_data(900);
+        let pad = B128::from(0x80u128);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle_with_padding(&test_data, pad)
+            .expect("Failed to create packed MLE");
+        assert_eq!(packed_mle_values.num_data_elements(), 57);
+        assert_eq!(packed_mle_values.num_padding_elements(), 7);
+        assert!(packed_mle_values.packed_values[57..]
+            .iter()
+            .all(|&value| value == pad));
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+        let decoded = friVail
+            .decode_codeword(
+                &commit_output.codeword,
+                fri_params,
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to decode codeword");
+
+        assert_eq!(
+            packed_mle_values.strip_padding(&decoded),
+            &packed_mle_values.packed_values[..57]
+        );
+
+        // Zero padding is still the default
+        let zero_padded = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        assert_eq!(zero_padded.num_padding_elements(), 7);
+        assert!(zero_padded.packed_values[57..]
+            .iter()
+            .all(|&value| value == B128::zero()));
+    }
+    #[test]
+    fn test_decode_codeword_buffer_recommits_to_same_root() {
+        let test_data = create_test_data(4096);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let buffer = friVail
+            .decode_codeword_buffer(&commit_output.codeword, &fri_params, &ntt)
+            .expect("Failed to decode codeword");
+        assert_eq!(
+            buffer.iter_scalars().collect::<Vec<_>>(),
+            friVail
+                .decode_codeword(
+                    &commit_output.codeword,
+                    fri_params.clone(),
+                    &ntt,
+                    EncodingLayout::BitReversed
+                )
+                .expect("Failed to decode codeword")
+        );
+
+        let recommitted = friVail
+            .commit(buffer, fri_params, &ntt)
+            .expect("Failed to commit decoded buffer");
+        assert_eq!(recommitted.commitment, commit_output.commitment);
+    }
+
+    #[test]
+    fn test_encode_codeword_into_matches_allocating() {
+        let friVail = TestFriVail::new(1, 3, 2, 8, 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(8)
+            .expect("Failed to initialize FRI context");
+
+        // The buffer is reused across messages, starting with stale contents
+        let mut out = vec![B128::ONE; 3];
+        let mut reused_capacity = None;
+        for seed in 0..3 {
+            let packed_mle_values = Utils::<B128>::new()
+                .bytes_to_packed_mle(&synthetic_blob(4096, DataPattern::Random(seed)))
+                .expect("Failed to create packed MLE");
+
+            let encoded = friVail
+                .encode_codeword(
+                    &packed_mle_values.packed_values,
+                    fri_params.clone(),
+                    &ntt,
+                    EncodingLayout::BitReversed,
+                )
+                .expect("Failed to encode codeword");
+            friVail
+                .encode_codeword_into(
+                    &packed_mle_values.packed_values,
+                    &fri_params,
+                    &ntt,
+                    &mut out,
+                )
+                .expect("Failed to encode codeword");
+            assert_eq!(out, encoded);
+            // Same-sized messages reuse the allocation from the first call
+            let capacity = *reused_capacity.get_or_insert(out.capacity());
+            assert_eq!(out.capacity(), capacity);
+        }
+    }
+
+    #[test]
+    fn test_detect_errors_at_unknown_positions() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        assert_eq!(
+            friVail
+                .detect_errors(&commit_output.codeword, &fri_params, &ntt)
+                .expect("Failed to check codeword"),
+            Vec::<usize>::new()
+        );
+
+        let mut corrupted = commit_output.codeword.clone();
+        let flipped = vec![3, 17, 40, 41, 99];
+        for &index in &flipped {
+            corrupted[index] += B128::from(0xdead_beef_u128 + index as u128);
+        }
+        assert_eq!(
+            friVail
+                .detect_errors(&corrupted, &fri_params, &ntt)
+                .expect("Failed to locate errors"),
+            flipped
+        );
+
+        // Located errors can be repaired as erasures
+        friVail
+            .reconstruct_codeword_naive(&mut corrupted, &flipped, None)
+            .expect("Failed to reconstruct codeword");
+        assert_eq!(corrupted, commit_output.codeword);
+    }
+
+    #[test]
+    fn test_correct_errors_up_to_half_the_redundancy() {
+        let test_data = create_test_data(1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        let n = commit_output.codeword.len();
+        let max_errors = (n - packed_mle_values.packed_values.len()) / 2;
+        let corrupt = |num_errors: usize| {
+            let mut corrupted = commit_output.codeword.clone();
+            let mut rng = StdRng::seed_from_u64(num_errors as u64);
+            for index in rand::seq::index::sample(&mut rng, n, num_errors) {
+                corrupted[index] += B128::from(rng.random::<u128>() | 1);
+            }
+            corrupted
+        };
+
+        for num_errors in [0, max_errors] {
+            let mut corrupted = corrupt(num_errors);
+            let corrected = friVail
+                .correct_errors(&mut corrupted, &fri_params, &ntt, max_errors)
+                .expect("Failed to correct errors");
+            assert_eq!(corrected, num_errors);
+            assert_eq!(corrupted, commit_output.codeword);
+            assert_eq!(
+                friVail
+                    .decode_codeword(
+                        &corrupted,
+                        fri_params.clone(),
+                        &ntt,
+                        EncodingLayout::BitReversed
+                    )
+                    .expect("Failed to decode codeword"),
+                packed_mle_values.packed_values
+            );
+        }
+
+        let mut corrupted = corrupt(max_errors + 1);
+        assert!(matches!(
+            friVail.correct_errors(&mut corrupted, &fri_params, &ntt, max_errors),
+            Err(FriVailError::Unrecoverable { .. })
+        ));
+        assert!(matches!(
+            friVail.correct_errors(&mut corrupted, &fri_params, &ntt, max_errors + 1),
+            Err(FriVailError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_encoding_layouts_round_trip() {
+        let test_data = create_test_data(4096);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+            )
+            .expect("Failed to commit");
+
+        // The default layout reproduces the committed codeword
+        let bit_reversed = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::default(),
+            )
+            .expect("Failed to encode codeword");
+        assert_eq!(bit_reversed, commit_output.codeword);
+        assert_eq!(
+            friVail
+                .decode_codeword(
+                    &bit_reversed,
+                    fri_params.clone(),
+                    &ntt,
+                    EncodingLayout::BitReversed
+                )
+                .expect("Failed to decode codeword"),
+            packed_mle_values.packed_values
+        );
+
+        let natural = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::NaturalOrder,
+            )
+            .expect("Failed to encode codeword");
+        assert_ne!(natural, bit_reversed);
+        assert_eq!(
+            friVail
+                .decode_codeword(
+                    &natural,
+                    fri_params.clone(),
+                    &ntt,
+                    EncodingLayout::NaturalOrder
+                )
+                .expect("Failed to decode codeword"),
+            packed_mle_values.packed_values
+        );
+    }
+
+    #[test]
+    fn test_codewords_encode_same_data_across_rates() {
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        let n_vars = packed_mle_values.packed_mle.log_len();
+
+        let rate_two = TestFriVail::new(1, 3, 2, n_vars, 3);
+        let (params_two, ntt_two) = rate_two
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+        let rate_four = TestFriVail::new(2, 3, 2, n_vars, 3);
+        let (params_four, ntt_four) = rate_four
+            .initialize_fri_context(n_vars)
+            .expect("Failed to initialize FRI context");
+
+        let codeword_two = rate_two
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                params_two.clone(),
+                &ntt_two,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+        let codeword_four = rate_four
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                params_four.clone(),
+                &ntt_four,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+        assert_eq!(codeword_four.len(), 2 * codeword_two.len());
+
+        assert!(rate_four
+            .codewords_encode_same_data(
+                &codeword_two,
+                &params_two,
+                &codeword_four,
+                &params_four,
+                &ntt_four
+            )
+            .expect("Failed to compare codewords"));
+
+        let mut other_values = packed_mle_values.packed_values.clone();
+        other_values[0] += B128::ONE;
+        let other_codeword = rate_four
+            .encode_codeword(
+                &other_values,
+                params_four.clone(),
+                &ntt_four,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+        assert!(!rate_four
+            .codewords_encode_same_data(
+                &codeword_two,
+                &params_two,
+                &other_codeword,
+                &params_four,
+                &ntt_four
+            )
+            .expect("Failed to compare codewords"));
+    }
+
+    #[test]
+    fn test_error_correction_reconstruction() {
+        use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+
+        // Create test data
+        let test_data = create_test_data(2048);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+
+        // Initialize FRI context
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        // Encode codeword
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+
+        // Corrupt the codeword
+        let mut corrupted_codeword = encoded_codeword.clone();
+        let total_elements = corrupted_codeword.len();
+        let corruption_percentage = 0.1;
+
+        // Corrupt random elements
+        let num_corrupted = (total_elements as f64 * corruption_percentage) as usize;
+        let mut rng = StdRng::seed_from_u64(42);
+        let corrupted_indices = sample(&mut rng, total_elements, num_corrupted).into_vec();
+
+        for &index in &corrupted_indices {
+            corrupted_codeword[index] = B128::zero();
+        }
+
+        // Verify corruption happened
+        assert_ne!(
+            corrupted_codeword, encoded_codeword,
+            "Codeword should be corrupted"
+        );
+
+        // Reconstruct corrupted codeword
+        friVail
+            .reconstruct_codeword_naive(&mut corrupted_codeword, &corrupted_indices, None)
+            .expect("Failed to reconstruct codeword");
+
+        // Verify reconstruction succeeded
+        assert_eq!(
+            corrupted_codeword, encoded_codeword,
+            "Reconstructed codeword should match original encoded codeword"
+        );
+
+        // Decode the reconstructed codeword to verify it's correct
+        let decoded_reconstructed = friVail
+            .decode_codeword(
+                &corrupted_codeword,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to decode reconstructed codeword");
+
+        // Verify decoded reconstructed codeword matches original values
+        assert_eq!(
+            decoded_reconstructed, packed_mle_values.packed_values,
+            "Decoded reconstructed codeword should match original packed values"
+        );
+
+        println!(
+            "✅ Error correction reconstruction test passed: {} elements, {:.1}% corruption",
+            total_elements,
+            corruption_percentage * 100.0
+        );
+    }
+
+    #[test]
+    fn test_known_points_on_large_codeword() {
+        use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+
+        // Interpolating 2^16 points naively is far too slow for a unit test, so
+        // this covers the gather that used to be quadratic in the codeword length
+        let len = 1 << 16;
+        let codeword: Vec<B128> = (0..len as u128).map(B128::from).collect();
+        let mut rng = StdRng::seed_from_u64(42);
+        let erased = sample(&mut rng, len, len / 10).into_vec();
+
+        let known = codec::known_points(&codeword, &codec::codeword_domain::<B128>(len), &erased)
+            .expect("Failed to collect known points");
+
+        assert_eq!(known.len(), len - erased.len());
+        let erased: HashSet<usize> = erased.into_iter().collect();
+        let domain = codec::codeword_domain::<B128>(len);
+        let expected: Vec<(B128, B128)> = (0..len)
+            .filter(|i| !erased.contains(i))
+            .map(|i| (domain[i], codeword[i]))
+            .collect();
+        assert_eq!(known, expected);
+    }
+
+    #[test]
+    fn test_fft_reconstruction_matches_naive() {
+        use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+
+        let test_data = create_test_data(2048);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+
+        // 10% of positions erased, with garbage left in place
+        let total_elements = encoded_codeword.len();
+        let mut rng = StdRng::seed_from_u64(42);
+        let corrupted_indices = sample(&mut rng, total_elements, total_elements / 10).into_vec();
+        let mut corrupted_codeword = encoded_codeword.clone();
+        for &index in &corrupted_indices {
+            corrupted_codeword[index] = B128::from(index as u128 + 7);
+        }
+
+        let mut naive_codeword = corrupted_codeword.clone();
+        friVail
+            .reconstruct_codeword_naive(&mut naive_codeword, &corrupted_indices, None)
+            .expect("Failed to reconstruct codeword");
+        let mut fft_codeword = corrupted_codeword.clone();
+        friVail
+            .reconstruct_codeword_fft(&mut fft_codeword, &corrupted_indices)
+            .expect("Failed to reconstruct codeword");
+
+        assert_eq!(fft_codeword, naive_codeword);
+        assert_eq!(fft_codeword, encoded_codeword);
+
+        // Up to the erasure bound, as in DAS with half the codeword missing
+        let max = friVail.max_recoverable_erasures(&fri_params);
+        let corrupted_indices = sample(&mut rng, total_elements, max).into_vec();
+        let mut fft_codeword = encoded_codeword.clone();
+        for &index in &corrupted_indices {
+            fft_codeword[index] = B128::zero();
+        }
+        friVail
+            .reconstruct_codeword_fft(&mut fft_codeword, &corrupted_indices)
+            .expect("Failed to reconstruct codeword");
+        assert_eq!(fft_codeword, encoded_codeword);
+
+        let erased_all: Vec<usize> = (0..total_elements).collect();
+        assert_eq!(
+            friVail.reconstruct_codeword_fft(&mut fft_codeword, &erased_all),
+            Err(FriVailError::Reconstruction { known_points: 0 })
+        );
+    }
+
+    /// Lagrange interpolation recomputing every denominator, as reconstruction did
+    /// before the denominators were shared across erased positions
+    fn interpolate_per_position(x_e: B128, known: &[(B128, B128)]) -> B128 {
+        let mut value = B128::zero();
+        for (j, &(x_j, y_j)) in known.iter().enumerate() {
+            let mut l_j = B128::ONE;
+            for (m, &(x_m, _)) in known.iter().enumerate() {
+                if m != j {
+                    l_j = l_j * (x_e - x_m) * (x_j - x_m).invert().unwrap();
+                }
+            }
+            value += y_j * l_j;
+        }
+        value
+    }
+
+    #[test]
+    fn test_reconstruction_domain_matches_encoder() {
+        use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+
+        let test_data = create_test_data(4096);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+
+        // The interpolation points are the encoder's subspace, prefixes included
+        let domain_dim = friVail.reconstruction_params(&fri_params).domain_dim;
+        let subspace: Vec<B128> = BinarySubspace::<B128>::with_dim(domain_dim)
+            .iter()
+            .collect();
+        assert_eq!(
+            codec::codeword_domain::<B128>(encoded_codeword.len()),
+            subspace
+        );
+        assert_eq!(codec::codeword_domain::<B128>(100), subspace[..100]);
+
+        // Erase the most the code can recover, leaving garbage in place
+        let max = friVail.max_recoverable_erasures(&fri_params);
+        let mut rng = StdRng::seed_from_u64(5);
+        let erased = sample(&mut rng, encoded_codeword.len(), max).into_vec();
+        let mut corrupted_codeword = encoded_codeword.clone();
+        for &index in &erased {
+            corrupted_codeword[index] = B128::from(index as u128 + 1);
+        }
+
+        friVail
+            .reconstruct_codeword_naive(&mut corrupted_codeword, &erased, None)
+            .expect("Failed to reconstruct codeword");
+        assert_eq!(corrupted_codeword, encoded_codeword);
+
+        let decoded = friVail
+            .decode_codeword(
+                &corrupted_codeword,
+                fri_params,
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to decode reconstructed codeword");
+        assert_eq!(decoded, packed_mle_values.packed_values);
+    }
+
+    #[test]
+    fn test_shared_denominators_match_per_position_interpolation() {
+        let test_data = create_test_data(2048);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
+
+        let corrupted_indices: Vec<usize> = (0..encoded_codeword.len()).step_by(9).collect();
+        let mut corrupted_codeword = encoded_codeword.clone();
+        for &index in &corrupted_indices {
+            corrupted_codeword[index] = B128::from(index as u128 + 7);
+        }
+
+        let domain = codec::codeword_domain::<B128>(corrupted_codeword.len());
+        let known = codec::known_points(&corrupted_codeword, &domain, &corrupted_indices)
+            .expect("Failed to collect known points");
+        let mut expected = corrupted_codeword.clone();
+        for &index in &corrupted_indices {
+            expected[index] = interpolate_per_position(domain[index], &known);
+        }
+
+        friVail
+            .reconstruct_codeword_naive(&mut corrupted_codeword, &corrupted_indices, None)
+            .expect("Failed to reconstruct codeword");
+        assert_eq!(corrupted_codeword, expected);
+        assert_eq!(corrupted_codeword, encoded_codeword);
+    }
+
+    #[test]
+    fn test_decode_batch_matches_sequential_butterflies() {
+        use binius_math::ntt::DomainContext;
+
+        for size in [256, 4096, 64 * 1024] {
+            let packed_mle_values = Utils::<B128>::new()
+                .bytes_to_packed_mle(&synthetic_blob(size, DataPattern::Random(size as u64)))
+                .expect("Failed to create packed MLE");
+
+            let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+            let (fri_params, ntt) = friVail
+                .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+                .expect("Failed to initialize FRI context");
+            let commit_output = friVail
+                .commit(
+                    packed_mle_values.packed_mle.clone(),
+                    fri_params.clone(),
+                    &ntt,
+                )
+                .expect("Failed to commit");
+            let rs_code = fri_params.rs_code();
+            let log_batch_size = fri_params.log_batch_size();
+
+            let mut output = Vec::with_capacity(commit_output.codeword.len());
+            let initialized = friVail
+                .decode_batch(
+                    rs_code.log_len(),
+                    rs_code.log_inv_rate(),
+                    log_batch_size,
+                    &ntt,
+                    &commit_output.codeword,
+                    &mut output.spare_capacity_mut()[..commit_output.codeword.len()],
+                )
+                .expect("Failed to decode codeword");
+            unsafe {
+                // Safety: decode_batch initialized the first `initialized` elements
+                output.set_len(initialized);
+            }
+
+            // Sequential reference of the same inverse butterflies
+            let mut expected = commit_output.codeword.clone();
+            let log_d = rs_code.log_len() + log_batch_size;
+            for layer in (rs_code.log_inv_rate()..(log_d - log_batch_size)).rev() {
+                let block_size_half = 1 << (log_d - layer - 1);
+                for block in 0..1 << layer {
+                    let twiddle = ntt.domain_context().twiddle(layer, block);
+                    let block_start = block << (log_d - layer);
+                    for idx0 in block_start..(block_start + block_size_half) {
+                        let idx1 = block_size_half | idx0;
+                        expected[idx1] += expected[idx0];
+                        let v = expected[idx1];
+                        expected[idx0] += v * twiddle;
+                    }
+                }
+            }
+
+            assert_eq!(output, expected, "Mismatch for {} bytes", size);
+        }
+    }
+
+    #[test]
+    fn test_inverse_rs_decode_matches_decode_batch() {
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&create_test_data(4096))
+            .expect("Failed to create packed MLE");
+
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let commit_output = friVail
+            .commit(packed_mle_values.packed_mle, fri_params.clone(), &ntt)
+            .expect("Failed to commit");
+        let rs_code = fri_params.rs_code();
+        let len = commit_output.codeword.len();
+
+        let mut from_method = Vec::with_capacity(len);
+        let initialized = friVail
+            .decode_batch(
+                rs_code.log_len(),
+                rs_code.log_inv_rate(),
+                fri_params.log_batch_size(),
+                &ntt,
+                &commit_output.codeword,
+                &mut from_method.spare_capacity_mut()[..len],
+            )
+            .expect("Failed to decode codeword");
+        unsafe {
+            // Safety: decode_batch initialized the first `initialized` elements
+            from_method.set_len(initialized);
+        }
+
+        let mut from_function = Vec::with_capacity(len);
+        inverse_rs_decode(
+            rs_code.log_len(),
+            rs_code.log_inv_rate(),
+            fri_params.log_batch_size(),
+            &ntt,
+            &commit_output.codeword,
+            &mut from_function.spare_capacity_mut()[..len],
+        )
+        .expect("Failed to decode codeword");
+        unsafe {
+            // Safety: inverse_rs_decode initialized the whole output on success
+            from_function.set_len(len);
+        }
+
+        assert_eq!(from_function, from_method);
+
+        // Wrong lengths are rejected before any output is written
+        let mut short = Vec::<B128>::with_capacity(len);
+        assert!(matches!(
+            inverse_rs_decode(
+                rs_code.log_len(),
+                rs_code.log_inv_rate(),
+                fri_params.log_batch_size(),
+                &ntt,
+                &commit_output.codeword[..len - 1],
+                &mut short.spare_capacity_mut()[..len],
+            ),
+            Err(FriVailError::Decode { .. })
+        ));
+    }
     #[test]
-    fn test_initialize_fri_context() {
-        let friVail = TestFriVail::new(1, 3, 2, 12, 2);
+    fn test_reconstruct_from_samples() {
+        use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
 
-        // Create test data
-        let test_data = create_test_data(1024); // 1KB test data
+        let test_data = create_test_data(2048);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
-        let result = friVail.initialize_fri_context(packed_mle_values.packed_mle.log_len());
-        assert!(result.is_ok());
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
 
-        let (fri_params, _ntt) = result.unwrap();
+        let info = friVail.code_info(&fri_params);
+        let dimension = 1 << (info.log_dim + info.log_batch_size);
+        let mut rng = StdRng::seed_from_u64(42);
+        let samples: Vec<(usize, B128)> = sample(&mut rng, encoded_codeword.len(), dimension)
+            .into_iter()
+            .map(|index| (index, encoded_codeword[index]))
+            .collect();
 
-        // Verify FRI parameters are reasonable
-        assert_eq!(fri_params.rs_code().log_inv_rate(), friVail.log_inv_rate);
-        assert_eq!(fri_params.n_test_queries(), friVail.num_test_queries);
+        let recovered = friVail
+            .reconstruct_from_samples(&samples, &fri_params, &ntt)
+            .expect("Failed to reconstruct from samples");
+        assert_eq!(recovered, packed_mle_values.packed_values);
+
+        // A repeated sample does not count towards the dimension
+        let mut too_few = samples[..dimension - 1].to_vec();
+        too_few.push(samples[0]);
+        assert_eq!(
+            friVail.reconstruct_from_samples(&too_few, &fri_params, &ntt),
+            Err(FriVailError::Unrecoverable {
+                erasures: info.codeword_len - (dimension - 1),
+                max: info.codeword_len - dimension,
+            })
+        );
     }
 
     #[test]
     #[ignore]
-    fn test_commit_and_inclusion_proofs() {
-        // Create test data
-        let test_data = create_test_data(1024);
+    fn test_shared_denominators_are_faster() {
+        // Quadratic in the codeword length per erasure before the change; run
+        // with `cargo test --release -- --ignored`
+        let len = 1 << 14;
+        let codeword: Vec<B128> = (0..len as u128)
+            .map(|i| B128::from(i.wrapping_mul(0x9e37_79b9_7f4a_7c15)))
+            .collect();
+        let corrupted_indices: Vec<usize> = (0..4).map(|i| i * (len / 4)).collect();
+        let domain = codec::codeword_domain::<B128>(len);
+        let known = codec::known_points(&codeword, &domain, &corrupted_indices)
+            .expect("Failed to collect known points");
+
+        let start = Instant::now();
+        let expected: Vec<B128> = corrupted_indices
+            .iter()
+            .map(|&index| interpolate_per_position(domain[index], &known))
+            .collect();
+        let per_position_time = start.elapsed();
+
+        let friVail = TestFriVail::new(1, 3, 2, 14, 3);
+        let mut reconstructed = codeword.clone();
+        let start = Instant::now();
+        friVail
+            .reconstruct_codeword_naive(&mut reconstructed, &corrupted_indices, None)
+            .expect("Failed to reconstruct codeword");
+        let shared_time = start.elapsed();
+
+        for (&index, &value) in corrupted_indices.iter().zip(&expected) {
+            assert_eq!(reconstructed[index], value);
+        }
+        assert!(
+            shared_time < per_position_time,
+            "Shared denominators took {:?}, per-position interpolation {:?}",
+            shared_time,
+            per_position_time
+        );
+    }
+
+    #[test]
+    fn test_standalone_decoder_from_reconstruction_params() {
+        let test_data = create_test_data(2048);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
-        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
-
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
         let (fri_params, ntt) = friVail
             .initialize_fri_context(packed_mle_values.packed_mle.log_len())
             .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
 
-        // Test commit
-        let commit_result = friVail.commit(
-            packed_mle_values.packed_mle.clone(),
-            fri_params.clone(),
-            &ntt,
-        );
-        assert!(commit_result.is_ok());
-
-        let commit_output = commit_result.unwrap();
-        assert!(!commit_output.commitment.is_empty());
-        assert!(commit_output.codeword.len() > 0);
+        // Ship only the serialized parameters to the decoder
+        let params = ReconstructionParams::from_bytes(
+            &friVail.reconstruction_params(&fri_params).to_bytes(),
+        )
+        .expect("Failed to decode reconstruction parameters");
+        assert_eq!(params.len, encoded_codeword.len());
+        assert_eq!(params.len, params.dim << params.log_inv_rate);
 
-        let commitment_bytes: [u8; 32] = commit_output
-            .commitment
-            .to_vec()
-            .try_into()
-            .expect("We know commitment size is 32 bytes");
-        // Test inclusion proofs for first few elements
-        for i in 0..std::cmp::min(5, commit_output.codeword.len()) {
-            let value = commit_output.codeword[i];
+        let erased: Vec<usize> = (0..params.len - params.dim)
+            .map(|i| i * 2 % params.len)
+            .collect();
+        let mut corrupted_codeword = encoded_codeword.clone();
+        for &index in &erased {
+            corrupted_codeword[index] = B128::zero();
+        }
 
-            // Generate inclusion proof
-            let inclusion_proof_result = friVail.inclusion_proof(&commit_output.committed, i);
-            assert!(inclusion_proof_result.is_ok());
+        // Standalone Lagrange decoder through any `dim` known points
+        let domain: Vec<B128> = BinarySubspace::<B128>::with_dim(params.domain_dim)
+            .iter()
+            .collect();
+        let known: Vec<(B128, B128)> = (0..params.len)
+            .filter(|i| !erased.contains(i))
+            .take(params.dim)
+            .map(|i| (domain[i], corrupted_codeword[i]))
+            .collect();
+        assert_eq!(known.len(), params.dim);
+        let mut standalone = corrupted_codeword.clone();
+        for &index in &erased {
+            standalone[index] = known
+                .iter()
+                .enumerate()
+                .map(|(j, &(x_j, y_j))| {
+                    known.iter().enumerate().filter(|&(m, _)| m != j).fold(
+                        y_j,
+                        |acc, (_, &(x_m, _))| {
+                            acc * (domain[index] - x_m) * (x_j - x_m).invert().unwrap()
+                        },
+                    )
+                })
+                .sum();
+        }
 
-            let mut inclusion_proof = inclusion_proof_result.unwrap();
+        friVail
+            .reconstruct_codeword_naive(&mut corrupted_codeword, &erased, None)
+            .expect("Failed to reconstruct codeword");
+        assert_eq!(standalone, corrupted_codeword);
+        assert_eq!(standalone, encoded_codeword);
 
-            // Verify inclusion proof
-            let verify_result = friVail.verify_inclusion_proof(
-                &mut inclusion_proof,
-                &[value],
-                i,
-                &fri_params,
-                commitment_bytes,
-            );
-            assert!(
-                verify_result.is_ok(),
-                "Inclusion proof verification failed for index {}",
-                i
-            );
-        }
+        assert!(ReconstructionParams::from_bytes(&[0u8; 31]).is_err());
     }
 
     #[test]
-    #[ignore]
-    fn test_open_method() {
-        // Create test data
+    #[cfg(feature = "std")]
+    fn test_resumable_reconstruction_matches_single_pass() {
         let test_data = create_test_data(1024);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
         let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
-
         let (fri_params, ntt) = friVail
             .initialize_fri_context(packed_mle_values.packed_mle.log_len())
             .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params,
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
 
-        // Test commit
-        let commit_result = friVail.commit(
-            packed_mle_values.packed_mle.clone(),
-            fri_params.clone(),
-            &ntt,
-        );
-        assert!(commit_result.is_ok());
-
-        let commit_output = commit_result.unwrap();
-        assert!(!commit_output.commitment.is_empty());
-        assert!(commit_output.codeword.len() > 0);
-
-        // Generate evaluation point for prove
-        let evaluation_point = friVail
-            .calculate_evaluation_point_random()
-            .expect("Failed to generate evaluation point");
-
-        // Generate proof to get query_prover
-        let prove_result = friVail.prove(
-            packed_mle_values.packed_mle.clone(),
-            &fri_params,
-            &ntt,
-            &commit_output,
-            &evaluation_point,
-        );
-        assert!(prove_result.is_ok());
+        let erased = vec![2, 7, 11, 20];
+        let mut corrupted_codeword = encoded_codeword.clone();
+        for &index in &erased {
+            corrupted_codeword[index] = B128::zero();
+        }
 
-        let (_, query_prover, _) = prove_result.unwrap();
+        let mut single_pass = corrupted_codeword.clone();
+        friVail
+            .reconstruct_codeword_naive(&mut single_pass, &erased, None)
+            .expect("Failed to reconstruct codeword");
 
-        // Test that open() method works with query_prover
-        for i in 0..std::cmp::min(5, commit_output.codeword.len()) {
-            let open_result = friVail.open(i, &query_prover);
-            assert!(open_result.is_ok(), "open() method failed for index {}", i);
-        }
+        // A zero budget still reconstructs one position before checkpointing
+        let mut resumed = corrupted_codeword.clone();
+        let progress = friVail
+            .reconstruct_codeword_resumable(
+                ReconstructionState::new(erased.clone()),
+                &mut resumed,
+                Duration::ZERO,
+            )
+            .expect("Failed to run first pass");
+        let ReconstructionProgress::Incomplete(state) = progress else {
+            panic!("First pass should be incomplete");
+        };
+        assert_eq!(state.completed, 1);
+
+        // Restart from the serialized checkpoint
+        let state = ReconstructionState::from_bytes(&state.to_bytes())
+            .expect("Failed to decode reconstruction state");
+        let progress = friVail
+            .reconstruct_codeword_resumable(state, &mut resumed, Duration::MAX)
+            .expect("Failed to run second pass");
+        assert_eq!(progress, ReconstructionProgress::Done);
+
+        assert_eq!(resumed, single_pass);
+        assert_eq!(resumed, encoded_codeword);
     }
 
     #[test]
-    fn test_calculate_evaluation_claim() {
-        let test_data = create_test_data(1024); // 1mb test data
+    fn test_spot_check_reconstruction() {
+        let test_data = create_test_data(1024);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
-        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
-
-        let evaluation_point = friVail
-            .calculate_evaluation_point_random()
-            .expect("Failed to generate evaluation point");
-
-        println!("evaluation point {:?}", evaluation_point.len());
-        let eval_point_eq = eq_ind_partial_eval(&evaluation_point);
-        println!("eval_point_eq {:?}", eval_point_eq.len());
-        println!("mle value {:?}", packed_mle_values.packed_mle.len());
-        let evaluation_claim = inner_product_buffers(&packed_mle_values.packed_mle, &eval_point_eq);
-
-        println!("evaluation claim {:?}", evaluation_claim);
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params,
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
 
-        let result =
-            friVail.calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point);
-        assert!(result.is_ok());
+        let erased = vec![1, 4, 9];
+        let mut reconstructed = encoded_codeword.clone();
+        for &index in &erased {
+            reconstructed[index] = B128::zero();
+        }
+        friVail
+            .reconstruct_codeword_naive(&mut reconstructed, &erased, None)
+            .expect("Failed to reconstruct codeword");
 
-        let evaluation_claim = result.unwrap();
-        // The evaluation claim should be a valid field element
-        assert_ne!(evaluation_claim, B128::default()); // Should not be zero for random inputs
+        let fresh_samples: Vec<(usize, B128)> = [0, 1, 4, 9, 12]
+            .into_iter()
+            .map(|index| (index, encoded_codeword[index]))
+            .collect();
+        assert!(friVail
+            .spot_check_reconstruction(&reconstructed, &fresh_samples)
+            .is_ok());
+
+        // A reconstruction shifted by one position is caught
+        let mut shifted = reconstructed.clone();
+        shifted.rotate_right(1);
+        let err = friVail
+            .spot_check_reconstruction(&shifted, &fresh_samples)
+            .expect_err("Shifted reconstruction should fail spot checks");
+        assert!(err.to_string().contains("index 0"));
+
+        assert!(friVail
+            .spot_check_reconstruction(&reconstructed, &[(reconstructed.len(), B128::zero())])
+            .is_err());
     }
 
     #[test]
-    fn test_full_prove_verify_workflow() {
-        // Create test data
-        let test_data = create_test_data(1024 * 1024); // 2KB test data
+    fn test_cancelled_reconstruction_is_partial_and_consistent() {
+        let test_data = create_test_data(1024);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
-        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
-        // Initialize FRI context
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
         let (fri_params, ntt) = friVail
             .initialize_fri_context(packed_mle_values.packed_mle.log_len())
             .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
 
-        // Generate evaluation point
-        let evaluation_point = friVail
-            .calculate_evaluation_point_random()
-            .expect("Failed to generate evaluation point");
-        let eval_point_eq = eq_ind_partial_eval(&evaluation_point);
-        let evaluation_claim = inner_product_buffers(&packed_mle_values.packed_mle, &eval_point_eq);
+        let erased: Vec<usize> = (0..3 * RECONSTRUCTION_BATCH).map(|i| 2 * i + 1).collect();
+        let mut corrupted_codeword = encoded_codeword.clone();
+        for &index in &erased {
+            corrupted_codeword[index] = B128::zero();
+        }
 
-        println!("evaluation claim {:?}", evaluation_claim);
-        // The evaluation claim should be a valid field element
-        assert_ne!(evaluation_claim, B128::default()); // Should not be zero for random inputs
+        // Cancel after the first batch
+        let token = CancellationToken::cancelled_after(1);
+        let result = friVail.reconstruct_codeword_naive_cancellable(
+            &mut corrupted_codeword,
+            &erased,
+            &token,
+        );
+        assert_eq!(result, Err(FriVailError::Cancelled));
 
-        // Commit to MLE
+        let (done, pending) = erased.split_at(RECONSTRUCTION_BATCH);
+        for &index in done {
+            assert_eq!(corrupted_codeword[index], encoded_codeword[index]);
+        }
+        for &index in pending {
+            assert_eq!(corrupted_codeword[index], B128::zero());
+        }
+
+        // Resuming with a fresh token completes the reconstruction
+        friVail
+            .reconstruct_codeword_naive_cancellable(
+                &mut corrupted_codeword,
+                &erased,
+                &CancellationToken::new(),
+            )
+            .expect("Failed to reconstruct codeword");
+        assert_eq!(corrupted_codeword, encoded_codeword);
+
+        // Cancelled tokens stop commit and prove before any work
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        assert!(matches!(
+            friVail.commit_cancellable(
+                packed_mle_values.packed_mle.clone(),
+                fri_params.clone(),
+                &ntt,
+                &cancelled
+            ),
+            Err(FriVailError::Cancelled)
+        ));
         let commit_output = friVail
-            .commit(
+            .commit_cancellable(
                 packed_mle_values.packed_mle.clone(),
                 fri_params.clone(),
                 &ntt,
+                &CancellationToken::new(),
             )
             .expect("Failed to commit");
+        let evaluation_point = friVail
+            .calculate_evaluation_point_random()
+            .expect("Failed to generate evaluation point");
+        assert!(matches!(
+            friVail.prove_cancellable(
+                packed_mle_values.packed_mle.clone(),
+                &fri_params,
+                &ntt,
+                &commit_output,
+                &evaluation_point,
+                &cancelled
+            ),
+            Err(FriVailError::Cancelled)
+        ));
+    }
 
-        // Generate proof
-        let prove_result = friVail.prove(
-            packed_mle_values.packed_mle.clone(),
-            &fri_params,
-            &ntt,
-            &commit_output,
-            &evaluation_point,
-        );
-        assert!(prove_result.is_ok());
-
-        let (terminate_codeword, query_prover, transcript_bytes) = prove_result.unwrap();
-
-        // Extract layers directly from query_prover
-        let layers = query_prover
-            .vcs_optimal_layers()
-            .expect("Failed to get layers");
+    #[test]
+    fn test_reconstruction_beyond_bound_is_unrecoverable() {
+        let test_data = create_test_data(512);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
 
-        // Reconstruct verifier transcript from bytes
-        let mut verifier_transcript =
-            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+        let encoded_codeword = friVail
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params.clone(),
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
+            .expect("Failed to encode codeword");
 
-        // Recalculate evaluation claim
-        let eval_point_eq = eq_ind_partial_eval(&evaluation_point);
-        let evaluation_claim = inner_product_buffers(&packed_mle_values.packed_mle, &eval_point_eq);
+        let max = friVail.max_recoverable_erasures(&fri_params);
+        assert_eq!(
+            max,
+            encoded_codeword.len() - packed_mle_values.packed_values.len()
+        );
 
-        // Convert terminate_codeword to vector of scalars
-        let terminate_codeword_vec: Vec<_> = terminate_codeword.iter_scalars().collect();
+        // Exactly at the bound reconstruction still succeeds
+        let erased: Vec<usize> = (0..max).collect();
+        assert!(friVail.can_reconstruct(encoded_codeword.len(), &erased, &fri_params));
+        let mut codeword = encoded_codeword.clone();
+        for &index in &erased {
+            codeword[index] = B128::zero();
+        }
+        friVail
+            .reconstruct_codeword_checked(&mut codeword, &erased, &fri_params)
+            .expect("Failed to reconstruct at the bound");
+        assert_eq!(codeword, encoded_codeword);
+
+        // One more erasure is rejected before interpolating
+        let erased: Vec<usize> = (0..=max).collect();
+        assert!(!friVail.can_reconstruct(encoded_codeword.len(), &erased, &fri_params));
+        let mut codeword = encoded_codeword.clone();
+        for &index in &erased {
+            codeword[index] = B128::zero();
+        }
+        let result = friVail.reconstruct_codeword_checked(&mut codeword, &erased, &fri_params);
+        assert_eq!(
+            result,
+            Err(FriVailError::Unrecoverable {
+                erasures: max + 1,
+                max
+            })
+        );
+    }
 
-        // Generate extra query proof using open()
-        let mut extra_transcript = friVail
-            .open(0, &query_prover)
-            .expect("Failed to generate extra query proof");
+    #[test]
+    fn test_interpolate_at_point_checked_rejects_duplicate_x() {
+        let point = |x: u128, y: u128| (B128::from(x), B128::from(y));
 
-        // Verify proof with extra parameters
-        let verify_result = friVail.verify(
-            &mut verifier_transcript,
-            evaluation_claim,
-            &evaluation_point,
-            &fri_params,
-            &ntt,                          // ntt instance
-            Some(0),                       // extra_index - use 0 for testing
-            Some(&terminate_codeword_vec), // terminate_codeword
-            Some(&layers),                 // layers
-            Some(&mut extra_transcript),   // extra query transcript
+        // Agrees with the interpolation used by reconstruction on distinct points
+        let known = vec![point(1, 5), point(2, 6), point(3, 7)];
+        let x_e = B128::from(9u128);
+        assert_eq!(
+            TestFriVail::interpolate_at_point_checked(x_e, &known, 3),
+            Ok(codec::interpolate_with_denominators(
+                x_e,
+                &known,
+                &codec::lagrange_inverse_denominators(&known)
+            ))
         );
-        assert!(
-            verify_result.is_ok(),
-            "Verification failed: {:?}",
-            verify_result
+
+        let degenerate = vec![point(1, 5), point(2, 6), point(1, 7)];
+        assert_eq!(
+            TestFriVail::interpolate_at_point_checked(x_e, &degenerate, 3),
+            Err(FriVailError::DuplicatePoint {
+                first: 0,
+                second: 2
+            })
         );
+        // The duplicate is outside the first two points
+        assert!(TestFriVail::interpolate_at_point_checked(x_e, &degenerate, 2).is_ok());
+        assert!(matches!(
+            TestFriVail::interpolate_at_point_checked(x_e, &degenerate, 4),
+            Err(FriVailError::Reconstruction { known_points: 3 })
+        ));
     }
 
     #[test]
-    fn test_invalid_verification_fails() {
-        // Create test data
+    fn test_reconstruct_and_verify_catches_wrong_known_value() {
         let test_data = create_test_data(512);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
+
         let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
         let (fri_params, ntt) = friVail
             .initialize_fri_context(packed_mle_values.packed_mle.log_len())
             .expect("Failed to initialize FRI context");
-
         let commit_output = friVail
             .commit(
                 packed_mle_values.packed_mle.clone(),
@@ -1063,72 +10107,49 @@ mod tests {
                 &ntt,
             )
             .expect("Failed to commit");
+        let commitment_bytes: [u8; 32] = commit_output
+            .commitment
+            .to_vec()
+            .try_into()
+            .expect("We know commitment size is 32 bytes");
 
-        let evaluation_point = friVail
-            .calculate_evaluation_point_random()
-            .expect("Failed to generate evaluation point");
-
-        let (_terminate_codeword, _query_prover, transcript_bytes) = friVail
-            .prove(
-                packed_mle_values.packed_mle.clone(),
-                &fri_params,
-                &ntt,
-                &commit_output,
-                &evaluation_point,
-            )
-            .expect("Failed to generate proof");
-
-        // Reconstruct verifier transcript from bytes
-        let mut verifier_transcript =
-            VerifierTranscript::new(StdChallenger::default(), transcript_bytes);
-
-        // Use wrong evaluation claim (should cause verification to fail)
-        let wrong_evaluation_claim = B128::from(42u128);
-
-        let verify_result = friVail.verify(
-            &mut verifier_transcript,
-            wrong_evaluation_claim,
-            &evaluation_point,
-            &fri_params,
-            &ntt, // ntt instance
-            None,
-            None,
-            None,
-            None, // no extra transcript
-        );
+        let erased: Vec<usize> = (0..8).collect();
+        let mut codeword = commit_output.codeword.clone();
+        for &index in &erased {
+            codeword[index] = B128::zero();
+        }
+        friVail
+            .reconstruct_and_verify(&mut codeword, &erased, &fri_params, commitment_bytes)
+            .expect("Honest reconstruction should verify");
+        assert_eq!(codeword, commit_output.codeword);
+
+        // One wrong known value still interpolates, but to the wrong codeword
+        let mut codeword = commit_output.codeword.clone();
+        for &index in &erased {
+            codeword[index] = B128::zero();
+        }
+        codeword[20] += B128::ONE;
+        let mut unchecked = codeword.clone();
+        friVail
+            .reconstruct_codeword_naive(&mut unchecked, &erased, None)
+            .expect("Interpolation should succeed");
 
-        // Verification should fail with wrong claim
-        assert!(
-            verify_result.is_err(),
-            "Verification should fail with wrong evaluation claim"
-        );
+        let result =
+            friVail.reconstruct_and_verify(&mut codeword, &erased, &fri_params, commitment_bytes);
+        assert!(matches!(result, Err(FriVailError::Merkle(_))));
     }
 
     #[test]
-    fn test_data_availability_sampling() {
-        use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
-        use tracing::Level;
-
-        // Initialize logging for the test
-        let _ = tracing_subscriber::fmt()
-            .with_max_level(Level::DEBUG)
-            .with_test_writer()
-            .try_init();
-
-        // Create test data
-        let test_data = create_test_data(512); // 512 bytes test data
+    fn test_typed_errors_distinguish_failures() {
+        let test_data = create_test_data(512);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
-        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 2);
-
-        // Initialize FRI context
+        let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
         let (fri_params, ntt) = friVail
             .initialize_fri_context(packed_mle_values.packed_mle.log_len())
             .expect("Failed to initialize FRI context");
-
-        // Commit to MLE
         let commit_output = friVail
             .commit(
                 packed_mle_values.packed_mle.clone(),
@@ -1137,165 +10158,316 @@ mod tests {
             )
             .expect("Failed to commit");
 
-        println!(
-            "commit output codeword len {:?}",
-            commit_output.codeword.len()
+        // Erasing every position leaves nothing to interpolate from
+        let mut codeword = commit_output.codeword.clone();
+        let erased: Vec<usize> = (0..codeword.len()).collect();
+        let result = friVail.reconstruct_codeword_naive(&mut codeword, &erased, None);
+        assert_eq!(
+            result,
+            Err(FriVailError::Reconstruction { known_points: 0 })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "No known points available for reconstruction"
         );
 
-        let total_samples = commit_output.codeword.len();
-        let sample_size = std::cmp::min(5, total_samples / 4); // Limit to 5 samples or 1/4 of total
-        let indices =
-            sample(&mut StdRng::from_seed([0; 32]), total_samples, sample_size).into_vec();
-        let commitment_bytes: [u8; 32] = commit_output
-            .commitment
-            .to_vec()
-            .try_into()
-            .expect("We know commitment size is 32 bytes");
+        // A truncated codeword is reported with both lengths
+        let rs_code = fri_params.rs_code();
+        let truncated = &commit_output.codeword[..commit_output.codeword.len() - 1];
+        let mut output = Vec::with_capacity(commit_output.codeword.len());
+        let result = friVail.decode_batch(
+            rs_code.log_len(),
+            rs_code.log_inv_rate(),
+            fri_params.log_batch_size(),
+            &ntt,
+            truncated,
+            &mut output.spare_capacity_mut()[..commit_output.codeword.len()],
+        );
+        assert_eq!(
+            result,
+            Err(FriVailError::Decode {
+                expected: commit_output.codeword.len(),
+                got: truncated.len(),
+            })
+        );
 
-        let mut successful_samples = 0;
-        let mut failed_samples = Vec::new();
+        // A wrong value fails Merkle verification rather than reconstruction
+        let mut inclusion_proof = friVail
+            .inclusion_proof(&commit_output.committed, 0)
+            .expect("Failed to generate inclusion proof");
+        let result = friVail.verify_inclusion_proof(
+            &mut inclusion_proof,
+            &[commit_output.codeword[0] + B128::ONE],
+            0,
+            &fri_params,
+            commit_output.commitment,
+        );
+        assert!(matches!(
+            result,
+            Err(FriVailError::Verification(VerifyError::InclusionProof))
+        ));
+    }
 
-        for &sample_index in indices.iter() {
-            println!("sample index {sample_index}");
-            match friVail.inclusion_proof(&commit_output.committed, sample_index) {
-                Ok(mut inclusion_proof) => {
-                    let value = commit_output.codeword[sample_index];
-                    match friVail.verify_inclusion_proof(
+    #[test]
+    fn test_codeword_index_for_locates_every_scalar() {
+        for log_batch_size in [0, 2] {
+            let test_data = create_test_data(1024);
+            let packed_mle_values = Utils::<B128>::new()
+                .bytes_to_packed_mle(&test_data)
+                .expect("Failed to create packed MLE");
+
+            let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+            let (fri_params, ntt) = friVail
+                .initialize_fri_context_batched(
+                    packed_mle_values.packed_mle.log_len(),
+                    log_batch_size,
+                )
+                .expect("Failed to initialize FRI context");
+            let commit_output = friVail
+                .commit(
+                    packed_mle_values.packed_mle.clone(),
+                    fri_params.clone(),
+                    &ntt,
+                )
+                .expect("Failed to commit");
+
+            // Decode without undoing the bit-reversal, so coefficients stay at
+            // the positions the prover placed them
+            let rs_code = fri_params.rs_code();
+            let len = commit_output.codeword.len();
+            let mut coefficients = Vec::with_capacity(len);
+            let initialized = friVail
+                .decode_batch(
+                    rs_code.log_len(),
+                    rs_code.log_inv_rate(),
+                    fri_params.log_batch_size(),
+                    &ntt,
+                    &commit_output.codeword,
+                    &mut coefficients.spare_capacity_mut()[..len],
+                )
+                .expect("Failed to decode codeword");
+            unsafe {
+                coefficients.set_len(initialized);
+            }
+
+            let mut seen = HashSet::new();
+            for (logical_index, value) in packed_mle_values.packed_values.iter().enumerate() {
+                let index = friVail
+                    .codeword_index_for(logical_index, &fri_params)
+                    .expect("Data scalar should be in the message");
+                assert!(seen.insert(index));
+                assert_eq!(coefficients[index], *value);
+                assert!(index >> log_batch_size < 1 << rs_code.log_len());
+            }
+
+            let message_len = 1 << (rs_code.log_dim() + log_batch_size);
+            assert_eq!(friVail.codeword_index_for(message_len, &fri_params), None);
+        }
+    }
+
+    #[test]
+    fn test_sample_every_codeword_position() {
+        for log_batch_size in [0, 2] {
+            let test_data = create_test_data(512);
+            let packed_mle_values = Utils::<B128>::new()
+                .bytes_to_packed_mle(&test_data)
+                .expect("Failed to create packed MLE");
+
+            let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
+            let (fri_params, ntt) = friVail
+                .initialize_fri_context_batched(
+                    packed_mle_values.packed_mle.log_len(),
+                    log_batch_size,
+                )
+                .expect("Failed to initialize FRI context");
+            let commit_output = friVail
+                .commit(
+                    packed_mle_values.packed_mle.clone(),
+                    fri_params.clone(),
+                    &ntt,
+                )
+                .expect("Failed to commit");
+
+            let codeword = &commit_output.codeword;
+            for codeword_index in 0..codeword.len() {
+                let (leaf_index, leaf) = friVail
+                    .leaf_for(codeword, codeword_index, &fri_params)
+                    .expect("Codeword index should map to a leaf");
+                assert!(leaf.contains(&codeword[codeword_index]));
+
+                let mut inclusion_proof = friVail
+                    .inclusion_proof(&commit_output.committed, leaf_index)
+                    .expect("Failed to generate inclusion proof");
+                friVail
+                    .verify_inclusion_proof(
                         &mut inclusion_proof,
-                        &[value],
-                        sample_index,
+                        leaf,
+                        leaf_index,
                         &fri_params,
-                        commitment_bytes,
-                    ) {
-                        Ok(_) => {
-                            successful_samples += 1;
-                        }
-                        Err(e) => {
-                            failed_samples
-                                .push((sample_index, format!("Verification failed: {}", e)));
-                        }
-                    }
-                }
-                Err(e) => {
-                    failed_samples.push((
-                        sample_index,
-                        format!("Inclusion proof generation failed: {}", e),
-                    ));
-                }
+                        commit_output.commitment,
+                    )
+                    .expect("Sample should verify");
             }
+            assert!(friVail
+                .leaf_for(codeword, codeword.len(), &fri_params)
+                .is_none());
+
+            // A leaf index past the tree is an error rather than a panic
+            let num_leaves = codeword.len() >> log_batch_size;
+            let mut inclusion_proof = friVail
+                .inclusion_proof(&commit_output.committed, 0)
+                .expect("Failed to generate inclusion proof");
+            assert!(matches!(
+                friVail.verify_inclusion_proof(
+                    &mut inclusion_proof,
+                    &codeword[..1 << log_batch_size],
+                    num_leaves,
+                    &fri_params,
+                    commit_output.commitment,
+                ),
+                Err(FriVailError::Verification(VerifyError::LeafIndex { .. }))
+            ));
         }
+    }
 
-        assert_eq!(failed_samples.len(), 0, "Some samples failed verification");
+    #[test]
+    fn test_code_info_for_integration_config() {
+        // 9 KB of data, as in the integration test, packs into 2^10 elements
+        let test_data = create_test_data(9 * 1024);
+        let packed_mle_values = Utils::<B128>::new()
+            .bytes_to_packed_mle(&test_data)
+            .expect("Failed to create packed MLE");
+        assert_eq!(packed_mle_values.packed_mle.log_len(), 10);
+
+        let friVail = TestFriVail::new(1, 128, 4, packed_mle_values.total_n_vars, 80);
+        let (fri_params, ntt) = friVail
+            .initialize_fri_context(packed_mle_values.packed_mle.log_len())
+            .expect("Failed to initialize FRI context");
+
+        let info = friVail.code_info(&fri_params);
         assert_eq!(
-            successful_samples, sample_size,
-            "Not all samples were verified"
+            info,
+            CodeInfo {
+                log_dim: 10,
+                log_len: 11,
+                log_inv_rate: 1,
+                log_batch_size: 0,
+                num_leaves: 2048,
+                codeword_len: 2048,
+            }
         );
 
-        println!("Successfully verified {} samples", successful_samples);
+        let commit_output = friVail
+            .commit(packed_mle_values.packed_mle, fri_params.clone(), &ntt)
+            .expect("Failed to commit");
+        assert_eq!(commit_output.codeword.len(), info.codeword_len);
+        assert_eq!(info.num_leaves << info.log_batch_size, info.codeword_len);
     }
 
     #[test]
-    fn test_codeword_decode() {
-        // Create test data
+    fn test_code_distance_and_correction_capability() {
         let test_data = create_test_data(512);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
         let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
-
-        // Initialize FRI context
-        let (fri_params, ntt) = friVail
+        let (fri_params, _ntt) = friVail
             .initialize_fri_context(packed_mle_values.packed_mle.log_len())
             .expect("Failed to initialize FRI context");
 
-        // Encode codeword
-        let encoded_codeword = friVail
-            .encode_codeword(&packed_mle_values.packed_values, fri_params.clone(), &ntt)
-            .expect("Failed to encode codeword");
+        // 32 message elements at rate 1/2 give a codeword of 64
+        let k = packed_mle_values.packed_values.len();
+        assert_eq!(k, 32);
+        let n = k << 1;
+        assert_eq!(friVail.code_distance(&fri_params), n - k + 1);
+        assert_eq!(friVail.code_distance(&fri_params), 33);
+        assert_eq!(friVail.error_correction_capability(&fri_params), 16);
 
-        // Decode codeword
-        let decoded_codeword = friVail
-            .decode_codeword(&encoded_codeword, fri_params.clone(), &ntt)
-            .expect("Failed to decode codeword");
+        // Twice the correctable errors never exceed the erasure bound
+        assert!(
+            2 * friVail.error_correction_capability(&fri_params)
+                <= friVail.max_recoverable_erasures(&fri_params)
+        );
+    }
 
-        // Verify decoded codeword matches original values
+    #[test]
+    fn test_simulate_erasures_is_reproducible() {
+        let friVail = TestFriVail::new(1, 3, 2, 10, 3);
+        let codeword: Vec<B128> = (1..=1000u128).map(B128::from).collect();
+
+        let mut first = codeword.clone();
+        let mut second = codeword.clone();
+        let erased = friVail.simulate_erasures(&mut first, 0.1, Some(42));
         assert_eq!(
-            decoded_codeword, packed_mle_values.packed_values,
-            "Decoded codeword should match original packed values"
+            friVail.simulate_erasures(&mut second, 0.1, Some(42)),
+            erased
         );
+        assert_eq!(first, second);
 
-        println!("✅ Codeword decode test passed");
+        // The fraction is respected up to rounding down, without duplicates
+        assert_eq!(erased.len(), 100);
+        assert_eq!(erased.iter().collect::<HashSet<_>>().len(), erased.len());
+        for (i, value) in first.iter().enumerate() {
+            assert_eq!(*value == B128::zero(), erased.contains(&i));
+        }
+
+        let mut other = codeword.clone();
+        assert_ne!(friVail.simulate_erasures(&mut other, 0.1, Some(43)), erased);
+        assert_eq!(
+            friVail.simulate_erasures(&mut other, 0.0, None),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            friVail
+                .simulate_erasures(&mut codeword.clone(), 0.333, Some(1))
+                .len(),
+            333
+        );
     }
 
     #[test]
-    fn test_error_correction_reconstruction() {
+    fn test_reconstruct_positions_subset() {
         use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
 
-        // Create test data
-        let test_data = create_test_data(2048);
+        let test_data = create_test_data(512);
         let packed_mle_values = Utils::<B128>::new()
             .bytes_to_packed_mle(&test_data)
             .expect("Failed to create packed MLE");
 
         let friVail = TestFriVail::new(1, 3, 2, packed_mle_values.packed_mle.log_len(), 3);
-
-        // Initialize FRI context
         let (fri_params, ntt) = friVail
             .initialize_fri_context(packed_mle_values.packed_mle.log_len())
             .expect("Failed to initialize FRI context");
-
-        // Encode codeword
         let encoded_codeword = friVail
-            .encode_codeword(&packed_mle_values.packed_values, fri_params.clone(), &ntt)
+            .encode_codeword(
+                &packed_mle_values.packed_values,
+                fri_params,
+                &ntt,
+                EncodingLayout::BitReversed,
+            )
             .expect("Failed to encode codeword");
 
-        // Corrupt the codeword
+        // Erase 20% of the codeword
+        let total_elements = encoded_codeword.len();
+        let mut rng = StdRng::seed_from_u64(7);
+        let erased = sample(&mut rng, total_elements, total_elements / 5).into_vec();
         let mut corrupted_codeword = encoded_codeword.clone();
-        let total_elements = corrupted_codeword.len();
-        let corruption_percentage = 0.1;
-
-        // Corrupt random elements
-        let num_corrupted = (total_elements as f64 * corruption_percentage) as usize;
-        let mut rng = StdRng::seed_from_u64(42);
-        let corrupted_indices = sample(&mut rng, total_elements, num_corrupted).into_vec();
-
-        for &index in &corrupted_indices {
+        for &index in &erased {
             corrupted_codeword[index] = B128::zero();
         }
 
-        // Verify corruption happened
-        assert_ne!(
-            corrupted_codeword, encoded_codeword,
-            "Codeword should be corrupted"
-        );
+        let wanted = erased[0];
+        let partial = friVail
+            .reconstruct_positions(&corrupted_codeword, &erased, &[wanted])
+            .expect("Failed to reconstruct wanted position");
 
-        // Reconstruct corrupted codeword
+        let mut fully_reconstructed = corrupted_codeword.clone();
         friVail
-            .reconstruct_codeword_naive(&mut corrupted_codeword, &corrupted_indices)
+            .reconstruct_codeword_naive(&mut fully_reconstructed, &erased, None)
             .expect("Failed to reconstruct codeword");
 
-        // Verify reconstruction succeeded
-        assert_eq!(
-            corrupted_codeword, encoded_codeword,
-            "Reconstructed codeword should match original encoded codeword"
-        );
-
-        // Decode the reconstructed codeword to verify it's correct
-        let decoded_reconstructed = friVail
-            .decode_codeword(&corrupted_codeword, fri_params.clone(), &ntt)
-            .expect("Failed to decode reconstructed codeword");
-
-        // Verify decoded reconstructed codeword matches original values
-        assert_eq!(
-            decoded_reconstructed, packed_mle_values.packed_values,
-            "Decoded reconstructed codeword should match original packed values"
-        );
-
-        println!(
-            "✅ Error correction reconstruction test passed: {} elements, {:.1}% corruption",
-            total_elements,
-            corruption_percentage * 100.0
-        );
+        assert_eq!(partial, vec![(wanted, fully_reconstructed[wanted])]);
+        assert_eq!(partial[0].1, encoded_codeword[wanted]);
     }
 }
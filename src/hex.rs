@@ -0,0 +1,28 @@
+//! Minimal lowercase hex encoding helpers
+
+use crate::error::FriVailError;
+
+/// Encode bytes as lowercase hex
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode a hex string, accepting an optional `0x` prefix
+pub(crate) fn decode(hex: &str) -> Result<Vec<u8>, FriVailError> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() % 2 != 0 {
+        return Err(FriVailError::Deserialize(format!(
+            "Hex string has odd length {}",
+            hex.len()
+        )));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| {
+                FriVailError::Deserialize(format!("Invalid hex at offset {}: {}", i, e))
+            })
+        })
+        .collect()
+}
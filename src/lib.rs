@@ -1,8 +1,25 @@
+pub mod accumulator;
+pub mod cancellation;
+#[cfg(feature = "ed25519")]
+pub mod certificate;
+pub mod codec;
+pub mod commitment;
+pub mod error;
+pub mod evm;
 pub mod frivail;
+mod hex;
 #[cfg(feature = "kzg")]
 pub mod kzg_proof_of_proof;
 pub mod poly;
+pub mod sampling;
+pub mod test_vectors;
+pub mod testing;
 pub mod traits;
 pub mod types;
+pub mod verifier;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
 
+pub use commitment::{CommitmentId, CommitmentRoot};
+pub use error::FriVailError;
 pub use types::*;
@@ -1,18 +1,32 @@
+use crate::error::FriVailError;
 use binius_field::field::FieldOps;
 use binius_field::{ExtensionField, PackedField};
 use binius_math::FieldBuffer;
 use binius_verifier::config::B1;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
+use std::io::Read;
 use std::marker::PhantomData;
 
 /// Number of bytes per field element (128 bits = 16 bytes)
-const BYTES_PER_ELEMENT: usize = 16;
-/// Number of bits per field element
-const BITS_PER_ELEMENT: usize = 128;
+pub(crate) const BYTES_PER_ELEMENT: usize = 16;
+
+/// Number of field elements decoded per read in `stream_to_packed_mle`
+const STREAM_BLOCK_ELEMENTS: usize = 4096;
+
+/// Byte order used when decoding a chunk into a field element
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first
+    #[default]
+    Little,
+    /// Most significant byte first
+    Big,
+}
 
 /// Utility struct for converting bytes to packed multilinear extensions
 pub struct Utils<P> {
+    endianness: Endianness,
     _p: PhantomData<P>,
 }
 
@@ -25,6 +39,91 @@ where
     pub packed_mle: FieldBuffer<P>,
     pub packed_values: Vec<P::Scalar>,
     pub total_n_vars: usize,
+    /// Length of the input bytes, used to trim decoded values back to the payload
+    pub original_byte_len: usize,
+}
+
+impl<P> PackedMLE<P>
+where
+    P: PackedField + ExtensionField<B1>,
+    P::Scalar: From<u128> + ExtensionField<B1>,
+{
+    /// Number of leading `packed_values` holding data, before zero padding
+    pub fn num_data_elements(&self) -> usize {
+        self.original_byte_len.div_ceil(BYTES_PER_ELEMENT)
+    }
+
+    /// Bytes of real data in the last data element, 0 for empty input
+    pub fn last_chunk_len(&self) -> usize {
+        match self.original_byte_len % BYTES_PER_ELEMENT {
+            0 if self.original_byte_len == 0 => 0,
+            0 => BYTES_PER_ELEMENT,
+            partial => partial,
+        }
+    }
+
+    /// Number of trailing `packed_values` that are padding
+    pub fn num_padding_elements(&self) -> usize {
+        self.packed_values.len() - self.num_data_elements()
+    }
+
+    /// Drop the padding elements from decoded values
+    ///
+    /// # Arguments
+    /// * `decoded` - Values decoded from this MLE's codeword, such as from `decode_codeword`
+    ///
+    /// # Returns
+    /// The values without the trailing `num_padding_elements`
+    pub fn strip_padding<'v>(&self, decoded: &'v [P::Scalar]) -> &'v [P::Scalar] {
+        &decoded[..decoded.len().saturating_sub(self.num_padding_elements())]
+    }
+}
+
+/// Field elements that are overwritten with zeros by `Zeroize`
+///
+/// Field types do not implement `Zeroize` themselves, so decoded payloads are
+/// wrapped in this buffer to be held in a `Zeroizing`.
+#[cfg(feature = "zeroize")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarBuffer<F>(pub Vec<F>);
+
+#[cfg(feature = "zeroize")]
+impl<F> std::ops::Deref for ScalarBuffer<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: FieldOps + Copy> zeroize::Zeroize for ScalarBuffer<F> {
+    fn zeroize(&mut self) {
+        volatile_zero(&mut self.0, F::zero());
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<P> zeroize::Zeroize for PackedMLE<P>
+where
+    P: PackedField + ExtensionField<B1>,
+    P::Scalar: From<u128> + ExtensionField<B1>,
+{
+    fn zeroize(&mut self) {
+        volatile_zero(&mut self.packed_values, P::Scalar::zero());
+        volatile_zero(self.packed_mle.as_mut(), P::broadcast(P::Scalar::zero()));
+        self.original_byte_len = 0;
+    }
+}
+
+/// Overwrite a slice with `zero` in a way the compiler cannot elide
+#[cfg(feature = "zeroize")]
+fn volatile_zero<T: Copy>(values: &mut [T], zero: T) {
+    for value in values.iter_mut() {
+        // Safety: `value` is a valid, aligned reference to a `Copy` type
+        unsafe { std::ptr::write_volatile(value, zero) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
 }
 
 impl<P> Utils<P>
@@ -37,14 +136,59 @@ where
     /// # Returns
     /// New Utils instance
     pub fn new() -> Self {
-        Self { _p: PhantomData }
+        Self::with_endianness(Endianness::default())
+    }
+
+    /// Create a utility instance decoding chunks with the given byte order
+    ///
+    /// # Arguments
+    /// * `endianness` - Byte order of each 16-byte chunk
+    ///
+    /// # Returns
+    /// New Utils instance
+    pub fn with_endianness(endianness: Endianness) -> Self {
+        Self {
+            endianness,
+            _p: PhantomData,
+        }
+    }
+
+    /// Convert a byte chunk to a field element using the configured byte order
+    fn bytes_to_scalar(&self, chunk: &[u8]) -> Result<P::Scalar, FriVailError> {
+        self.bytes_to_scalar_with(chunk, self.endianness)
     }
 
     /// Convert a byte chunk to a field element
-    fn bytes_to_scalar(&self, chunk: &[u8]) -> P::Scalar {
+    ///
+    /// Chunks shorter than 16 bytes are zero-filled at the end before decoding.
+    ///
+    /// # Arguments
+    /// * `chunk` - Up to 16 bytes to convert
+    /// * `endianness` - Byte order of the chunk
+    ///
+    /// # Returns
+    /// Field element holding the chunk's value
+    ///
+    /// # Errors
+    /// `FriVailError::ChunkTooLong` when the chunk is longer than 16 bytes
+    pub fn bytes_to_scalar_with(
+        &self,
+        chunk: &[u8],
+        endianness: Endianness,
+    ) -> Result<P::Scalar, FriVailError> {
+        if chunk.len() > BYTES_PER_ELEMENT {
+            return Err(FriVailError::ChunkTooLong { len: chunk.len() });
+        }
+
         let mut bytes_array = [0u8; BYTES_PER_ELEMENT];
         bytes_array[..chunk.len()].copy_from_slice(chunk);
-        P::Scalar::from(u128::from_le_bytes(bytes_array))
+        let value = match endianness {
+            Endianness::Little => u128::from_le_bytes(bytes_array),
+            Endianness::Big => u128::from_be_bytes(bytes_array),
+        };
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut bytes_array);
+        Ok(P::Scalar::from(value))
     }
 
     /// Convert raw bytes to a packed multilinear extension
@@ -53,12 +197,34 @@ where
     /// * `data` - Raw bytes to convert
     ///
     /// # Returns
+    /// Packed multilinear extension representation, zero-padded
+    ///
+    /// # Errors
+    /// When a chunk cannot be converted to a field element
+    pub fn bytes_to_packed_mle(&self, data: &[u8]) -> Result<PackedMLE<P>, FriVailError> {
+        self.bytes_to_packed_mle_with_padding(data, P::Scalar::zero())
+    }
+
+    /// Convert raw bytes to a packed multilinear extension padded with `pad`
+    ///
+    /// A padding marker that data cannot end with makes the padding
+    /// unambiguous; `PackedMLE::num_padding_elements` reports how many were added.
+    ///
+    /// # Arguments
+    /// * `data` - Raw bytes to convert
+    /// * `pad` - Value filling the positions up to the next power of two
+    ///
+    /// # Returns
     /// Packed multilinear extension representation
     ///
     /// # Errors
-    /// When conversion fails
-    pub fn bytes_to_packed_mle(&self, data: &[u8]) -> Result<PackedMLE<P>, String> {
-        let num_elements = data.len().div_ceil(BITS_PER_ELEMENT);
+    /// When a chunk cannot be converted to a field element
+    pub fn bytes_to_packed_mle_with_padding(
+        &self,
+        data: &[u8],
+        pad: P::Scalar,
+    ) -> Result<PackedMLE<P>, FriVailError> {
+        let num_elements = data.len().div_ceil(BYTES_PER_ELEMENT);
 
         let padded_size = num_elements.next_power_of_two();
         let big_field_n_vars = padded_size.ilog2() as usize;
@@ -67,27 +233,380 @@ where
         let mut packed_values: Vec<P::Scalar> = {
             data.par_chunks(BYTES_PER_ELEMENT)
                 .map(|chunk| self.bytes_to_scalar(chunk))
-                .collect()
+                .collect::<Result<_, _>>()?
         };
 
         #[cfg(not(feature = "parallel"))]
         let mut packed_values: Vec<P::Scalar> = {
             let mut values = Vec::with_capacity(num_elements);
             for chunk in data.chunks(BYTES_PER_ELEMENT) {
-                values.push(self.bytes_to_scalar(chunk));
+                values.push(self.bytes_to_scalar(chunk)?);
             }
             values
         };
 
-        packed_values.resize(packed_size, P::Scalar::zero());
+        packed_values.resize(packed_size, pad);
+
+        Ok(Self::assemble_packed_mle(packed_values, data.len()))
+    }
+
+    /// Convert raw bytes to a packed multilinear extension with tunable parallelism
+    ///
+    /// Inputs shorter than `min_parallel_len` are decoded sequentially. Longer
+    /// inputs are split into tasks of `min_parallel_len` bytes each, rounded up
+    /// to whole field elements, so large blobs do not spawn one task per chunk.
+    /// Without the `parallel` feature decoding is always sequential.
+    ///
+    /// # Arguments
+    /// * `data` - Raw bytes to convert
+    /// * `min_parallel_len` - Input length from which decoding runs in parallel,
+    ///   also the number of bytes each parallel task decodes
+    ///
+    /// # Returns
+    /// The same packed multilinear extension as `bytes_to_packed_mle`
+    ///
+    /// # Errors
+    /// When conversion fails
+    pub fn bytes_to_packed_mle_with_chunking(
+        &self,
+        data: &[u8],
+        min_parallel_len: usize,
+    ) -> Result<PackedMLE<P>, FriVailError> {
+        let num_elements = data.len().div_ceil(BYTES_PER_ELEMENT);
+        let mut packed_values = vec![P::Scalar::zero(); num_elements.next_power_of_two()];
+        let decode = |out: &mut [P::Scalar], bytes: &[u8]| -> Result<(), FriVailError> {
+            for (value, chunk) in out.iter_mut().zip(bytes.chunks(BYTES_PER_ELEMENT)) {
+                *value = self.bytes_to_scalar(chunk)?;
+            }
+            Ok(())
+        };
+
+        #[cfg(feature = "parallel")]
+        if data.len() >= min_parallel_len {
+            let grain = min_parallel_len.div_ceil(BYTES_PER_ELEMENT).max(1);
+            packed_values[..num_elements]
+                .par_chunks_mut(grain)
+                .zip(data.par_chunks(grain * BYTES_PER_ELEMENT))
+                .try_for_each(|(out, bytes)| decode(out, bytes))?;
+        } else {
+            decode(&mut packed_values[..num_elements], data)?;
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            let _ = min_parallel_len;
+            decode(&mut packed_values[..num_elements], data)?;
+        }
+
+        Ok(Self::assemble_packed_mle(packed_values, data.len()))
+    }
+
+    /// Convert bytes read from a stream to a packed multilinear extension
+    ///
+    /// The padded power-of-two size is known from `total_len`, so the values are
+    /// decoded in place into a pre-sized buffer while the input is read in
+    /// fixed-size blocks; the bytes are never held in memory as a whole.
+    ///
+    /// # Arguments
+    /// * `reader` - Source of exactly `total_len` bytes
+    /// * `total_len` - Number of bytes to read
+    ///
+    /// # Returns
+    /// The same packed multilinear extension as `bytes_to_packed_mle` on the
+    /// bytes read
+    ///
+    /// # Errors
+    /// When reading fails or the stream ends before `total_len` bytes
+    pub fn stream_to_packed_mle<R: Read>(
+        &self,
+        mut reader: R,
+        total_len: usize,
+    ) -> Result<PackedMLE<P>, FriVailError> {
+        let num_elements = total_len.div_ceil(BYTES_PER_ELEMENT);
+        let mut packed_values = vec![P::Scalar::zero(); num_elements.next_power_of_two()];
+
+        let mut block = vec![0u8; STREAM_BLOCK_ELEMENTS * BYTES_PER_ELEMENT];
+        let mut remaining = total_len;
+        for out in packed_values[..num_elements].chunks_mut(STREAM_BLOCK_ELEMENTS) {
+            let block_len = remaining.min(block.len());
+            reader
+                .read_exact(&mut block[..block_len])
+                .map_err(|e| FriVailError::Io(format!("Failed to read blob stream: {}", e)))?;
+            for (value, chunk) in out
+                .iter_mut()
+                .zip(block[..block_len].chunks(BYTES_PER_ELEMENT))
+            {
+                *value = self.bytes_to_scalar(chunk)?;
+            }
+            remaining -= block_len;
+        }
+
+        Ok(Self::assemble_packed_mle(packed_values, total_len))
+    }
+
+    /// Build a packed multilinear extension directly from field elements
+    ///
+    /// The values are zero-padded to the next power of two without a byte
+    /// round trip. Every value counts as a full 16-byte element, so
+    /// `original_byte_len` is `values.len() * 16`.
+    ///
+    /// # Arguments
+    /// * `values` - Field elements in data order
+    ///
+    /// # Returns
+    /// Packed multilinear extension over `values`
+    pub fn scalars_to_packed_mle(&self, values: &[P::Scalar]) -> PackedMLE<P> {
+        let mut packed_values = Vec::with_capacity(values.len().next_power_of_two());
+        packed_values.extend_from_slice(values);
+        packed_values.resize(values.len().next_power_of_two(), P::Scalar::zero());
+
+        Self::assemble_packed_mle(packed_values, values.len() * BYTES_PER_ELEMENT)
+    }
 
+    /// Build a `PackedMLE` from padded values of power-of-two length
+    fn assemble_packed_mle(packed_values: Vec<P::Scalar>, data_len: usize) -> PackedMLE<P> {
         let packed_mle = FieldBuffer::<P>::from_values(packed_values.as_slice());
         let total_n_vars = packed_mle.log_len();
 
-        Ok(PackedMLE::<P> {
+        PackedMLE::<P> {
             packed_mle,
             packed_values,
             total_n_vars,
-        })
+            original_byte_len: data_len,
+        }
+    }
+}
+
+impl<P> Utils<P>
+where
+    P: PackedField + ExtensionField<B1>,
+    P::Scalar: From<u128> + ExtensionField<B1>,
+    u128: From<P::Scalar>,
+{
+    /// Convert a field element back to its 16-byte chunk
+    ///
+    /// # Arguments
+    /// * `scalar` - Field element to convert
+    /// * `endianness` - Byte order of the chunk
+    ///
+    /// # Returns
+    /// The 16 bytes `bytes_to_scalar_with` decodes to `scalar`
+    pub fn scalar_to_bytes_with(
+        &self,
+        scalar: P::Scalar,
+        endianness: Endianness,
+    ) -> [u8; BYTES_PER_ELEMENT] {
+        let value = u128::from(scalar);
+        match endianness {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+        }
+    }
+
+    /// Convert a packed multilinear extension back to the bytes it was built from
+    ///
+    /// Scalars are written back as 16-byte chunks in order, then the output is
+    /// truncated to `original_byte_len` to drop the zero padding.
+    ///
+    /// # Arguments
+    /// * `mle` - Packed MLE from `bytes_to_packed_mle` with the same byte order
+    ///
+    /// # Returns
+    /// The original bytes
+    pub fn packed_mle_to_bytes(&self, mle: &PackedMLE<P>) -> Vec<u8> {
+        self.values_to_bytes(&mle.packed_values, mle.original_byte_len)
+    }
+
+    /// Convert field elements, such as the output of `decode_codeword`, to bytes
+    ///
+    /// # Arguments
+    /// * `values` - Field elements in data order
+    /// * `original_len` - Number of bytes to keep, usually `PackedMLE::original_byte_len`
+    ///
+    /// # Returns
+    /// The first `original_len` bytes of the 16-byte chunks of `values`
+    pub fn values_to_bytes(&self, values: &[P::Scalar], original_len: usize) -> Vec<u8> {
+        let mut bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|&scalar| self.scalar_to_bytes_with(scalar, self.endianness))
+            .collect();
+        bytes.truncate(original_len);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binius_verifier::config::B128;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_big_endian_scalar_is_byte_reversed() {
+        let utils = Utils::<B128>::new();
+        let chunk: Vec<u8> = (1..=16).collect();
+
+        let little = utils
+            .bytes_to_scalar_with(&chunk, Endianness::Little)
+            .expect("Failed to convert chunk");
+        let big = utils
+            .bytes_to_scalar_with(&chunk, Endianness::Big)
+            .expect("Failed to convert chunk");
+        assert_eq!(u128::from(big), u128::from(little).swap_bytes());
+
+        // The default instance decodes little-endian
+        assert_eq!(utils.bytes_to_scalar(&chunk), Ok(little));
+
+        let big_utils = Utils::<B128>::with_endianness(Endianness::Big);
+        assert_eq!(big_utils.bytes_to_scalar(&chunk), Ok(big));
+        assert_eq!(
+            big_utils
+                .scalar_to_bytes_with(big, Endianness::Big)
+                .to_vec(),
+            chunk
+        );
+        assert_eq!(
+            utils
+                .scalar_to_bytes_with(little, Endianness::Little)
+                .to_vec(),
+            chunk
+        );
+    }
+
+    #[test]
+    fn test_oversized_chunk_is_rejected() {
+        let utils = Utils::<B128>::new();
+        assert_eq!(
+            utils.bytes_to_scalar_with(&[0xAB; 17], Endianness::Little),
+            Err(FriVailError::ChunkTooLong { len: 17 })
+        );
+        assert!(utils.bytes_to_scalar(&[0xAB; 16]).is_ok());
+        assert!(utils.bytes_to_scalar(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_partial_last_chunk_round_trip() {
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let utils = Utils::<B128>::with_endianness(endianness);
+            for len in [17, 31] {
+                let data: Vec<u8> = (1..=len as u8).collect();
+                let mle = utils
+                    .bytes_to_packed_mle(&data)
+                    .expect("Failed to create packed MLE");
+
+                assert_eq!(mle.num_data_elements(), 2);
+                assert_eq!(mle.last_chunk_len(), len - BYTES_PER_ELEMENT);
+                assert_eq!(utils.packed_mle_to_bytes(&mle), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_packed_len_counts_every_chunk() {
+        let utils = Utils::<B128>::new();
+        for len in [1024, 2048] {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let mle = utils
+                .bytes_to_packed_mle(&data)
+                .expect("Failed to create packed MLE");
+
+            assert_eq!(mle.num_data_elements(), len / BYTES_PER_ELEMENT);
+            assert_eq!(
+                mle.packed_values.len(),
+                data.len().div_ceil(16).next_power_of_two()
+            );
+            assert_eq!(utils.packed_mle_to_bytes(&mle), data);
+        }
+    }
+
+    #[test]
+    fn test_chunked_conversion_matches_sequential() {
+        let utils = Utils::<B128>::new();
+        let data: Vec<u8> = (0..1 << 16)
+            .map(|i: usize| (i * 31 + i / 7) as u8)
+            .collect();
+
+        let sequential = utils
+            .bytes_to_packed_mle_with_chunking(&data, usize::MAX)
+            .expect("Failed to create packed MLE");
+        let chunked = utils
+            .bytes_to_packed_mle_with_chunking(&data, 1 << 12)
+            .expect("Failed to create packed MLE");
+
+        assert_eq!(chunked.packed_values, sequential.packed_values);
+        assert_eq!(chunked.total_n_vars, sequential.total_n_vars);
+        assert_eq!(chunked.num_data_elements(), sequential.num_data_elements());
+
+        // Odd grain and a partial last chunk agree with the default conversion
+        let tail = &data[..1000];
+        let expected = utils
+            .bytes_to_packed_mle(tail)
+            .expect("Failed to create packed MLE");
+        let chunked = utils
+            .bytes_to_packed_mle_with_chunking(tail, 100)
+            .expect("Failed to create packed MLE");
+        assert_eq!(chunked.packed_values, expected.packed_values);
+        assert_eq!(chunked.last_chunk_len(), expected.last_chunk_len());
+    }
+
+    #[test]
+    fn test_stream_matches_slice_conversion() {
+        let utils = Utils::<B128>::new();
+        let data: Vec<u8> = (0..(1 << 20) + 5).map(|i: usize| (i * 13) as u8).collect();
+
+        let streamed = utils
+            .stream_to_packed_mle(Cursor::new(&data), data.len())
+            .expect("Failed to stream packed MLE");
+        let expected = utils
+            .bytes_to_packed_mle(&data)
+            .expect("Failed to create packed MLE");
+
+        assert_eq!(streamed.packed_values, expected.packed_values);
+        assert_eq!(streamed.total_n_vars, expected.total_n_vars);
+        assert_eq!(streamed.last_chunk_len(), expected.last_chunk_len());
+        assert_eq!(streamed.original_byte_len, data.len());
+
+        // A stream shorter than the announced length is an error
+        assert!(utils
+            .stream_to_packed_mle(Cursor::new(&data[..100]), 200)
+            .is_err());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_clears_packed_mle() {
+        use zeroize::Zeroize;
+
+        let utils = Utils::<B128>::new();
+        let data: Vec<u8> = (1..=200).collect();
+        let mut mle = utils
+            .bytes_to_packed_mle(&data)
+            .expect("Failed to create packed MLE");
+        assert!(mle.packed_values.iter().any(|&value| value != B128::zero()));
+
+        mle.zeroize();
+        assert!(mle.packed_values.iter().all(|&value| value == B128::zero()));
+        assert!(mle
+            .packed_mle
+            .iter_scalars()
+            .all(|value| value == B128::zero()));
+        assert_eq!(mle.original_byte_len, 0);
+
+        let mut buffer = ScalarBuffer(vec![B128::from(5u128); 4]);
+        buffer.zeroize();
+        assert_eq!(&buffer[..], &[B128::zero(); 4]);
+    }
+
+    #[test]
+    fn test_packed_mle_to_bytes_round_trip() {
+        let utils = Utils::<B128>::new();
+        for len in [0, 16, 1000, 1024, 1500] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+            let mle = utils
+                .bytes_to_packed_mle(&data)
+                .expect("Failed to create packed MLE");
+
+            assert_eq!(utils.packed_mle_to_bytes(&mle), data);
+        }
     }
 }
@@ -0,0 +1,306 @@
+//! Sample collection for Data Availability Sampling clients
+
+use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Identifier of a peer serving samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PeerId(pub u64);
+
+impl fmt::Display for PeerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "peer-{}", self.0)
+    }
+}
+
+/// Two peers returned different values for the same codeword index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictError<F> {
+    pub index: usize,
+    pub first_peer: PeerId,
+    pub first_value: F,
+    pub conflicting_peer: PeerId,
+    pub conflicting_value: F,
+}
+
+impl<F: fmt::Debug> fmt::Display for ConflictError<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Conflicting sample at index {}: {} returned {:?}, {} returned {:?}",
+            self.index,
+            self.first_peer,
+            self.first_value,
+            self.conflicting_peer,
+            self.conflicting_value
+        )
+    }
+}
+
+impl<F: fmt::Debug> std::error::Error for ConflictError<F> {}
+
+/// Strategy for choosing which codeword indices a sampling client requests
+pub trait SamplingStrategy {
+    /// Choose distinct indices in `0..total`
+    ///
+    /// # Arguments
+    /// * `total` - Number of sampleable indices
+    /// * `count` - Number of indices to choose, capped at `total`
+    /// * `seed` - Seed for the random choice; equal seeds give equal indices
+    ///
+    /// # Returns
+    /// Distinct indices below `total`
+    fn indices(&self, total: usize, count: usize, seed: [u8; 32]) -> Vec<usize>;
+}
+
+/// Indices drawn uniformly at random from the whole codeword
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniformSampling;
+
+impl SamplingStrategy for UniformSampling {
+    fn indices(&self, total: usize, count: usize, seed: [u8; 32]) -> Vec<usize> {
+        sample(&mut StdRng::from_seed(seed), total, count.min(total)).into_vec()
+    }
+}
+
+/// Indices spread evenly over `2^log_buckets` contiguous buckets
+///
+/// For a power-of-two codeword each bucket is the leaf range of one Merkle
+/// subtree at depth `log_buckets`, so a withholding adversary cannot hide a
+/// whole subtree from a client. Every bucket is hit once `count` reaches the
+/// number of buckets; earlier buckets take the remainder of an uneven split,
+/// and indices a bucket is too small for go to the other buckets.
+#[derive(Debug, Clone, Copy)]
+pub struct StratifiedSampling {
+    pub log_buckets: usize,
+}
+
+impl StratifiedSampling {
+    /// Create a strategy over `2^log_buckets` buckets
+    pub fn new(log_buckets: usize) -> Self {
+        Self { log_buckets }
+    }
+}
+
+impl SamplingStrategy for StratifiedSampling {
+    fn indices(&self, total: usize, count: usize, seed: [u8; 32]) -> Vec<usize> {
+        let count = count.min(total);
+        let num_buckets = 1usize
+            .checked_shl(self.log_buckets as u32)
+            .unwrap_or(usize::MAX)
+            .min(total.max(1));
+        let bounds = |bucket: usize| {
+            (
+                bucket * total / num_buckets,
+                (bucket + 1) * total / num_buckets,
+            )
+        };
+
+        // Quota per bucket, carrying what a bucket cannot hold into later ones
+        let mut quotas = Vec::with_capacity(num_buckets);
+        let mut carry = 0;
+        for bucket in 0..num_buckets {
+            let (start, end) = bounds(bucket);
+            let wanted = count / num_buckets + usize::from(bucket < count % num_buckets) + carry;
+            let quota = wanted.min(end - start);
+            carry = wanted - quota;
+            quotas.push(quota);
+        }
+        // Whatever is left over after the last bucket goes back to earlier ones
+        for bucket in (0..num_buckets).rev() {
+            let (start, end) = bounds(bucket);
+            let extra = carry.min(end - start - quotas[bucket]);
+            quotas[bucket] += extra;
+            carry -= extra;
+        }
+
+        let mut rng = StdRng::from_seed(seed);
+        let mut indices = Vec::with_capacity(count);
+        for (bucket, &quota) in quotas.iter().enumerate() {
+            let (start, end) = bounds(bucket);
+            indices.extend(
+                sample(&mut rng, end - start, quota)
+                    .into_iter()
+                    .map(|offset| start + offset),
+            );
+        }
+        indices
+    }
+}
+
+/// Outcome of a data availability sampling round for one commitment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailabilityReport {
+    /// Commitment root the samples were verified against
+    pub root: [u8; 32],
+    /// Codeword indices whose inclusion proofs verified
+    pub sample_indices: Vec<usize>,
+}
+
+/// Accumulates samples from multiple peers, deduplicating by codeword index
+#[derive(Debug, Clone)]
+pub struct SampleCollector<F> {
+    samples: BTreeMap<usize, (PeerId, F)>,
+}
+
+impl<F> Default for SampleCollector<F> {
+    fn default() -> Self {
+        Self {
+            samples: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: Copy + PartialEq> SampleCollector<F> {
+    /// Create an empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a sample returned by a peer
+    ///
+    /// # Arguments
+    /// * `peer` - Peer that served the sample
+    /// * `index` - Codeword index of the sample
+    /// * `value` - Sampled value
+    ///
+    /// # Returns
+    /// Ok(()) if the sample is new or agrees with the recorded value
+    ///
+    /// # Errors
+    /// When a different value was already recorded for `index`
+    pub fn add(&mut self, peer: PeerId, index: usize, value: F) -> Result<(), ConflictError<F>> {
+        match self.samples.get(&index) {
+            Some(&(first_peer, first_value)) if first_value != value => Err(ConflictError {
+                index,
+                first_peer,
+                first_value,
+                conflicting_peer: peer,
+                conflicting_value: value,
+            }),
+            Some(_) => Ok(()),
+            None => {
+                self.samples.insert(index, (peer, value));
+                Ok(())
+            }
+        }
+    }
+
+    /// Value recorded for a codeword index
+    pub fn get(&self, index: usize) -> Option<F> {
+        self.samples.get(&index).map(|&(_, value)| value)
+    }
+
+    /// Number of distinct indices collected
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether no samples have been collected
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Deduplicated `(index, value)` samples in index order
+    pub fn samples(&self) -> Vec<(usize, F)> {
+        self.samples
+            .iter()
+            .map(|(&index, &(_, value))| (index, value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::B128;
+
+    #[test]
+    fn test_sample_collector_deduplicates() {
+        let mut collector = SampleCollector::new();
+
+        collector.add(PeerId(1), 5, B128::from(10u128)).unwrap();
+        collector.add(PeerId(2), 5, B128::from(10u128)).unwrap();
+        collector.add(PeerId(2), 3, B128::from(7u128)).unwrap();
+
+        assert_eq!(collector.len(), 2);
+        assert_eq!(
+            collector.samples(),
+            vec![(3, B128::from(7u128)), (5, B128::from(10u128))]
+        );
+    }
+
+    #[test]
+    fn test_stratified_sampling_hits_every_bucket() {
+        let total = 1024;
+        let log_buckets = 4;
+        let bucket_len = total >> log_buckets;
+
+        for seed in 0..8u8 {
+            let indices = StratifiedSampling::new(log_buckets).indices(total, 20, [seed; 32]);
+            assert_eq!(indices.len(), 20);
+            assert!(indices.iter().all(|&i| i < total));
+
+            let mut per_bucket = [0usize; 16];
+            for &i in &indices {
+                per_bucket[i / bucket_len] += 1;
+            }
+            assert!(per_bucket.iter().all(|&hits| hits >= 1));
+
+            let mut distinct = indices.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(distinct.len(), indices.len());
+        }
+
+        // More buckets than indices still returns every index once
+        let mut all = StratifiedSampling::new(6).indices(10, 50, [0; 32]);
+        all.sort_unstable();
+        assert_eq!(all, (0..10).collect::<Vec<_>>());
+
+        // Uneven buckets pass their shortfall on, so the count is still met
+        let mut uneven = StratifiedSampling::new(1).indices(3, 3, [0; 32]);
+        uneven.sort_unstable();
+        assert_eq!(uneven, vec![0, 1, 2]);
+        for total in 1..40 {
+            for count in 0..=total {
+                assert_eq!(
+                    StratifiedSampling::new(3)
+                        .indices(total, count, [1; 32])
+                        .len(),
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_uniform_sampling_is_seeded() {
+        let strategy = UniformSampling;
+        let indices = strategy.indices(100, 10, [1; 32]);
+        assert_eq!(indices.len(), 10);
+        assert_eq!(indices, strategy.indices(100, 10, [1; 32]));
+        assert_ne!(indices, strategy.indices(100, 10, [2; 32]));
+        assert_eq!(strategy.indices(5, 10, [1; 32]).len(), 5);
+    }
+
+    #[test]
+    fn test_sample_collector_flags_conflict() {
+        let mut collector = SampleCollector::new();
+
+        collector.add(PeerId(1), 5, B128::from(10u128)).unwrap();
+        let err = collector
+            .add(PeerId(2), 5, B128::from(11u128))
+            .expect_err("Conflicting values should be flagged");
+
+        assert_eq!(err.index, 5);
+        assert_eq!(err.first_peer, PeerId(1));
+        assert_eq!(err.conflicting_peer, PeerId(2));
+        assert_eq!(err.first_value, B128::from(10u128));
+        assert_eq!(err.conflicting_value, B128::from(11u128));
+
+        // The first recorded value is kept
+        assert_eq!(collector.get(5), Some(B128::from(10u128)));
+    }
+}
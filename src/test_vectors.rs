@@ -0,0 +1,176 @@
+//! Deterministic test vectors for cross-implementation compatibility
+//!
+//! A vector pins the full pipeline for a seed: the data bytes, the MLE conversion,
+//! the commitment root, the evaluation point, the claim and the proof transcript.
+//! Field elements are encoded as 16 little-endian bytes, all byte strings as hex.
+
+use crate::error::FriVailError;
+use crate::hex;
+use crate::poly::Utils;
+use crate::traits::FriVailSampling;
+use crate::types::{FriVailDefault, B128};
+use binius_field::Random;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+/// Parameters a test vector is generated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestVectorConfig {
+    /// Number of data bytes
+    pub data_len: usize,
+    /// Logarithm of inverse rate for Reed-Solomon encoding
+    pub log_inv_rate: usize,
+    /// Number of test queries for FRI protocol
+    pub num_test_queries: usize,
+    /// Arity for FRI folding strategy
+    pub arity: usize,
+    /// Logarithm of number of shares for the NTT
+    pub log_num_shares: usize,
+}
+
+impl Default for TestVectorConfig {
+    fn default() -> Self {
+        Self {
+            data_len: 1024,
+            log_inv_rate: 1,
+            num_test_queries: 3,
+            arity: 2,
+            log_num_shares: 2,
+        }
+    }
+}
+
+/// Canonical inputs and outputs of one commit/prove run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    pub seed: u64,
+    pub config: TestVectorConfig,
+    pub data: Vec<u8>,
+    pub commitment_root: [u8; 32],
+    pub evaluation_point: Vec<B128>,
+    pub claim: B128,
+    pub proof_bytes: Vec<u8>,
+}
+
+impl TestVector {
+    /// Serialize the vector as JSON with a fixed key order
+    pub fn to_json(&self) -> String {
+        let point = self
+            .evaluation_point
+            .iter()
+            .map(|&x| format!("\"{}\"", scalar_hex(x)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            concat!(
+                "{{\"seed\":{},",
+                "\"config\":{{\"data_len\":{},\"log_inv_rate\":{},\"num_test_queries\":{},",
+                "\"arity\":{},\"log_num_shares\":{}}},",
+                "\"data\":\"{}\",\"commitment_root\":\"{}\",\"evaluation_point\":[{}],",
+                "\"claim\":\"{}\",\"proof_bytes\":\"{}\"}}"
+            ),
+            self.seed,
+            self.config.data_len,
+            self.config.log_inv_rate,
+            self.config.num_test_queries,
+            self.config.arity,
+            self.config.log_num_shares,
+            hex::encode(&self.data),
+            hex::encode(&self.commitment_root),
+            point,
+            scalar_hex(self.claim),
+            hex::encode(&self.proof_bytes),
+        )
+    }
+}
+
+/// Hex encoding of a field element's little-endian bytes
+fn scalar_hex(x: B128) -> String {
+    hex::encode(&u128::from(x).to_le_bytes())
+}
+
+/// Generate the test vector for a seed and configuration
+///
+/// # Arguments
+/// * `seed` - Seed for the data bytes and evaluation point
+/// * `config` - Scheme parameters
+///
+/// # Returns
+/// Test vector covering commitment, claim and proof
+///
+/// # Errors
+/// When any stage of the pipeline fails for the configuration
+pub fn generate_test_vector(
+    seed: u64,
+    config: TestVectorConfig,
+) -> Result<TestVector, FriVailError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut data = vec![0u8; config.data_len];
+    rng.fill_bytes(&mut data);
+
+    let packed_mle_values = Utils::<B128>::new().bytes_to_packed_mle(&data)?;
+    let fri_vail = FriVailDefault::new(
+        config.log_inv_rate,
+        config.num_test_queries,
+        config.arity,
+        packed_mle_values.total_n_vars,
+        config.log_num_shares,
+    );
+    let (fri_params, ntt) =
+        fri_vail.initialize_fri_context(packed_mle_values.packed_mle.log_len())?;
+
+    let commit_output = fri_vail.commit(
+        packed_mle_values.packed_mle.clone(),
+        fri_params.clone(),
+        &ntt,
+    )?;
+    let commitment_root: [u8; 32] = commit_output
+        .commitment
+        .to_vec()
+        .try_into()
+        .map_err(|_| FriVailError::Commit("Commitment is not 32 bytes".into()))?;
+
+    let evaluation_point: Vec<B128> = (0..packed_mle_values.total_n_vars)
+        .map(|_| <B128 as Random>::random(&mut rng))
+        .collect();
+    let (_, _, proof_bytes, claim) = fri_vail.prove(
+        packed_mle_values.packed_mle,
+        &fri_params,
+        &ntt,
+        &commit_output,
+        &evaluation_point,
+    )?;
+
+    Ok(TestVector {
+        seed,
+        config,
+        data,
+        commitment_root,
+        evaluation_point,
+        claim,
+        proof_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_is_reproducible() {
+        let config = TestVectorConfig::default();
+
+        let first = generate_test_vector(7, config).expect("Failed to generate test vector");
+        let second = generate_test_vector(7, config).expect("Failed to generate test vector");
+        assert_eq!(first, second);
+        assert_eq!(first.to_json().into_bytes(), second.to_json().into_bytes());
+
+        assert_eq!(first.data.len(), config.data_len);
+        assert!(first
+            .to_json()
+            .contains(&hex::encode(&first.commitment_root)));
+
+        let other = generate_test_vector(8, config).expect("Failed to generate test vector");
+        assert_ne!(first.commitment_root, other.commitment_root);
+    }
+}
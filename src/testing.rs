@@ -0,0 +1,88 @@
+//! Deterministic synthetic data for tests and benchmarks
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+/// Seed used to place nonzero bytes in `DataPattern::Sparse` blobs
+const SPARSE_SEED: u64 = 0x5eed;
+
+/// Byte pattern of a synthetic blob
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataPattern {
+    /// Byte `i` is `i % 256`, the data used throughout the test suite
+    Sequential,
+    /// Uniformly random bytes from a seeded generator
+    Random(u64),
+    /// All bytes zero
+    Zeros,
+    /// Each byte is nonzero with probability `density`, zero otherwise
+    Sparse { density: f64 },
+}
+
+/// Generate a synthetic blob
+///
+/// The same length and pattern always produce the same bytes.
+///
+/// # Arguments
+/// * `len` - Number of bytes
+/// * `pattern` - Byte pattern to fill the blob with
+///
+/// # Returns
+/// Blob of `len` bytes
+pub fn synthetic_blob(len: usize, pattern: DataPattern) -> Vec<u8> {
+    match pattern {
+        DataPattern::Sequential => (0..len).map(|i| (i % 256) as u8).collect(),
+        DataPattern::Random(seed) => {
+            let mut blob = vec![0u8; len];
+            StdRng::seed_from_u64(seed).fill_bytes(&mut blob);
+            blob
+        }
+        DataPattern::Zeros => vec![0u8; len],
+        DataPattern::Sparse { density } => {
+            let mut rng = StdRng::seed_from_u64(SPARSE_SEED);
+            (0..len)
+                .map(|i| {
+                    if rng.random::<f64>() < density {
+                        (i % 255) as u8 + 1
+                    } else {
+                        0
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_matches_test_data() {
+        for len in [0, 17, 1024, 2048] {
+            let expected: Vec<u8> = (0..len).map(|i| (i % 256) as u8).collect();
+            assert_eq!(synthetic_blob(len, DataPattern::Sequential), expected);
+        }
+    }
+
+    #[test]
+    fn test_patterns_are_deterministic() {
+        for pattern in [
+            DataPattern::Random(3),
+            DataPattern::Zeros,
+            DataPattern::Sparse { density: 0.1 },
+        ] {
+            let blob = synthetic_blob(4096, pattern);
+            assert_eq!(blob.len(), 4096);
+            assert_eq!(blob, synthetic_blob(4096, pattern));
+        }
+
+        assert_ne!(
+            synthetic_blob(64, DataPattern::Random(3)),
+            synthetic_blob(64, DataPattern::Random(4))
+        );
+
+        let sparse = synthetic_blob(4096, DataPattern::Sparse { density: 0.1 });
+        let nonzero = sparse.iter().filter(|&&b| b != 0).count();
+        assert!(nonzero > 0 && nonzero < 4096 / 4);
+    }
+}
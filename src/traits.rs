@@ -7,22 +7,30 @@ pub use binius_verifier::config::B128;
 use binius_verifier::{
     config::{StdChallenger, B1},
     fri::FRIParams,
-    hash::StdDigest,
 };
 use std::mem::MaybeUninit;
 
+use crate::commitment::CommitmentRoot;
+use crate::error::FriVailError;
+use crate::frivail::{EncodingLayout, TerminalCodeword};
 use crate::types::*;
 
 pub trait FriVailSampling<
     P: PackedField<Scalar = B128> + PackedExtension<B128> + PackedExtension<B1>,
     NTT: AdditiveNTT<Field = B128> + Sync,
+    H: MerkleHash = StdMerkleHash,
 >
 {
     /// Reconstruct a corrupted codeword using naive Lagrange interpolation
     ///
+    /// The erasure count is not checked against the code's `n - k` bound, so
+    /// too many erasures yield a wrong codeword; use `reconstruct_codeword_checked`
+    /// to reject them with `FriVailError::Unrecoverable` first.
+    ///
     /// # Arguments
     /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
     /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    /// * `progress` - Called with `(done, total)` erased positions after each batch
     ///
     /// # Returns
     /// Ok(()) if reconstruction succeeds
@@ -33,7 +41,48 @@ pub trait FriVailSampling<
         &self,
         corrupted_codeword: &mut [P::Scalar],
         corrupted_indices: &[usize],
-    ) -> Result<(), String>;
+        progress: Option<&dyn Fn(usize, usize)>,
+    ) -> Result<(), FriVailError>;
+    /// Reconstruct a corrupted codeword with additive-NTT erasure decoding
+    ///
+    /// Produces the same values as `reconstruct_codeword_naive` in
+    /// `O(n log^2 n)` field operations instead of quadratic time.
+    ///
+    /// # Arguments
+    /// * `corrupted_codeword` - Mutable reference to the corrupted codeword to reconstruct
+    /// * `corrupted_indices` - Indices of corrupted elements in the codeword
+    ///
+    /// # Returns
+    /// Ok(()) if reconstruction succeeds
+    ///
+    /// # Errors
+    /// When the codeword length is not a power of two, an index is out of range,
+    /// or no known points are available
+    fn reconstruct_codeword_fft(
+        &self,
+        corrupted_codeword: &mut [P::Scalar],
+        corrupted_indices: &[usize],
+    ) -> Result<(), FriVailError>;
+
+    /// Reconstruct only selected erased positions of a codeword
+    ///
+    /// # Arguments
+    /// * `codeword` - Codeword with erased positions
+    /// * `erased` - Indices of erased elements in the codeword
+    /// * `wanted` - Indices whose values should be recovered
+    ///
+    /// # Returns
+    /// `(index, value)` pairs in the order of `wanted`
+    ///
+    /// # Errors
+    /// When a wanted index is out of range or no known points are available
+    fn reconstruct_positions(
+        &self,
+        codeword: &[P::Scalar],
+        erased: &[usize],
+        wanted: &[usize],
+    ) -> Result<Vec<(usize, P::Scalar)>, FriVailError>;
+
     /// Verify an evaluation proof for the committed polynomial
     ///
     /// # Arguments
@@ -43,7 +92,7 @@ pub trait FriVailSampling<
     /// * `fri_params` - FRI protocol parameters
     /// * `ntt` - Number Theoretic Transform instance
     /// * `extra_index` - Optional index for extra query verification
-    /// * `terminate_codeword` - Optional terminal codeword for verification
+    /// * `terminate_codeword` - Optional terminal codeword from `prove`
     /// * `layers` - Optional Merkle tree layers for verification
     /// * `extra_transcript` - Optional extra transcript for query verification
     ///
@@ -60,16 +109,16 @@ pub trait FriVailSampling<
         fri_params: &FRIParams<P::Scalar>,
         ntt: &NTT,
         extra_index: Option<usize>,
-        terminate_codeword: Option<&[P::Scalar]>,
-        layers: Option<&[Vec<digest::Output<StdDigest>>]>,
+        terminate_codeword: Option<&TerminalCodeword<P::Scalar>>,
+        layers: Option<&[Vec<digest::Output<H::Digest>>]>,
         extra_transcript: Option<&mut VerifierTranscript<StdChallenger>>,
-    ) -> Result<(), String>;
+    ) -> Result<(), FriVailError>;
 
     /// Verify a Merkle inclusion proof for a codeword value
     ///
     /// # Arguments
     /// * `verifier_transcript` - Verifier transcript containing the inclusion proof
-    /// * `data` - Data value to verify inclusion for
+    /// * `data` - Leaf values, `2^log_batch_size` scalars per leaf
     /// * `index` - Index in the codeword
     /// * `fri_params` - FRI protocol parameters
     /// * `commitment` - Merkle tree root commitment
@@ -78,21 +127,21 @@ pub trait FriVailSampling<
     /// Ok(()) if inclusion proof is valid
     ///
     /// # Errors
-    /// When inclusion proof verification fails
+    /// When `data` is not one full leaf or inclusion proof verification fails
     fn verify_inclusion_proof(
         &self,
         verifier_transcript: &mut VerifierTranscript<StdChallenger>,
         data: &[P::Scalar],
         index: usize,
         fri_params: &FRIParams<P::Scalar>,
-        commitment: [u8; 32],
-    ) -> Result<(), String>;
+        commitment: impl Into<CommitmentRoot>,
+    ) -> Result<(), FriVailError>;
 
     /// Generate a Merkle inclusion proof for a specific codeword position
     ///
     /// # Arguments
     /// * `committed` - Committed Merkle tree
-    /// * `index` - Index in the codeword to generate proof for
+    /// * `index` - Merkle leaf index, see `FriVail::leaf_for` for batched codewords
     ///
     /// # Returns
     /// Verifier transcript containing the inclusion proof
@@ -101,23 +150,34 @@ pub trait FriVailSampling<
     /// When proof generation fails
     fn inclusion_proof(
         &self,
-        committed: &<MerkleProver<P> as MerkleTreeProver<<P as PackedField>::Scalar>>::Committed,
+        committed: &<H::Prover as MerkleTreeProver<B128>>::Committed,
         index: usize,
     ) -> TranscriptResult;
 
     /// Open a commitment at a specific index using FRI query prover
     ///
+    /// The opening transcript starts with `commitment` and a fixed context tag.
+    /// They are a label only: no challenge is sampled after them, so the
+    /// decommitment does not depend on them. What ties the query to a codeword
+    /// is its Merkle paths, which `verify` checks against the proof's layers.
+    ///
     /// # Arguments
     /// * `index` - Index in the codeword to open
+    /// * `commitment` - Codeword commitment the query prover was built from
     /// * `query_prover` - FRI query prover instance
     ///
     /// # Returns
     /// Verifier transcript containing the opening proof
     ///
     /// # Errors
-    /// When opening fails
-    fn open<'b>(&self, index: usize, query_prover: &FRIQueryProverAlias<'b, P>)
-        -> TranscriptResult;
+    /// `FriVailError::InvalidParams` when `commitment` is not the root the query
+    /// prover was built from, or `FriVailError::Prove` when opening fails
+    fn open<'b>(
+        &self,
+        index: usize,
+        commitment: &digest::Output<H::Digest>,
+        query_prover: &FRIQueryProverAlias<'b, P, H>,
+    ) -> TranscriptResult;
 
     /// Decode a Reed-Solomon encoded codeword back to original data
     ///
@@ -125,6 +185,7 @@ pub trait FriVailSampling<
     /// * `codeword` - Encoded codeword to decode
     /// * `fri_params` - FRI protocol parameters
     /// * `ntt` - Number Theoretic Transform instance
+    /// * `layout` - Order the codeword was encoded in
     ///
     /// # Returns
     /// Decoded packed field values
@@ -136,6 +197,7 @@ pub trait FriVailSampling<
         codeword: &[P::Scalar],
         fri_params: FRIParams<P::Scalar>,
         ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
+        layout: EncodingLayout,
     ) -> FieldResult<P>;
 
     /// Extract commitment from verifier transcript
@@ -155,6 +217,8 @@ pub trait FriVailSampling<
 
     /// Low-level batch decoding using inverse NTT
     ///
+    /// Callers without a `FriVail` instance can use `frivail::inverse_rs_decode`.
+    ///
     /// # Arguments
     /// * `log_dim` - Logarithm of dimension
     /// * `log_inv` - Logarithm of inverse rate
@@ -164,10 +228,11 @@ pub trait FriVailSampling<
     /// * `output` - Output buffer for decoded data
     ///
     /// # Returns
-    /// Ok(()) if decoding succeeds
+    /// Number of leading `output` elements initialized, all of `output` on success
     ///
     /// # Errors
-    /// When decoding fails due to invalid parameters
+    /// When `data` or `output` does not match the codeword length; `output` is
+    /// left uninitialized in that case
     fn decode_batch(
         &self,
         log_dim: usize,
@@ -176,7 +241,7 @@ pub trait FriVailSampling<
         ntt: &NeighborsLastMultiThread<GenericPreExpanded<P::Scalar>>,
         data: &[P::Scalar],
         output: &mut [MaybeUninit<P::Scalar>],
-    ) -> Result<(), String>;
+    ) -> Result<usize, FriVailError>;
 }
 
 pub trait FriVailUtils {
@@ -1,17 +1,18 @@
 //! Type aliases for FRI-Vail
 
+use crate::error::FriVailError;
 pub use binius_field::PackedField;
 use binius_prover::{
     fri::{CommitOutput, FRIQueryProver},
     hash::parallel_compression::ParallelCompressionAdaptor,
-    merkle_tree::prover::BinaryMerkleTreeProver,
+    merkle_tree::{prover::BinaryMerkleTreeProver, MerkleTreeProver},
 };
 use binius_transcript::VerifierTranscript;
 pub use binius_verifier::config::B128;
 use binius_verifier::{
     config::StdChallenger,
-    hash::{StdCompression, StdDigest},
-    merkle_tree::BinaryMerkleTreeScheme,
+    hash::{PseudoCompressionFunction, StdCompression, StdDigest},
+    merkle_tree::{BinaryMerkleTreeScheme, MerkleTreeScheme},
 };
 
 pub type FriVailDefault = crate::frivail::FriVail<
@@ -29,40 +30,72 @@ pub type MerkleProver<P> = BinaryMerkleTreeProver<
     ParallelCompressionAdaptor<StdCompression>,
 >;
 
+/// Merkle tree hash used by a `FriVail` instance
+///
+/// Digests are 32 bytes, so the roots of every hash convert to `CommitmentRoot`.
+/// `Scheme` and `Prover` must hash leaves with `Digest` and merge siblings
+/// with `Compression`.
+pub trait MerkleHash {
+    /// Digest of a Merkle leaf
+    type Digest: digest::Digest + digest::OutputSizeUser<OutputSize = digest::consts::U32>;
+    /// Compression of two sibling digests into their parent
+    type Compression: PseudoCompressionFunction<digest::Output<Self::Digest>, 2> + Default;
+    /// Scheme verifying openings against a root
+    type Scheme: MerkleTreeScheme<B128, Digest = digest::Output<Self::Digest>> + Clone + Send + Sync;
+    /// Prover committing codewords to a Merkle tree
+    type Prover: MerkleTreeProver<B128, Scheme = Self::Scheme, Committed: Send + Sync> + Send + Sync;
+
+    /// Create the Merkle tree prover
+    fn merkle_prover() -> Self::Prover;
+}
+
+/// SHA-256 leaves with the standard compression, the default `MerkleHash`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdMerkleHash;
+
+impl MerkleHash for StdMerkleHash {
+    type Digest = StdDigest;
+    type Compression = StdCompression;
+    type Scheme = BinaryMerkleTreeScheme<B128, StdDigest, StdCompression>;
+    type Prover = MerkleProver<B128>;
+
+    fn merkle_prover() -> Self::Prover {
+        MerkleProver::<B128>::new(ParallelCompressionAdaptor::new(StdCompression::default()))
+    }
+}
+
 pub type FieldElements<P> = Vec<<P as PackedField>::Scalar>;
 
-pub type FieldResult<P> = Result<FieldElements<P>, String>;
+pub type FieldResult<P> = Result<FieldElements<P>, FriVailError>;
 
-pub type TranscriptResult = Result<VerifierTranscript<StdChallenger>, String>;
+pub type TranscriptResult = Result<VerifierTranscript<StdChallenger>, FriVailError>;
 
-pub type ByteResult = Result<Vec<u8>, String>;
+pub type ByteResult = Result<Vec<u8>, FriVailError>;
 
-pub type CommitmentOutput<P> =
-    CommitOutput<
-        P,
-        digest::Output<StdDigest>,
-        <MerkleProver<P> as binius_prover::merkle_tree::MerkleTreeProver<
-            <P as PackedField>::Scalar,
-        >>::Committed,
-    >;
+pub type CommitmentOutput<P, H = StdMerkleHash> = CommitOutput<
+    P,
+    digest::Output<<H as MerkleHash>::Digest>,
+    <<H as MerkleHash>::Prover as MerkleTreeProver<B128>>::Committed,
+>;
 
-pub type FRIQueryProverAlias<'a, P> = FRIQueryProver<
+pub type FRIQueryProverAlias<'a, P, H = StdMerkleHash> = FRIQueryProver<
     'a,
     <P as PackedField>::Scalar,
     P,
-    MerkleProver<P>,
-    BinaryMerkleTreeScheme<<P as PackedField>::Scalar, StdDigest, StdCompression>,
+    <H as MerkleHash>::Prover,
+    <H as MerkleHash>::Scheme,
 >;
 
-pub type ProveResult<'a, P> = Result<
+pub type ProveResult<'a, P, H = StdMerkleHash> = Result<
     (
-        binius_math::FieldBuffer<<P as PackedField>::Scalar>,
-        FRIQueryProverAlias<'a, P>,
+        crate::frivail::TerminalCodeword<<P as PackedField>::Scalar>,
+        FRIQueryProverAlias<'a, P, H>,
         Vec<u8>,
     ),
-    String,
+    FriVailError,
 >;
 
+/// Instance type used by the unit tests, which run with insecure query counts
 pub type TestFriVail = crate::frivail::FriVail<
     'static,
     B128,
@@ -0,0 +1,161 @@
+//! Verification primitives with allocation-free errors
+//!
+//! Each primitive reads from a `VerifierTranscript` and reports failure as a
+//! `VerifyError`, which holds no heap data. Apart from the folding schedule
+//! vector nothing here needs more than `core` and `alloc`, so a future `no_std`
+//! build can reuse these functions unchanged.
+
+use crate::error::VerifyError;
+use binius_transcript::VerifierTranscript;
+use binius_verifier::{
+    config::{StdChallenger, B128},
+    fri::FRIParams,
+    hash::StdDigest,
+    merkle_tree::MerkleTreeScheme,
+};
+
+/// Domain separation tag written into every `open` transcript
+pub(crate) const OPEN_CONTEXT_TAG: &[u8; 15] = b"frivail-open-v1";
+
+/// Upper bound on the number of fold rounds accepted from a proof header
+pub(crate) const MAX_FOLD_ROUNDS: usize = 64;
+
+/// Read a proof header: the codeword commitment and the prover's folding schedule
+///
+/// # Arguments
+/// * `transcript` - Verifier transcript positioned at the proof start
+/// * `evaluation_point_len` - Number of coordinates of the evaluation point
+/// * `fri_params` - FRI protocol parameters; the fold arities are taken from the proof
+///
+/// # Returns
+/// Codeword commitment and FRI parameters with the prover's folding schedule
+///
+/// # Errors
+/// When the evaluation point is too short or the header is malformed
+pub fn read_proof_header(
+    transcript: &mut VerifierTranscript<StdChallenger>,
+    evaluation_point_len: usize,
+    fri_params: &FRIParams<B128>,
+) -> Result<(digest::Output<StdDigest>, FRIParams<B128>), VerifyError> {
+    let n_packed_vars = fri_params.rs_code().log_dim() + fri_params.log_batch_size();
+    if evaluation_point_len < n_packed_vars {
+        return Err(VerifyError::PointTooShort {
+            got: evaluation_point_len,
+            expected: n_packed_vars,
+        });
+    }
+
+    let commitment = transcript
+        .message()
+        .read()
+        .map_err(|_| VerifyError::MalformedHeader)?;
+
+    let mut read_u64 = || -> Result<usize, VerifyError> {
+        let mut bytes = [0u8; 8];
+        transcript
+            .message()
+            .read_bytes(&mut bytes)
+            .map_err(|_| VerifyError::MalformedHeader)?;
+        usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| VerifyError::MalformedHeader)
+    };
+    let num_rounds = read_u64()?;
+    if num_rounds > MAX_FOLD_ROUNDS {
+        return Err(VerifyError::TooManyFoldRounds {
+            rounds: num_rounds,
+            max: MAX_FOLD_ROUNDS,
+        });
+    }
+    let fold_arities = (0..num_rounds)
+        .map(|_| read_u64())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let proof_params = FRIParams::new(
+        fri_params.rs_code().clone(),
+        fri_params.log_batch_size(),
+        fold_arities,
+        fri_params.n_test_queries(),
+    )
+    .map_err(|_| VerifyError::InvalidFoldSchedule)?;
+
+    Ok((commitment, proof_params))
+}
+
+/// Check a query proof transcript was opened against `commitment`
+///
+/// The commitment and tag are a label, not a cryptographic binding: anyone can
+/// rewrite them. They catch an opening attached to the wrong proof by mistake;
+/// a forged label is still caught when the decommitment's Merkle paths are
+/// checked against the proof's layers.
+///
+/// # Arguments
+/// * `transcript` - Query proof transcript positioned at its start
+/// * `commitment` - Codeword commitment of the evaluation proof
+///
+/// # Returns
+/// Ok(()) with the transcript positioned at the query decommitment
+///
+/// # Errors
+/// When the binding cannot be read or names another commitment
+pub fn check_query_binding(
+    transcript: &mut VerifierTranscript<StdChallenger>,
+    commitment: &digest::Output<StdDigest>,
+) -> Result<(), VerifyError> {
+    let bound_commitment: digest::Output<StdDigest> = transcript
+        .message()
+        .read()
+        .map_err(|_| VerifyError::MalformedQuery)?;
+    let mut context_tag = [0u8; OPEN_CONTEXT_TAG.len()];
+    transcript
+        .message()
+        .read_bytes(&mut context_tag)
+        .map_err(|_| VerifyError::MalformedQuery)?;
+
+    if &bound_commitment != commitment || &context_tag != OPEN_CONTEXT_TAG {
+        return Err(VerifyError::UnboundQuery);
+    }
+    Ok(())
+}
+
+/// Verify a Merkle opening of one leaf against a root
+///
+/// # Arguments
+/// * `scheme` - Merkle tree scheme the tree was built with
+/// * `transcript` - Transcript containing the opening
+/// * `leaf` - Leaf values
+/// * `index` - Leaf index
+/// * `tree_depth` - Depth of the tree
+/// * `root` - Merkle root
+///
+/// # Returns
+/// Ok(()) if the opening is valid
+///
+/// # Errors
+/// When the depth is too large for the platform, the index is out of range
+/// or the opening does not verify
+pub fn verify_leaf_opening<VCS: MerkleTreeScheme<B128>>(
+    scheme: &VCS,
+    transcript: &mut VerifierTranscript<StdChallenger>,
+    leaf: &[B128],
+    index: usize,
+    tree_depth: usize,
+    root: VCS::Digest,
+) -> Result<(), VerifyError> {
+    let num_leaves = u32::try_from(tree_depth)
+        .ok()
+        .and_then(|depth| 1usize.checked_shl(depth))
+        .ok_or(VerifyError::MalformedHeader)?;
+    if index >= num_leaves {
+        return Err(VerifyError::LeafIndex { index, num_leaves });
+    }
+
+    scheme
+        .verify_opening(
+            index,
+            leaf,
+            0,
+            tree_depth,
+            &[root],
+            &mut transcript.message(),
+        )
+        .map_err(|_| VerifyError::InclusionProof)
+}
@@ -0,0 +1,115 @@
+//! Verifier entry points for `wasm32-unknown-unknown`
+//!
+//! The verifier path needs neither rayon nor OS randomness, so it builds with
+//! `cargo build --target wasm32-unknown-unknown --no-default-features`. This
+//! module is compiled only for wasm32 and instantiates `verify`,
+//! `verify_inclusion_proof` and `decode_codeword` for `FriVailDefault`, so a
+//! wasm build fails as soon as that path picks up an unsupported dependency.
+//!
+//! Without the `std` feature `reconstruct_codeword_resumable` is not built.
+//! `issue_certificate` and unseeded `simulate_erasures` still read the system
+//! clock, which panics on `wasm32-unknown-unknown`; keep them out of browser
+//! builds.
+
+use crate::error::FriVailError;
+use crate::frivail::EncodingLayout;
+use crate::traits::{FriVailSampling, FriVailUtils};
+use crate::types::{FieldResult, FriVailDefault, B128};
+use crate::CommitmentRoot;
+use binius_math::ntt::{domain_context::GenericPreExpanded, NeighborsLastMultiThread};
+use binius_verifier::fri::FRIParams;
+
+/// NTT instance used by `FriVailDefault`
+pub type DefaultNtt = NeighborsLastMultiThread<GenericPreExpanded<B128>>;
+
+/// Verify an evaluation proof from its transcript bytes
+///
+/// # Arguments
+/// * `fri_vail` - Verifier instance
+/// * `transcript_bytes` - Transcript bytes from `prove`
+/// * `evaluation_claim` - Claimed evaluation result
+/// * `evaluation_point` - Point at which polynomial was evaluated
+/// * `fri_params` - FRI protocol parameters
+/// * `ntt` - Number Theoretic Transform instance
+///
+/// # Returns
+/// Ok(()) if the proof verifies
+///
+/// # Errors
+/// When verification fails
+pub fn verify_evaluation(
+    fri_vail: &FriVailDefault,
+    transcript_bytes: Vec<u8>,
+    evaluation_claim: B128,
+    evaluation_point: &[B128],
+    fri_params: &FRIParams<B128>,
+    ntt: &DefaultNtt,
+) -> Result<(), FriVailError> {
+    let mut verifier_transcript = fri_vail.reconstruct_transcript_from_bytes(transcript_bytes);
+    fri_vail.verify(
+        &mut verifier_transcript,
+        evaluation_claim,
+        evaluation_point,
+        fri_params,
+        ntt,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Verify a sampled leaf against a commitment root
+///
+/// # Arguments
+/// * `fri_vail` - Verifier instance
+/// * `proof_bytes` - Inclusion proof transcript bytes
+/// * `leaf` - Leaf values, `2^log_batch_size` scalars
+/// * `index` - Leaf index
+/// * `fri_params` - FRI protocol parameters
+/// * `commitment` - Commitment root the sample must be under
+///
+/// # Returns
+/// Ok(()) if the inclusion proof is valid
+///
+/// # Errors
+/// When the inclusion proof does not verify
+pub fn verify_sample(
+    fri_vail: &FriVailDefault,
+    proof_bytes: Vec<u8>,
+    leaf: &[B128],
+    index: usize,
+    fri_params: &FRIParams<B128>,
+    commitment: CommitmentRoot,
+) -> Result<(), FriVailError> {
+    let mut verifier_transcript = fri_vail.reconstruct_transcript_from_bytes(proof_bytes);
+    fri_vail.verify_inclusion_proof(
+        &mut verifier_transcript,
+        leaf,
+        index,
+        fri_params,
+        commitment,
+    )
+}
+
+/// Decode a complete codeword back to its data
+///
+/// # Arguments
+/// * `fri_vail` - Verifier instance
+/// * `codeword` - Complete or reconstructed codeword
+/// * `fri_params` - FRI protocol parameters
+/// * `ntt` - Number Theoretic Transform instance
+///
+/// # Returns
+/// Decoded data values
+///
+/// # Errors
+/// When decoding fails
+pub fn decode(
+    fri_vail: &FriVailDefault,
+    codeword: &[B128],
+    fri_params: FRIParams<B128>,
+    ntt: &DefaultNtt,
+) -> FieldResult<B128> {
+    fri_vail.decode_codeword(codeword, fri_params, ntt, EncodingLayout::BitReversed)
+}
@@ -1,7 +1,9 @@
 use binius_field::field::FieldOps;
 use binius_transcript::VerifierTranscript;
 use binius_verifier::config::StdChallenger;
-use frivail::{poly::Utils, traits::FriVailSampling, FriVailDefault, B128};
+use frivail::{
+    frivail::EncodingLayout, poly::Utils, traits::FriVailSampling, FriVailDefault, B128,
+};
 use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
 use std::time::Instant;
 use tracing::{debug, error, info, span, warn, Level};
@@ -141,7 +143,12 @@ fn test_integration_main() {
     info!("🔄 Phase 5: Encoding codeword");
     let start = Instant::now();
     let encoded_codeword = friveil
-        .encode_codeword(&packed_mle_values.packed_values, fri_params.clone(), &ntt)
+        .encode_codeword(
+            &packed_mle_values.packed_values,
+            fri_params.clone(),
+            &ntt,
+            EncodingLayout::BitReversed,
+        )
         .unwrap();
 
     let encode_time = start.elapsed().as_millis();
@@ -156,7 +163,12 @@ fn test_integration_main() {
     info!("🔄 Phase 6: Decoding codeword");
     let start = Instant::now();
     let decoded_codeword = friveil
-        .decode_codeword(&encoded_codeword, fri_params.clone(), &ntt)
+        .decode_codeword(
+            &encoded_codeword,
+            fri_params.clone(),
+            &ntt,
+            EncodingLayout::BitReversed,
+        )
         .unwrap();
     let decode_time = start.elapsed().as_millis();
     info!("✅ Codeword decoded in {} ms", decode_time);
@@ -189,7 +201,7 @@ fn test_integration_main() {
         assert_ne!(corrupted_codeword, encoded_codeword);
 
         let _reconstructed_codeword = friveil
-            .reconstruct_codeword_naive(&mut corrupted_codeword, &corrupted_indices_vec)
+            .reconstruct_codeword_naive(&mut corrupted_codeword, &corrupted_indices_vec, None)
             .unwrap();
 
         let reconstruction_time = start.elapsed().as_millis();
@@ -201,9 +213,9 @@ fn test_integration_main() {
         info!("Phase 7: Skipping error correction test for big data size");
     }
 
-    // Phase 8: Data Availability Sampling - COMMENTED OUT DUE TO MERKLE TREE INDEX MISMATCH
-    // The inclusion_proof function fails with index out of bounds because the Merkle tree
-    // has fewer leaves than the codeword has elements. See issue analysis for details.
+    // Phase 8: Data Availability Sampling
+    // With log_batch_size 0 every codeword position is its own Merkle leaf; batched
+    // commitments map positions to leaves with `FriVail::leaf_for`.
     let _span = span!(Level::INFO, "data_availability_sampling").entered();
     info!("🎯 Phase 8: Performing data availability sampling");
     info!(
@@ -349,7 +361,7 @@ fn test_integration_main() {
     let _span = span!(Level::INFO, "proof_generation").entered();
     info!("📝 Phase 9: Generating evaluation proof");
     let start = Instant::now();
-    let (terminate_codeword, query_prover, transcript_bytes) = friveil
+    let (terminate_codeword, query_prover, transcript_bytes, evaluation_claim) = friveil
         .prove(
             packed_mle_values.packed_mle.clone(),
             &fri_params,
@@ -364,33 +376,27 @@ fn test_integration_main() {
 
     drop(_span);
 
-    let _span = span!(Level::INFO, "evaluation_claim").entered();
-
-    info!("🧮 Computing evaluation claim");
-    let start = Instant::now();
-    let evaluation_claim = friveil
-        .calculate_evaluation_claim(&packed_mle_values.packed_values, &evaluation_point)
-        .unwrap();
-    let claim_time = start.elapsed().as_millis();
-    info!("✅ Evaluation claim computed in {} ms", claim_time);
     debug!("   - Evaluation claim value: {:?}", evaluation_claim);
-    drop(_span);
 
     let _span = span!(Level::INFO, "final_verification").entered();
     info!("🔍 Phase 10: Final proof verification with extra query");
 
     // Extract layers from query_prover for extra verification
     let layers = query_prover.vcs_optimal_layers().unwrap();
-    let terminate_codeword_vec: Vec<_> = terminate_codeword.iter_scalars().collect();
 
     // Generate extra query proof using open()
-    let mut extra_transcript = friveil.open(0, &query_prover).unwrap();
+    let mut extra_transcript = friveil
+        .open(0, &commit_output.commitment, &query_prover)
+        .unwrap();
 
     // Extract transcript bytes for network propagation
     info!(
         "📦 Transcript size: {} bytes (ready for network transmission)",
         transcript_bytes.len()
     );
+    let estimated_size = friveil.estimated_fri_proof_size(&fri_params);
+    info!("   - Estimated size: {} bytes", estimated_size);
+    assert!(estimated_size.abs_diff(transcript_bytes.len()) * 10 <= transcript_bytes.len());
 
     // Reconstruct verifier transcript from bytes
     let mut verifier_transcript =
@@ -404,7 +410,7 @@ fn test_integration_main() {
         &fri_params,
         &ntt,
         Some(0),
-        Some(&terminate_codeword_vec),
+        Some(&terminate_codeword),
         Some(&layers),
         Some(&mut extra_transcript),
     );